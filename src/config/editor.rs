@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
@@ -16,6 +17,20 @@ pub struct EditorConfig {
     pub scroll_lines: u16,         // Lines to scroll with mouse wheel
     pub scroll_margin_bottom: u16, // Empty lines at bottom when scrolling
     pub undo_history_limit: usize,
+    pub undo_group_idle_ms: u64, // pause ending a run of typing undone in one step, 0 undoes each char
+    pub persistent_undo: bool,   // keep the undo history of saved files between sessions
+    pub persistent_undo_max_kb: u64, // largest undo file written per file
+    pub modal_editing: bool,     // vim style normal, insert and visual modes
+    pub auto_indent: bool,       // new lines keep the indentation of the line above
+    pub smart_indent: bool,      // and indent further after { ( [ and :
+    pub auto_pairs: Vec<String>, // opening and closing chars typed together, like "()"
+    pub match_brackets: bool,    // highlight the bracket matching the one at the cursor
+    pub clipboard_history_size: usize, // copies and cuts kept to pick from
+    pub clipboard_provider: ClipboardProviderKind, // where copies are shared outside the editor
+    pub clipboard_copy_command: Vec<String>, // program and args copied text is piped to
+    pub clipboard_paste_command: Vec<String>, // program and args printing text to paste
+    #[serde(deserialize_with = "merge_comment_tokens")]
+    pub comment_tokens: HashMap<String, CommentTokens>, // by file extension, or file name
 }
 
 impl Default for EditorConfig {
@@ -34,6 +49,102 @@ impl Default for EditorConfig {
             scroll_lines: 1,
             scroll_margin_bottom: 5,
             undo_history_limit: 1000,
+            undo_group_idle_ms: 1000,
+            persistent_undo: false,
+            persistent_undo_max_kb: 1024,
+            modal_editing: false,
+            auto_indent: true,
+            smart_indent: true,
+            auto_pairs: ["()", "[]", "{}", "\"\""]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            match_brackets: true,
+            clipboard_history_size: 20,
+            clipboard_provider: ClipboardProviderKind::Internal,
+            clipboard_copy_command: Vec::new(),
+            clipboard_paste_command: Vec::new(),
+            comment_tokens: default_comment_tokens(),
         }
     }
 }
+
+/// Where copied text is shared besides the editor's own clipboard
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardProviderKind {
+    /// Nowhere, text stays in the editor
+    #[default]
+    Internal,
+    /// The terminal, through OSC 52 escape sequences
+    Osc52,
+    /// External programs, set by clipboard_copy_command and clipboard_paste_command
+    Command,
+}
+
+/// Comment syntax of a language, a line comment prefix and/or block comment delimiters
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CommentTokens {
+    pub line: Option<String>,
+    pub block: Option<(String, String)>,
+}
+
+/// Reads the configured comment_tokens over the built-in ones, so setting one
+/// language keeps the others
+fn merge_comment_tokens<'de, D>(deserializer: D) -> Result<HashMap<String, CommentTokens>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mut tokens = default_comment_tokens();
+    tokens.extend(HashMap::<String, CommentTokens>::deserialize(deserializer)?);
+    Ok(tokens)
+}
+
+fn default_comment_tokens() -> HashMap<String, CommentTokens> {
+    let line = |token: &str| CommentTokens {
+        line: Some(token.to_string()),
+        block: None,
+    };
+    let block = |open: &str, close: &str| CommentTokens {
+        line: None,
+        block: Some((open.to_string(), close.to_string())),
+    };
+    let line_and_block = |token: &str, open: &str, close: &str| CommentTokens {
+        line: Some(token.to_string()),
+        block: Some((open.to_string(), close.to_string())),
+    };
+
+    let mut tokens = HashMap::new();
+    for ext in [
+        "rs", "c", "h", "cpp", "hpp", "cs", "java", "js", "ts", "go", "kt", "swift",
+    ] {
+        tokens.insert(ext.to_string(), line_and_block("//", "/*", "*/"));
+    }
+    for ext in [
+        "py",
+        "sh",
+        "bash",
+        "zsh",
+        "toml",
+        "yaml",
+        "yml",
+        "rb",
+        "pl",
+        "conf",
+        "Makefile",
+        "Dockerfile",
+    ] {
+        tokens.insert(ext.to_string(), line("#"));
+    }
+    for ext in ["lua", "sql", "hs"] {
+        tokens.insert(ext.to_string(), line("--"));
+    }
+    tokens.insert("ini".to_string(), line(";"));
+    tokens.insert("vim".to_string(), line("\""));
+    tokens.insert("css".to_string(), block("/*", "*/"));
+    for ext in ["html", "xml", "md"] {
+        tokens.insert(ext.to_string(), block("<!--", "-->"));
+    }
+    tokens
+}
@@ -1,5 +1,8 @@
+use crate::core::clipboard::{is_named_register, is_register_name};
 use crate::errors::config_errors::ConfigError;
-use crate::input::actions::{CommandLineAction, DebugAction, EditorAction, InputAction};
+use crate::input::actions::{
+    CommandLineAction, DebugAction, EditorAction, InputAction, Motion, SplitAction,
+};
 use crossterm::event::{KeyCode, KeyModifiers};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -121,40 +124,152 @@ impl KeymapConfig {
             "copy" => Ok(InputAction::Editor(EditorAction::COPY)),
             "paste" => Ok(InputAction::Editor(EditorAction::PASTE)),
             "cut" => Ok(InputAction::Editor(EditorAction::CUT)),
+            "clipboard_history" => Ok(InputAction::Editor(EditorAction::ClipboardHistory)),
             "undo" => Ok(InputAction::Editor(EditorAction::UNDO)),
             "redo" => Ok(InputAction::Editor(EditorAction::REDO)),
+            "older_state" => Ok(InputAction::Editor(EditorAction::OlderState)),
+            "newer_state" => Ok(InputAction::Editor(EditorAction::NewerState)),
+            "undo_tree" => Ok(InputAction::Editor(EditorAction::UndoTree)),
             "backspace" => Ok(InputAction::Editor(EditorAction::BACKSPACE)),
             "delete" => Ok(InputAction::Editor(EditorAction::DELETE)),
             "enter" => Ok(InputAction::ENTER),
             "tab" => Ok(InputAction::TAB),
+            "outdent" => Ok(InputAction::Editor(EditorAction::Outdent)),
             "toggle_area" => Ok(InputAction::ToggleActiveArea),
 
             // Movement
-            "move_up" => Ok(InputAction::Editor(EditorAction::MoveCursor(Direction::Up))),
-            "move_down" => Ok(InputAction::Editor(EditorAction::MoveCursor(
-                Direction::Down,
+            "move_up" => Ok(InputAction::Editor(EditorAction::MoveCursor(Motion::Up))),
+            "move_down" => Ok(InputAction::Editor(EditorAction::MoveCursor(Motion::Down))),
+            "move_left" => Ok(InputAction::Editor(EditorAction::MoveCursor(Motion::Left))),
+            "move_right" => Ok(InputAction::Editor(EditorAction::MoveCursor(Motion::Right))),
+            "move_word_left" => Ok(InputAction::Editor(EditorAction::MoveCursor(
+                Motion::WordLeft,
             ))),
-            "move_left" => Ok(InputAction::Editor(EditorAction::MoveCursor(
-                Direction::Left,
+            "move_word_right" => Ok(InputAction::Editor(EditorAction::MoveCursor(
+                Motion::WordRight,
             ))),
-            "move_right" => Ok(InputAction::Editor(EditorAction::MoveCursor(
-                Direction::Right,
+            "move_line_start" => Ok(InputAction::Editor(EditorAction::MoveCursor(
+                Motion::LineStart,
+            ))),
+            "move_line_end" => Ok(InputAction::Editor(EditorAction::MoveCursor(
+                Motion::LineEnd,
+            ))),
+            "move_document_start" => Ok(InputAction::Editor(EditorAction::MoveCursor(
+                Motion::DocumentStart,
+            ))),
+            "move_document_end" => Ok(InputAction::Editor(EditorAction::MoveCursor(
+                Motion::DocumentEnd,
+            ))),
+            "move_page_up" => Ok(InputAction::Editor(EditorAction::MoveCursor(
+                Motion::PageUp,
+            ))),
+            "move_page_down" => Ok(InputAction::Editor(EditorAction::MoveCursor(
+                Motion::PageDown,
             ))),
 
             // Selection
             "select_up" => Ok(InputAction::Editor(EditorAction::MoveSelectionCursor(
-                Direction::Up,
+                Motion::Up,
             ))),
             "select_down" => Ok(InputAction::Editor(EditorAction::MoveSelectionCursor(
-                Direction::Down,
+                Motion::Down,
             ))),
             "select_left" => Ok(InputAction::Editor(EditorAction::MoveSelectionCursor(
-                Direction::Left,
+                Motion::Left,
             ))),
             "select_right" => Ok(InputAction::Editor(EditorAction::MoveSelectionCursor(
+                Motion::Right,
+            ))),
+            "select_word_left" => Ok(InputAction::Editor(EditorAction::MoveSelectionCursor(
+                Motion::WordLeft,
+            ))),
+            "select_word_right" => Ok(InputAction::Editor(EditorAction::MoveSelectionCursor(
+                Motion::WordRight,
+            ))),
+            "select_line_start" => Ok(InputAction::Editor(EditorAction::MoveSelectionCursor(
+                Motion::LineStart,
+            ))),
+            "select_line_end" => Ok(InputAction::Editor(EditorAction::MoveSelectionCursor(
+                Motion::LineEnd,
+            ))),
+            "select_document_start" => Ok(InputAction::Editor(EditorAction::MoveSelectionCursor(
+                Motion::DocumentStart,
+            ))),
+            "select_document_end" => Ok(InputAction::Editor(EditorAction::MoveSelectionCursor(
+                Motion::DocumentEnd,
+            ))),
+            "select_page_up" => Ok(InputAction::Editor(EditorAction::MoveSelectionCursor(
+                Motion::PageUp,
+            ))),
+            "select_page_down" => Ok(InputAction::Editor(EditorAction::MoveSelectionCursor(
+                Motion::PageDown,
+            ))),
+            "block_select_up" => Ok(InputAction::Editor(EditorAction::MoveBlockSelection(
+                Direction::Up,
+            ))),
+            "block_select_down" => Ok(InputAction::Editor(EditorAction::MoveBlockSelection(
+                Direction::Down,
+            ))),
+            "block_select_left" => Ok(InputAction::Editor(EditorAction::MoveBlockSelection(
+                Direction::Left,
+            ))),
+            "block_select_right" => Ok(InputAction::Editor(EditorAction::MoveBlockSelection(
                 Direction::Right,
             ))),
 
+            // Multiple cursors
+            "add_cursor_above" => Ok(InputAction::Editor(EditorAction::AddCursorAbove)),
+            "add_cursor_below" => Ok(InputAction::Editor(EditorAction::AddCursorBelow)),
+            "add_cursor_next_match" => Ok(InputAction::Editor(EditorAction::AddCursorAtNextMatch)),
+
+            // Brackets
+            "jump_to_matching_bracket" => {
+                Ok(InputAction::Editor(EditorAction::JumpToMatchingBracket))
+            }
+
+            // Whole lines
+            "duplicate_line" => Ok(InputAction::Editor(EditorAction::DuplicateLines)),
+            "move_line_up" => Ok(InputAction::Editor(EditorAction::MoveLinesUp)),
+            "move_line_down" => Ok(InputAction::Editor(EditorAction::MoveLinesDown)),
+            "delete_line" => Ok(InputAction::Editor(EditorAction::DeleteLines)),
+            "join_lines" => Ok(InputAction::Editor(EditorAction::JoinLines)),
+            "toggle_comment" => Ok(InputAction::Editor(EditorAction::ToggleComment)),
+            "goto_line" => Ok(InputAction::Editor(EditorAction::GotoLine)),
+            "jump_back" => Ok(InputAction::Editor(EditorAction::JumpBack)),
+            "jump_forward" => Ok(InputAction::Editor(EditorAction::JumpForward)),
+            // Macros
+            "toggle_macro_recording" => Ok(InputAction::Editor(EditorAction::ToggleMacroRecording)),
+            "play_macro" => Ok(InputAction::Editor(EditorAction::PlayMacro)),
+            // Splits
+            "split_horizontal" => Ok(InputAction::Split(SplitAction::SplitHorizontal)),
+            "split_vertical" => Ok(InputAction::Split(SplitAction::SplitVertical)),
+            "focus_split_up" => Ok(InputAction::Split(SplitAction::Focus(Direction::Up))),
+            "focus_split_down" => Ok(InputAction::Split(SplitAction::Focus(Direction::Down))),
+            "focus_split_left" => Ok(InputAction::Split(SplitAction::Focus(Direction::Left))),
+            "focus_split_right" => Ok(InputAction::Split(SplitAction::Focus(Direction::Right))),
+            "focus_split_next" => Ok(InputAction::Split(SplitAction::FocusNext)),
+            "grow_split" => Ok(InputAction::Split(SplitAction::Grow)),
+            "shrink_split" => Ok(InputAction::Split(SplitAction::Shrink)),
+            "close_split" => Ok(InputAction::Split(SplitAction::Close)),
+
+            // Registers, named in the action as in "paste_from_register:a"
+            s if s.contains(':') => {
+                let (action, name) = s.split_once(':').unwrap_or_default();
+                let mut chars = name.chars();
+                match (action, chars.next(), chars.next()) {
+                    ("copy_to_register", Some(name), None) if is_named_register(name) => {
+                        Ok(InputAction::Editor(EditorAction::CopyToRegister(name)))
+                    }
+                    ("paste_from_register", Some(name), None) if is_register_name(name) => {
+                        Ok(InputAction::Editor(EditorAction::PasteFromRegister(name)))
+                    }
+                    _ => Err(ConfigError::InvalidKeymap(format!(
+                        "Unknown action: {}",
+                        action_str
+                    ))),
+                }
+            }
+
             _ => Err(ConfigError::InvalidKeymap(format!(
                 "Unknown action: {}",
                 action_str
@@ -236,8 +351,12 @@ impl Default for KeymapConfig {
         editor.insert("Ctrl+c".to_string(), "copy".to_string());
         editor.insert("Ctrl+v".to_string(), "paste".to_string());
         editor.insert("Ctrl+x".to_string(), "cut".to_string());
+        editor.insert("Alt+v".to_string(), "clipboard_history".to_string());
         editor.insert("Ctrl+z".to_string(), "undo".to_string());
         editor.insert("Ctrl+y".to_string(), "redo".to_string());
+        editor.insert("Alt+z".to_string(), "older_state".to_string());
+        editor.insert("Alt+y".to_string(), "newer_state".to_string());
+        editor.insert("Alt+u".to_string(), "undo_tree".to_string());
         editor.insert("Backspace".to_string(), "backspace".to_string());
         editor.insert("Delete".to_string(), "delete".to_string());
         editor.insert("Up".to_string(), "move_up".to_string());
@@ -248,9 +367,75 @@ impl Default for KeymapConfig {
         editor.insert("Shift+Down".to_string(), "select_down".to_string());
         editor.insert("Shift+Left".to_string(), "select_left".to_string());
         editor.insert("Shift+Right".to_string(), "select_right".to_string());
+        editor.insert("Ctrl+Left".to_string(), "move_word_left".to_string());
+        editor.insert("Ctrl+Right".to_string(), "move_word_right".to_string());
+        editor.insert("Home".to_string(), "move_line_start".to_string());
+        editor.insert("End".to_string(), "move_line_end".to_string());
+        editor.insert("Ctrl+Home".to_string(), "move_document_start".to_string());
+        editor.insert("Ctrl+End".to_string(), "move_document_end".to_string());
+        editor.insert("PageUp".to_string(), "move_page_up".to_string());
+        editor.insert("PageDown".to_string(), "move_page_down".to_string());
+        editor.insert(
+            "Ctrl+Shift+Left".to_string(),
+            "select_word_left".to_string(),
+        );
+        editor.insert(
+            "Ctrl+Shift+Right".to_string(),
+            "select_word_right".to_string(),
+        );
+        editor.insert("Shift+Home".to_string(), "select_line_start".to_string());
+        editor.insert("Shift+End".to_string(), "select_line_end".to_string());
+        editor.insert(
+            "Ctrl+Shift+Home".to_string(),
+            "select_document_start".to_string(),
+        );
+        editor.insert(
+            "Ctrl+Shift+End".to_string(),
+            "select_document_end".to_string(),
+        );
+        editor.insert("Shift+PageUp".to_string(), "select_page_up".to_string());
+        editor.insert("Shift+PageDown".to_string(), "select_page_down".to_string());
+        editor.insert("Alt+Shift+Up".to_string(), "block_select_up".to_string());
+        editor.insert(
+            "Alt+Shift+Down".to_string(),
+            "block_select_down".to_string(),
+        );
+        editor.insert(
+            "Alt+Shift+Left".to_string(),
+            "block_select_left".to_string(),
+        );
+        editor.insert(
+            "Alt+Shift+Right".to_string(),
+            "block_select_right".to_string(),
+        );
         editor.insert("Esc".to_string(), "toggle_area".to_string());
         editor.insert("Enter".to_string(), "enter".to_string());
         editor.insert("Tab".to_string(), "tab".to_string());
+        editor.insert("Shift+BackTab".to_string(), "outdent".to_string());
+        editor.insert("Ctrl+Alt+Up".to_string(), "add_cursor_above".to_string());
+        editor.insert("Ctrl+Alt+Down".to_string(), "add_cursor_below".to_string());
+        editor.insert("Ctrl+d".to_string(), "add_cursor_next_match".to_string());
+        editor.insert("Ctrl+b".to_string(), "jump_to_matching_bracket".to_string());
+        editor.insert("Ctrl+l".to_string(), "duplicate_line".to_string());
+        editor.insert("Ctrl+Shift+Up".to_string(), "move_line_up".to_string());
+        editor.insert("Ctrl+Shift+Down".to_string(), "move_line_down".to_string());
+        editor.insert("Ctrl+k".to_string(), "delete_line".to_string());
+        editor.insert("Ctrl+j".to_string(), "join_lines".to_string());
+        editor.insert("Ctrl+/".to_string(), "toggle_comment".to_string());
+        // terminals without keyboard enhancements report Ctrl+/ as Ctrl+7
+        editor.insert("Ctrl+7".to_string(), "toggle_comment".to_string());
+        editor.insert("Ctrl+g".to_string(), "goto_line".to_string());
+        editor.insert("Ctrl+o".to_string(), "jump_back".to_string());
+        editor.insert("Ctrl+p".to_string(), "jump_forward".to_string());
+        editor.insert("Ctrl+r".to_string(), "toggle_macro_recording".to_string());
+        editor.insert("Ctrl+e".to_string(), "play_macro".to_string());
+        editor.insert("Alt+Up".to_string(), "focus_split_up".to_string());
+        editor.insert("Alt+Down".to_string(), "focus_split_down".to_string());
+        editor.insert("Alt+Left".to_string(), "focus_split_left".to_string());
+        editor.insert("Alt+Right".to_string(), "focus_split_right".to_string());
+        editor.insert("Ctrl+Alt+Right".to_string(), "grow_split".to_string());
+        editor.insert("Ctrl+Alt+Left".to_string(), "shrink_split".to_string());
+        editor.insert("Ctrl+w".to_string(), "close_split".to_string());
 
         let mut command_line = HashMap::new();
         command_line.insert("Enter".to_string(), "enter".to_string());
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -12,8 +13,9 @@ mod ui;
 
 use crate::config::keymaps::RuntimeKeymaps;
 use crate::config::plugins::PluginsConfig;
+use crate::core::keyboard_macros;
 use crate::errors::config_errors::ConfigError;
-pub use editor::EditorConfig;
+pub use editor::{ClipboardProviderKind, CommentTokens, EditorConfig};
 pub use keymaps::KeymapConfig;
 pub use performance::PerformanceConfig;
 pub use ui::UIConfig;
@@ -36,6 +38,8 @@ pub struct Config {
     pub ui: UIConfig,
     pub performance: PerformanceConfig,
     pub plugins: PluginsConfig,
+    /// Keyboard macros saved with :savemacro, register name to its steps
+    pub macros: HashMap<String, Vec<String>>,
 
     // Runtime keymaps (not serialized)
     #[serde(skip)]
@@ -196,6 +200,9 @@ impl Config {
         // Validate keymaps
         Self::validate_keymaps(&temp_config.keymaps, &mut result);
 
+        // Validate saved macros
+        Self::validate_macros(&temp_config.macros, &mut result);
+
         // Final check, if no errors were encountered, mark as valid.
         if result.errors.is_empty() {
             result.valid = true;
@@ -220,6 +227,26 @@ impl Config {
             ));
         }
 
+        // Validate auto pairs, each is an opening and a closing char
+        for pair in &config.auto_pairs {
+            if pair.chars().count() != 2 {
+                result.errors.push(format!(
+                    "editor.auto_pairs entry '{}' must be exactly two characters, like \"()\".",
+                    pair
+                ));
+            }
+        }
+
+        // Validate clipboard provider, commands need something to copy with
+        if config.clipboard_provider == ClipboardProviderKind::Command
+            && config.clipboard_copy_command.is_empty()
+        {
+            result.warnings.push(
+                "editor.clipboard_provider is \"command\" but clipboard_copy_command is empty, the internal clipboard is used."
+                    .to_string(),
+            );
+        }
+
         // Validate auto_save_delay
         if config.auto_save && config.auto_save_delay_ms < 100 {
             result.warnings.push(
@@ -246,6 +273,11 @@ impl Config {
                 config.undo_history_limit
             ));
         }
+        if config.persistent_undo && config.persistent_undo_max_kb == 0 {
+            result.warnings.push(
+                "editor.persistent_undo_max_kb is 0. No undo history will be kept.".to_string(),
+            );
+        }
     }
 
     fn validate_ui_config(config: &UIConfig, result: &mut ValidationResult) {
@@ -283,6 +315,21 @@ impl Config {
         }
     }
 
+    fn validate_macros(macros: &HashMap<String, Vec<String>>, result: &mut ValidationResult) {
+        for (name, steps) in macros {
+            if keyboard_macros::register_name(name).is_none() {
+                result.warnings.push(format!(
+                    "macros.{} is not a register name, use a single letter or digit. It is ignored.",
+                    name
+                ));
+            } else if let Err(e) = keyboard_macros::actions_from_steps(steps) {
+                result
+                    .warnings
+                    .push(format!("macros.{} is ignored: {}", name, e));
+            }
+        }
+    }
+
     fn validate_keymaps(keymaps: &KeymapConfig, result: &mut ValidationResult) {
         // Try to build runtime keymaps
         match keymaps.build_runtime_maps() {
@@ -376,6 +423,7 @@ impl Default for Config {
             ui: UIConfig::default(),
             performance: PerformanceConfig::default(),
             plugins: PluginsConfig::default(),
+            macros: HashMap::new(),
             runtime_keymaps,
         }
     }
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 pub struct UIConfig {
     pub theme: String,
     pub show_status_bar: bool,
-    pub show_tab_bar: bool, //TODO later feature add tabs to the editor
+    pub show_tab_bar: bool, // tab bar listing open buffers
     pub cursor_style: CursorStyle,
     pub cursor_blink: bool,
 }
@@ -1,26 +1,31 @@
+use super::buffers::BufferList;
 use super::command_line::{command, command_executor, CommandLine};
-use super::editor::Editor;
+use super::editor::{Editor, TextBuffer};
+use super::splits::SplitLayout;
 use crate::app_config::AppLaunchConfig;
 use crate::config::Config;
 use crate::core::debug::DebugState;
 use crate::core::help_registry::HelpRegistry;
+use crate::core::keyboard_macros::{KeyboardMacros, DEFAULT_MACRO_REGISTER};
 use crate::errors::error::AppError;
 use crate::errors::error::AppError::EditorFailure;
 use crate::errors::plugin_error::PluginError;
-use crate::input::actions::InputAction;
+use crate::input::actions::{CommandLineAction, EditorAction, InputAction};
 use crate::input::input::handle_input;
 use crate::plugins::plugin_registry::{Plugin, PluginManager};
 use crate::plugins::search_replace_plugin::SearchReplacePlugin;
 use crate::ui::debug::DebugView;
 use crate::ui::layout::UILayout;
 use crate::ui::popups::error_popup::ErrorPopup;
+use crate::ui::popups::input_popup::InputPopup;
 use crate::ui::popups::popup::{Popup, PopupResult, PopupType};
+use crate::ui::popups::select_popup::SelectPopup;
 use crate::ui::ui::ui;
 use color_eyre::Result;
 use crossterm::event;
 use ratatui::layout::Rect;
 use ratatui::{DefaultTerminal, Frame};
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::fs;
 use std::fs::{File, OpenOptions};
 use std::io::{BufReader, BufWriter, Read, Write};
@@ -34,9 +39,11 @@ pub struct App {
     pub config: Config,
     pub active_area: ActiveArea,
     pub editor: Editor,
+    pub buffers: BufferList, // open buffers, the active one lives in editor and file_path
+    pub splits: SplitLayout, // split views, the focused one lives in editor
     pub command_line: CommandLine,
     pub(crate) cursor_visible: bool,
-    pub(crate) terminal_height: i16,
+    pub(crate) terminal_height: i32,
     pub file_path: Option<PathBuf>,
     pub popup: Option<Box<dyn Popup>>,
     pub popup_result: PopupResult,
@@ -47,6 +54,7 @@ pub struct App {
     pub plugins: PluginManager,
     pub layout: UILayout,
     pub help_registry: Arc<HelpRegistry>,
+    pub macros: KeyboardMacros,
 }
 
 pub type OpCallback = Box<dyn FnOnce(&mut App)>;
@@ -57,6 +65,10 @@ pub enum PendingState {
     Quitting,         //quitting non absolute, requires confirm
     QuittingAbsolute, // quitting absolute, forced no confirm needed
     ConfigEdit { on_confirm: OpCallback },
+    Goto,                 // waiting for a line typed in the goto prompt
+    ClipboardHistory,     // waiting for an entry picked from the clipboard history
+    UndoTree(Vec<usize>), // waiting for a state picked from the undo tree, ids by row
+    TimeTravel(usize),    // waiting for the diff to state id of :earlier or :later to be accepted
 }
 
 #[derive(PartialEq, Debug, Default, Copy, Clone)]
@@ -77,6 +89,8 @@ impl Default for App {
             config,
             active_area: Default::default(),
             editor: Editor::new(temp_config),
+            buffers: BufferList::new(),
+            splits: SplitLayout::new(),
             command_line: CommandLine::new(),
             cursor_visible: true,
             terminal_height: 0,
@@ -95,6 +109,7 @@ impl Default for App {
                     HelpRegistry::empty()
                 }),
             ),
+            macros: KeyboardMacros::default(),
         };
 
         // Load default plugins
@@ -108,11 +123,14 @@ impl App {
     /// Construct a new instance of [`App`].
     pub fn new(config: Config, launch_config: AppLaunchConfig) -> Self {
         let editor_config_arc = Arc::new(config.editor.clone());
+        let macros = KeyboardMacros::from_config(&config.macros);
         let mut app = App {
             running: Default::default(),
             config,
             active_area: Default::default(),
             editor: Editor::new(editor_config_arc),
+            buffers: BufferList::new(),
+            splits: SplitLayout::new(),
             command_line: CommandLine::new(),
             cursor_visible: true,
             terminal_height: 0,
@@ -131,6 +149,7 @@ impl App {
                     HelpRegistry::empty()
                 }),
             ),
+            macros,
         };
 
         // Load default plugins
@@ -143,6 +162,7 @@ impl App {
     pub fn update_layout(
         &mut self,
         status_bar_area: Option<Rect>,
+        tab_bar_area: Option<Rect>,
         editor_area: Rect,
         line_number_area: Option<Rect>,
         content_area: Rect,
@@ -150,6 +170,7 @@ impl App {
     ) {
         self.layout = UILayout {
             status_bar_area,
+            tab_bar_area,
             editor_area,
             line_number_area,
             content_area,
@@ -318,7 +339,10 @@ impl App {
                     let mut buff_read_file = BufReader::new(f);
                     let mut contents = String::new();
                     match buff_read_file.read_to_string(&mut contents) {
-                        Ok(_size) => contents.lines().map(String::from).collect(),
+                        Ok(_size) => {
+                            self.editor.set_file_format_from(&contents);
+                            TextBuffer::from_text(&contents)
+                        }
                         Err(err) => {
                             //if file not found create new
                             self.running = false;
@@ -330,7 +354,7 @@ impl App {
                     match File::create(path) {
                         //create file, if ok then return else quit and panic
                         Ok(_) => {
-                            vec![String::new()] // Return an empty string as the content
+                            TextBuffer::from(vec![String::new()]) // Return an empty string as the content
                         }
                         Err(create_err) => {
                             self.running = false;
@@ -340,8 +364,11 @@ impl App {
                 }
             }
         } else {
-            vec![String::new()] // Start with an empty editor if no file is provided
+            TextBuffer::from(vec![String::new()]) // Start with an empty editor if no file is provided
         };
+        if let Some(path) = &self.file_path {
+            self.editor.restore_undo_history(path);
+        }
     }
 
     ///function to process input action, responsible for calling the related active area,
@@ -353,6 +380,9 @@ impl App {
             self.debug_state.metrics.record_event();
             log_info!("Action: {:?}", action);
         }
+        if self.active_area == ActiveArea::Editor {
+            self.macros.record(&action);
+        }
         self.check_for_app_related_input_actions(action.clone());
         match self.active_area {
             ActiveArea::Editor => {
@@ -362,7 +392,7 @@ impl App {
                 }
 
                 // else is successful, so set content modified true
-                self.content_modified = self.editor.undo_redo_manager.is_dirty();
+                self.content_modified = self.editor.is_modified();
             }
             ActiveArea::CommandLine => {
                 //check for ENTER on commandline, to execute commands,
@@ -383,6 +413,8 @@ impl App {
                         PopupType::Confirmation => self.handle_confirmation_popup_response(),
                         PopupType::Error => self.handle_error_popup_response(),
                         PopupType::Info => self.handle_info_popup_response(),
+                        PopupType::Input => self.handle_input_popup_response(),
+                        PopupType::Select => self.handle_select_popup_response(),
                         _ => {}
                     }
                 }
@@ -400,10 +432,74 @@ impl App {
         match action {
             //check for active area toggling,
             //because toggle active area should be handled by the app centrally.
+            //Esc in the editor drops secondary cursors first
+            InputAction::ToggleActiveArea
+                if self.active_area == ActiveArea::Editor
+                    && self.editor.has_secondary_cursors() =>
+            {
+                self.editor.clear_secondary_cursors()
+            }
+            //Esc in insert or visual mode returns to normal mode
+            InputAction::ToggleActiveArea
+                if self.active_area == ActiveArea::Editor
+                    && self.editor.escape_returns_to_normal_mode() =>
+            {
+                self.editor.enter_normal_mode()
+            }
             InputAction::ToggleActiveArea => self.toggle_active_area(),
+            //in normal mode, : starts a command like in vim
+            InputAction::Editor(EditorAction::WriteChar(':'))
+                if self.active_area == ActiveArea::Editor
+                    && self.editor.colon_opens_command_line() =>
+            {
+                self.toggle_active_area();
+                self.command_line
+                    .handle_input_action(InputAction::CommandLine(CommandLineAction::WriteChar(
+                        ':',
+                    )));
+            }
             //check for quitting,
             //because quitting should be handled by the app centrally
             InputAction::QUIT => self.quit(),
+            //split views span buffers, so the app handles them
+            InputAction::Split(split_action) => self.handle_split_action(split_action),
+            //comment syntax depends on the file type, which only the app knows
+            InputAction::Editor(EditorAction::ToggleComment)
+                if self.active_area == ActiveArea::Editor =>
+            {
+                self.toggle_comment()
+            }
+            InputAction::Editor(EditorAction::GotoLine)
+                if self.active_area == ActiveArea::Editor =>
+            {
+                self.open_goto_prompt()
+            }
+            InputAction::Editor(EditorAction::ClipboardHistory)
+                if self.active_area == ActiveArea::Editor =>
+            {
+                self.open_clipboard_history()
+            }
+            InputAction::Editor(EditorAction::UndoTree)
+                if self.active_area == ActiveArea::Editor =>
+            {
+                self.open_undo_tree()
+            }
+            //macros replay through the app, so actions handled here replay too
+            InputAction::Editor(EditorAction::ToggleMacroRecording)
+                if self.active_area == ActiveArea::Editor =>
+            {
+                self.toggle_macro_recording()
+            }
+            InputAction::Editor(EditorAction::PlayMacro)
+                if self.active_area == ActiveArea::Editor =>
+            {
+                match self.macros.last_used() {
+                    Some(register) => {
+                        self.play_macro(register, 1);
+                    }
+                    None => log_info!("[MACROS] No macro recorded to play"),
+                }
+            }
             InputAction::NoOp => {}
             _ => {}
         }
@@ -416,16 +512,7 @@ impl App {
         //if successful parse to command and use the executor to execute commands
         //open popup for error if execution unsuccessful
         match self.command_line.split_command_bind_and_args() {
-            Ok((bind, args)) => {
-                let command = command::parse_command(bind, args);
-                if let Err(e) = command_executor::execute_command(self, command) {
-                    let popup = Box::new(ErrorPopup::new(
-                        "Command Failed",
-                        AppError::CommandFailure(e),
-                    ));
-                    self.open_popup(popup);
-                }
-            }
+            Ok((bind, args)) => self.run_command(command::parse_command(bind, args)),
             Err(error) => {
                 let popup = Box::new(ErrorPopup::new(
                     "Command Parse Failed",
@@ -436,6 +523,131 @@ impl App {
         }
     }
 
+    ///executes command, opening a popup with the error if it fails
+    fn run_command(&mut self, command: command::Command) {
+        let path = self.file_path.clone();
+        let revision = self.editor.undo_redo_manager.revision();
+        let before = self.editor.cursor_state();
+        if let Err(e) = command_executor::execute_command(self, command) {
+            let popup = Box::new(ErrorPopup::new(
+                "Command Failed",
+                AppError::CommandFailure(e),
+            ));
+            self.open_popup(popup);
+        }
+        // commands switching buffers leave another editor behind
+        if self.file_path == path {
+            self.editor.stamp_cursor_states(revision, before);
+        }
+    }
+
+    ///opens prompt asking for a line to go to, answered in handle_input_popup_response
+    pub fn open_goto_prompt(&mut self) {
+        self.pending_states.push_back(PendingState::Goto);
+        let popup = Box::new(InputPopup::new("Go to Line", "line[:column], +N or -N"));
+        self.open_popup(popup);
+    }
+
+    ///opens list of the clipboard history to paste an entry from, answered in
+    /// handle_select_popup_response
+    pub fn open_clipboard_history(&mut self) {
+        let items: Vec<String> = self
+            .editor
+            .clipboard
+            .history()
+            .map(|entry| entry.preview(60))
+            .collect();
+        if items.is_empty() {
+            log_info!("[CLIPBOARD] Nothing copied yet");
+            return;
+        }
+        self.pending_states
+            .push_back(PendingState::ClipboardHistory);
+        let popup = Box::new(SelectPopup::new("Clipboard History", items));
+        self.open_popup(popup);
+    }
+
+    ///opens the undo tree to pick a state to go to, answered in
+    /// handle_select_popup_response
+    pub fn open_undo_tree(&mut self) {
+        let rows = self.editor.undo_redo_manager.tree_rows();
+        let current = rows.iter().position(|row| row.current).unwrap_or(0);
+        let items: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                format!(
+                    "{}{} {:>4}  {}{}",
+                    "│ ".repeat(row.depth),
+                    if row.current { "●" } else { "○" },
+                    row.id,
+                    row.summary,
+                    if row.saved { "  (saved)" } else { "" }
+                )
+            })
+            .collect();
+        self.pending_states.push_back(PendingState::UndoTree(
+            rows.iter().map(|row| row.id).collect(),
+        ));
+        let popup = SelectPopup::new("Undo Tree", items)
+            .with_selected(current)
+            .without_numbers();
+        self.open_popup(Box::new(popup));
+    }
+
+    ///toggles comments on the selected lines, with the comment tokens configured
+    /// for the extension, or name, of the open file
+    pub fn toggle_comment(&mut self) {
+        let tokens = self.file_path.as_deref().and_then(|path| {
+            let key = path.extension().or(path.file_name())?.to_str()?;
+            self.config.editor.comment_tokens.get(key).cloned()
+        });
+        match tokens {
+            Some(tokens) => {
+                self.editor.clear_block_selection();
+                self.editor.clear_secondary_cursors();
+                self.editor.toggle_comment(&tokens);
+            }
+            None => log_info!("No comment tokens configured for {:?}", self.file_path),
+        }
+    }
+
+    //MACROS
+    ///stops the macro being recorded, or starts recording into the default register
+    pub fn toggle_macro_recording(&mut self) {
+        match self.macros.stop_recording() {
+            Some(register) => log_info!("[MACROS] Recorded macro '{}'", register),
+            None => self.macros.start_recording(DEFAULT_MACRO_REGISTER),
+        }
+    }
+
+    ///replays the macro in register count times, its edits are undone as one step.
+    /// Returns false if the register holds no macro
+    pub fn play_macro(&mut self, register: char, count: usize) -> bool {
+        let Some(actions) = self.macros.get(register).map(<[InputAction]>::to_vec) else {
+            return false;
+        };
+        let area = self.active_area;
+        self.active_area = ActiveArea::Editor;
+        self.macros.set_replaying(register, true);
+        self.editor.undo_redo_manager.begin_group();
+        'replay: for _ in 0..count {
+            for action in &actions {
+                //an error popup, or an action leaving the editor, ends the replay
+                if self.active_area != ActiveArea::Editor {
+                    break 'replay;
+                }
+                self.process_input_action(action.clone());
+            }
+        }
+        self.editor.undo_redo_manager.end_group();
+        self.macros.set_replaying(register, false);
+        self.content_modified = self.editor.is_modified();
+        if self.active_area == ActiveArea::Editor {
+            self.active_area = area;
+        }
+        true
+    }
+
     //PANEL HANDLING
     ///toggles the active area of the app, between editor and command line
     pub(crate) fn toggle_active_area(&mut self) {
@@ -496,6 +708,12 @@ impl App {
                     self.close_popup();
                 }
                 PendingState::Quitting => self.quit(),
+                PendingState::TimeTravel(id) => {
+                    if let Err(e) = self.editor.goto_undo_state(id) {
+                        log_warn!("[UNDO] {}", e);
+                    }
+                    self.content_modified = self.editor.is_modified();
+                }
                 _ => {}
             }
         }
@@ -521,6 +739,67 @@ impl App {
         }
     }
 
+    ///handles response from input popup, passing the typed text to the
+    /// pending state waiting for it, or dropping that state when cancelled
+    pub fn handle_input_popup_response(&mut self) {
+        let input = match std::mem::replace(&mut self.popup_result, PopupResult::None) {
+            PopupResult::String(input) => Some(input),
+            PopupResult::Affirmed => None,
+            _ => return,
+        };
+        self.close_popup();
+        if !matches!(self.pending_states.front(), Some(PendingState::Goto)) {
+            return;
+        }
+        self.pending_states.pop_front();
+        if let Some(input) = input {
+            self.run_command(command::Command::Goto {
+                args: vec![input],
+                flags: HashSet::new(),
+            });
+        }
+    }
+
+    ///handles response from select popup, pasting the clipboard history entry
+    /// or going to the undo tree state picked, or dropping the pending state
+    /// when cancelled
+    pub fn handle_select_popup_response(&mut self) {
+        let picked = match std::mem::replace(&mut self.popup_result, PopupResult::None) {
+            PopupResult::Selected(idx) => Some(idx),
+            PopupResult::Affirmed => None,
+            _ => return,
+        };
+        self.close_popup();
+        if !matches!(
+            self.pending_states.front(),
+            Some(PendingState::ClipboardHistory | PendingState::UndoTree(_))
+        ) {
+            return;
+        }
+        let state = self.pending_states.pop_front();
+        let Some(idx) = picked else {
+            return;
+        };
+        let (title, result) = match state {
+            Some(PendingState::UndoTree(ids)) => match ids.get(idx) {
+                Some(id) => ("Undo Failed", self.editor.goto_undo_state(*id)),
+                None => return,
+            },
+            _ => {
+                self.editor.clear_block_selection();
+                self.editor.clear_secondary_cursors();
+                ("Paste Failed", self.editor.paste_from_history(idx))
+            }
+        };
+        match result {
+            Ok(()) => self.content_modified = self.editor.is_modified(),
+            Err(e) => {
+                let popup = Box::new(ErrorPopup::new(title, EditorFailure(e)));
+                self.open_popup(popup);
+            }
+        }
+    }
+
     ///handles setting popup with defined popup object
     pub fn open_popup(&mut self, popup: Box<dyn Popup>) {
         self.popup = Some(popup);
@@ -541,7 +820,7 @@ impl App {
 
     ///saves contents to file at path
     pub fn save_to_path(&mut self, path: &Path) -> Result<(), AppError> {
-        let new_content = self.editor.editor_content.join("\n");
+        let new_content = self.editor.file_text();
 
         let path_ref = Path::new(&path);
         if let Some(parent) = path_ref.parent() {
@@ -559,8 +838,12 @@ impl App {
 
         self.file_path = Some(path.to_path_buf()); // optionally update file_path
 
-        // mark saved index on undo tree
-        self.editor.undo_redo_manager.mark_saved();
+        // mark saved index on undo tree, and the format as written
+        self.editor.mark_saved();
+        self.content_modified = false;
+        if let Err(e) = self.editor.store_undo_history(path) {
+            log_warn!("[UNDO] Failed to write undo history: {}", e);
+        }
         Ok(())
     }
 
@@ -0,0 +1,131 @@
+use super::buffer_list::{buffer_display_name, same_file, Buffer, BufferSummary};
+use crate::core::app::App;
+use crate::core::editor::{Editor, TextBuffer, TextStorage};
+use crate::errors::error::AppError;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+impl App {
+    /// Opens file in a buffer, switching to it if it already is open.
+    /// Files that do not exist yet give an empty buffer, created on save.
+    pub fn open_buffer(&mut self, path: PathBuf) -> Result<(), AppError> {
+        if let Some(current) = &self.file_path {
+            if same_file(current, &path) {
+                return Ok(());
+            }
+        }
+        if let Some(idx) = self.buffers.position_of_path(&path) {
+            self.switch_to_buffer(idx);
+            return Ok(());
+        }
+
+        let mut editor = Editor::new(Arc::new(self.config.editor.clone()));
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            editor.editor_content = TextBuffer::from_text(&content);
+            editor.set_file_format_from(&content);
+            editor.restore_undo_history(&path);
+        }
+        if editor.editor_content.is_empty() {
+            editor.editor_content.push_line(String::new());
+        }
+
+        log_info!("Opened buffer: {}", path.display());
+        let idx = self.buffers.push(Buffer::new(editor, Some(path)));
+        self.switch_to_buffer(idx);
+        Ok(())
+    }
+
+    /// Makes buffer at index the active one, parking the current buffer
+    pub fn switch_to_buffer(&mut self, idx: usize) {
+        let current = self.take_active_buffer();
+        let next = self.buffers.activate(idx, current);
+        self.set_active_buffer(next);
+    }
+
+    /// Switches to next buffer in list, wrapping around
+    pub fn next_buffer(&mut self) {
+        self.switch_to_buffer(self.buffers.next_index());
+    }
+
+    /// Switches to previous buffer in list, wrapping around
+    pub fn prev_buffer(&mut self) {
+        self.switch_to_buffer(self.buffers.prev_index());
+    }
+
+    /// Closes the active buffer, discarding unsaved changes.
+    /// Closing the last buffer leaves an empty untitled one.
+    pub fn close_active_buffer(&mut self) {
+        log_info!(
+            "Closed buffer: {}",
+            buffer_display_name(self.file_path.as_deref())
+        );
+        let removed = self.buffers.active_index();
+        let next = self.buffers.remove_active().unwrap_or_else(|| {
+            let mut editor = Editor::new(Arc::new(self.config.editor.clone()));
+            editor.editor_content.push_line(String::new());
+            Buffer::new(editor, None)
+        });
+        self.splits
+            .buffer_removed(removed, self.buffers.active_index());
+        self.set_active_buffer(next);
+    }
+
+    /// Checks if any open buffer, active or parked, has unsaved changes
+    pub fn has_modified_buffers(&self) -> bool {
+        self.content_modified
+            || self
+                .buffers
+                .iter()
+                .any(|(_, buffer)| buffer.is_some_and(Buffer::is_modified))
+    }
+
+    /// Summary of all open buffers in tab order
+    pub fn buffer_summaries(&self) -> Vec<BufferSummary> {
+        self.buffers
+            .iter()
+            .map(|(index, buffer)| match buffer {
+                Some(buffer) => BufferSummary {
+                    index,
+                    name: buffer_display_name(buffer.file_path.as_deref()),
+                    file_path: buffer.file_path.clone(),
+                    modified: buffer.is_modified(),
+                    active: false,
+                    line_count: buffer.editor.editor_content.len(),
+                },
+                None => BufferSummary {
+                    index,
+                    name: buffer_display_name(self.file_path.as_deref()),
+                    file_path: self.file_path.clone(),
+                    modified: self.content_modified,
+                    active: true,
+                    line_count: self.editor.editor_content.len(),
+                },
+            })
+            .collect()
+    }
+
+    /// Moves active editor and file path out of app, to park it
+    fn take_active_buffer(&mut self) -> Buffer {
+        let mut placeholder = Editor::new(Arc::clone(&self.editor.editor_config));
+        // the clipboard is shared by all buffers, it stays with the app
+        placeholder.clipboard = std::mem::take(&mut self.editor.clipboard);
+        let mut editor = std::mem::replace(&mut self.editor, placeholder);
+        editor.reset_text_selection_cursor();
+        Buffer::new(editor, self.file_path.take())
+    }
+
+    /// Makes buffer the one shown and edited
+    fn set_active_buffer(&mut self, buffer: Buffer) {
+        let height = self.editor.editor_height;
+        let width = self.editor.editor_width;
+        let clipboard = std::mem::take(&mut self.editor.clipboard);
+        self.editor = buffer.editor;
+        self.editor.clipboard = clipboard;
+        // viewport size is only updated on render, carry it over until then
+        self.editor.editor_height = height;
+        self.editor.editor_width = width;
+        self.file_path = buffer.file_path;
+        self.content_modified = self.editor.is_modified();
+    }
+}
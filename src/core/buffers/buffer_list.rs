@@ -0,0 +1,144 @@
+use crate::core::editor::Editor;
+use std::path::{Path, PathBuf};
+
+/// An open buffer, its editor holds content, cursor and undo history
+#[derive(Debug)]
+pub struct Buffer {
+    pub editor: Editor,
+    pub file_path: Option<PathBuf>,
+}
+
+impl Buffer {
+    pub fn new(editor: Editor, file_path: Option<PathBuf>) -> Self {
+        Self { editor, file_path }
+    }
+
+    /// Checks if buffer has changes since last save
+    pub fn is_modified(&self) -> bool {
+        self.editor.is_modified()
+    }
+}
+
+/// Short info on an open buffer, used for tab bar and buffer listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct BufferSummary {
+    pub index: usize,
+    pub name: String,
+    pub file_path: Option<PathBuf>,
+    pub modified: bool,
+    pub active: bool,
+    pub line_count: usize,
+}
+
+/// Ordered list of open buffers.
+///
+/// The active buffer is owned by `App` (`editor` and `file_path`), so its slot
+/// in here is empty until another buffer is activated and it gets parked.
+#[derive(Debug)]
+pub struct BufferList {
+    slots: Vec<Option<Buffer>>,
+    active: usize,
+}
+
+impl BufferList {
+    pub fn new() -> Self {
+        Self {
+            slots: vec![None],
+            active: 0,
+        }
+    }
+
+    /// Number of open buffers, including the active one, never 0
+    pub fn count(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    /// Parked buffer at index, None for the active one
+    pub fn get(&self, idx: usize) -> Option<&Buffer> {
+        self.slots.get(idx).and_then(|slot| slot.as_ref())
+    }
+
+    /// Iterates slots in tab order, active slot yields None
+    pub fn iter(&self) -> impl Iterator<Item = (usize, Option<&Buffer>)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .map(|(i, slot)| (i, slot.as_ref()))
+    }
+
+    /// Adds a buffer at the end of the list, returns its index
+    pub fn push(&mut self, buffer: Buffer) -> usize {
+        self.slots.push(Some(buffer));
+        self.slots.len() - 1
+    }
+
+    /// Parks `current` in the active slot and takes out the buffer at `idx`,
+    /// which becomes the active one. Returns `current` back if idx is not parked.
+    pub fn activate(&mut self, idx: usize, current: Buffer) -> Buffer {
+        if idx == self.active || self.get(idx).is_none() {
+            return current;
+        }
+        self.slots[self.active] = Some(current);
+        self.active = idx;
+        self.slots[idx].take().unwrap()
+    }
+
+    /// Removes the active slot, and takes out the buffer that should become
+    /// active instead, None if it was the last buffer
+    pub fn remove_active(&mut self) -> Option<Buffer> {
+        self.slots.remove(self.active);
+        if self.slots.is_empty() {
+            self.slots.push(None);
+            self.active = 0;
+            return None;
+        }
+        // fall back to the buffer before the closed one, like closing a tab
+        self.active = self.active.saturating_sub(1);
+        self.slots[self.active].take()
+    }
+
+    /// Index of the next buffer, wrapping around
+    pub fn next_index(&self) -> usize {
+        (self.active + 1) % self.slots.len()
+    }
+
+    /// Index of the previous buffer, wrapping around
+    pub fn prev_index(&self) -> usize {
+        (self.active + self.slots.len() - 1) % self.slots.len()
+    }
+
+    /// Index of parked buffer showing given path
+    pub fn position_of_path(&self, path: &Path) -> Option<usize> {
+        self.iter().find_map(|(i, buffer)| {
+            buffer
+                .and_then(|b| b.file_path.as_deref())
+                .filter(|p| same_file(p, path))
+                .map(|_| i)
+        })
+    }
+}
+
+impl Default for BufferList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Name shown for a buffer, file name or untitled
+pub fn buffer_display_name(path: Option<&Path>) -> String {
+    path.and_then(|p| p.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "untitled".to_string())
+}
+
+/// Compares paths, resolving them first when they exist on disk
+pub fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
@@ -0,0 +1,4 @@
+mod app_extensions;
+mod buffer_list;
+
+pub use buffer_list::{buffer_display_name, Buffer, BufferList, BufferSummary};
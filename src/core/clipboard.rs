@@ -1,20 +1,210 @@
-#[derive(Debug, Default, Clone)]
+use crate::core::clipboard_provider::{ClipboardProvider, InternalProvider};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+
+/// Copies remembered when no limit is configured
+const DEFAULT_HISTORY_LIMIT: usize = 20;
+
+/// How copied text was taken, which decides how it is pasted back
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardKind {
+    /// Plain text, pasted at the cursor
+    #[default]
+    Text,
+    /// A column from a block selection, pasted line by line
+    Block,
+    /// Whole lines, pasted as lines of their own
+    Lines,
+}
+
+/// Text copied once, as kept in the history and in registers
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ClipboardEntry {
+    pub text: Vec<String>,
+    pub kind: ClipboardKind,
+}
+
+impl ClipboardEntry {
+    /// Lines joined by ↵ and cut to width chars, for listing the entry
+    pub fn preview(&self, width: usize) -> String {
+        let joined = self.text.join("↵");
+        match joined.char_indices().nth(width) {
+            Some((cut, _)) => format!("{}…", &joined[..cut]),
+            None => joined,
+        }
+    }
+}
+
+/// The text pasted by paste, a history of the latest copies and cuts, newest
+/// first, and named registers holding text until replaced. Copies and cuts
+/// are shared with the system clipboard through the provider
+#[derive(Debug, Clone)]
 pub struct Clipboard {
     pub copied_text: Vec<String>,
+    pub kind: ClipboardKind,
+    history: VecDeque<ClipboardEntry>,
+    history_limit: usize,
+    registers: BTreeMap<char, ClipboardEntry>,
+    provider: Arc<dyn ClipboardProvider>,
+    // text last given to or taken from the provider, anything else it holds
+    // was copied in another program
+    synced_text: Option<String>,
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Clipboard {
     pub fn new() -> Self {
+        Self::with_history_limit(DEFAULT_HISTORY_LIMIT)
+    }
+
+    pub fn with_history_limit(history_limit: usize) -> Self {
         Self {
             copied_text: vec![],
+            kind: ClipboardKind::Text,
+            history: VecDeque::new(),
+            history_limit,
+            registers: BTreeMap::new(),
+            provider: Arc::new(InternalProvider),
+            synced_text: None,
         }
     }
 
+    pub fn with_provider(mut self, provider: Arc<dyn ClipboardProvider>) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    /// Name of the provider copies are shared through
+    pub fn provider_name(&self) -> &'static str {
+        self.provider.name()
+    }
+
     pub fn copy(&mut self, text: &[String]) {
-        self.copied_text = text.to_owned();
+        self.set(text, ClipboardKind::Text);
+    }
+
+    pub fn copy_block(&mut self, rows: &[String]) {
+        self.set(rows, ClipboardKind::Block);
+    }
+
+    pub fn copy_lines(&mut self, lines: &[String]) {
+        self.set(lines, ClipboardKind::Lines);
     }
 
     pub fn paste(&self) -> Vec<String> {
         self.copied_text.clone()
     }
+
+    /// What paste pastes now
+    pub fn current(&self) -> ClipboardEntry {
+        ClipboardEntry {
+            text: self.copied_text.clone(),
+            kind: self.kind,
+        }
+    }
+
+    /// Replaces what paste pastes, without adding it to the history
+    pub fn restore(&mut self, entry: ClipboardEntry) {
+        self.copied_text = entry.text;
+        self.kind = entry.kind;
+    }
+
+    fn set(&mut self, text: &[String], kind: ClipboardKind) {
+        self.copied_text = text.to_owned();
+        self.kind = kind;
+        self.remember(self.current());
+
+        let text = text.join("\n");
+        if let Err(e) = self.provider.set_text(&text) {
+            log_warn!("[CLIPBOARD] {}", e);
+        }
+        self.synced_text = Some(text);
+    }
+
+    /// Takes text copied in another program from the provider, making it
+    /// what paste pastes. Text the editor copied itself is left alone, so
+    /// a pick from the history isn't overridden by the copy before it
+    pub fn sync_from_provider(&mut self) {
+        let text = match self.provider.get_text() {
+            Ok(Some(text)) => text,
+            Ok(None) => return,
+            Err(e) => {
+                log_warn!("[CLIPBOARD] {}", e);
+                return;
+            }
+        };
+        if text.is_empty() || self.synced_text.as_deref() == Some(text.as_str()) {
+            return;
+        }
+        self.copied_text = text
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line).to_string())
+            .collect();
+        self.kind = ClipboardKind::Text;
+        self.remember(self.current());
+        self.synced_text = Some(text);
+    }
+
+    /// Puts entry at the front of the history. Copying the same text again
+    /// moves it up rather than keeping it twice, and the oldest entries are
+    /// dropped past the limit
+    fn remember(&mut self, entry: ClipboardEntry) {
+        if entry.text.iter().all(String::is_empty) {
+            return;
+        }
+        self.history.retain(|old| *old != entry);
+        self.history.push_front(entry);
+        self.history.truncate(self.history_limit);
+    }
+
+    /// Copies and cuts, newest first
+    pub fn history(&self) -> impl Iterator<Item = &ClipboardEntry> {
+        self.history.iter()
+    }
+
+    /// Makes history entry idx, 0 being the newest, the one pasted and moves
+    /// it to the front. Returns false if there is no such entry
+    pub fn use_history_entry(&mut self, idx: usize) -> bool {
+        let Some(entry) = self.history.remove(idx) else {
+            return false;
+        };
+        self.restore(entry.clone());
+        self.history.push_front(entry);
+        true
+    }
+
+    /// Text of register name. Digits read the history, 0 being the newest
+    /// entry, letters are the named registers
+    pub fn register(&self, name: char) -> Option<&ClipboardEntry> {
+        match name.to_digit(10) {
+            Some(idx) => self.history.get(idx as usize),
+            None => self.registers.get(&name),
+        }
+    }
+
+    /// Stores entry in the named register name, a letter
+    pub fn set_register(&mut self, name: char, entry: ClipboardEntry) {
+        self.registers.insert(name, entry);
+    }
+
+    /// Named registers sorted by name
+    pub fn registers(&self) -> impl Iterator<Item = (char, &ClipboardEntry)> {
+        self.registers.iter().map(|(name, entry)| (*name, entry))
+    }
+}
+
+/// Names registers can have, letters for named registers and digits for
+/// entries of the history
+pub fn is_register_name(name: char) -> bool {
+    name.is_ascii_alphanumeric()
+}
+
+/// Registers text can be copied into, the letters
+pub fn is_named_register(name: char) -> bool {
+    name.is_ascii_alphabetic()
 }
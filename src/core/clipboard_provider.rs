@@ -0,0 +1,261 @@
+//! Providers sharing copied text with the clipboard outside the editor.
+//!
+//! The editor keeps its own clipboard either way, a provider is told about
+//! every copy and cut and asked for text copied in other programs when
+//! pasting. Providers that can't read, like OSC 52, leave pasting to the
+//! editor's own clipboard.
+
+use crate::config::{ClipboardProviderKind, EditorConfig};
+use crate::errors::editor_errors::ClipboardError;
+use std::fmt::Debug;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+/// Clipboard outside the editor that copied text is shared with
+pub trait ClipboardProvider: Debug + Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Puts text on the clipboard
+    fn set_text(&self, text: &str) -> Result<(), ClipboardError>;
+
+    /// Text on the clipboard, None when the provider can't read it
+    fn get_text(&self) -> Result<Option<String>, ClipboardError>;
+}
+
+/// Provider for the config, the internal one when the config asks for
+/// commands without giving a copy command
+pub fn from_config(config: &EditorConfig) -> Arc<dyn ClipboardProvider> {
+    match config.clipboard_provider {
+        ClipboardProviderKind::Internal => Arc::new(InternalProvider),
+        ClipboardProviderKind::Osc52 => Arc::new(Osc52Provider),
+        ClipboardProviderKind::Command if config.clipboard_copy_command.is_empty() => {
+            log_warn!("[CLIPBOARD] No clipboard_copy_command set, using the internal clipboard");
+            Arc::new(InternalProvider)
+        }
+        ClipboardProviderKind::Command => Arc::new(CommandProvider {
+            copy: config.clipboard_copy_command.clone(),
+            paste: config.clipboard_paste_command.clone(),
+        }),
+    }
+}
+
+/// Keeps copied text inside the editor
+#[derive(Debug, Default)]
+pub struct InternalProvider;
+
+impl ClipboardProvider for InternalProvider {
+    fn name(&self) -> &'static str {
+        "internal"
+    }
+
+    fn set_text(&self, _text: &str) -> Result<(), ClipboardError> {
+        Ok(())
+    }
+
+    fn get_text(&self) -> Result<Option<String>, ClipboardError> {
+        Ok(None)
+    }
+}
+
+/// Sends copied text to the terminal in an OSC 52 escape sequence, which
+/// the terminal puts on the system clipboard. Works over ssh too, but most
+/// terminals don't answer reads, so pasting uses the editor's clipboard
+#[derive(Debug, Default)]
+pub struct Osc52Provider;
+
+impl ClipboardProvider for Osc52Provider {
+    fn name(&self) -> &'static str {
+        "osc52"
+    }
+
+    fn set_text(&self, text: &str) -> Result<(), ClipboardError> {
+        let mut stdout = std::io::stdout();
+        stdout
+            .write_all(osc52_sequence(text).as_bytes())
+            .and_then(|()| stdout.flush())
+            .map_err(|e| ClipboardError::ProviderFailed(format!("osc52: {}", e)))
+    }
+
+    fn get_text(&self) -> Result<Option<String>, ClipboardError> {
+        Ok(None)
+    }
+}
+
+/// Escape sequence setting the system clipboard to text
+pub fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))
+}
+
+/// Pipes copied text to a program like `xclip -selection clipboard` or
+/// `wl-copy`, and reads text to paste from one like `wl-paste -n`
+#[derive(Debug, Clone)]
+pub struct CommandProvider {
+    pub copy: Vec<String>,
+    pub paste: Vec<String>,
+}
+
+impl ClipboardProvider for CommandProvider {
+    fn name(&self) -> &'static str {
+        "command"
+    }
+
+    fn set_text(&self, text: &str) -> Result<(), ClipboardError> {
+        run(&self.copy, Some(text)).map(|_| ())
+    }
+
+    fn get_text(&self) -> Result<Option<String>, ClipboardError> {
+        if self.paste.is_empty() {
+            return Ok(None);
+        }
+        run(&self.paste, None).map(Some)
+    }
+}
+
+/// Runs command, writing input to its stdin when given, and returns what it
+/// printed. Output is only read without input, since copy programs like
+/// xclip stay around holding the selection
+fn run(command: &[String], input: Option<&str>) -> Result<String, ClipboardError> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| ClipboardError::ProviderFailed("no command configured".to_string()))?;
+    let failed = |e: std::io::Error| ClipboardError::ProviderFailed(format!("{}: {}", program, e));
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(if input.is_some() {
+            Stdio::null()
+        } else {
+            Stdio::piped()
+        })
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(failed)?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input.as_bytes()).map_err(failed)?;
+    }
+
+    let output = child.wait_with_output().map_err(failed)?;
+    if !output.status.success() {
+        return Err(ClipboardError::ProviderFailed(format!(
+            "{} exited with {}",
+            program, output.status
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_clipboard_provider_tests {
+    use super::*;
+    use crate::core::clipboard::Clipboard;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// System clipboard standing in for the one of the desktop
+    #[derive(Debug, Default)]
+    struct FakeProvider(Mutex<String>);
+
+    impl ClipboardProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        fn set_text(&self, text: &str) -> Result<(), ClipboardError> {
+            *self.0.lock().unwrap() = text.to_string();
+            Ok(())
+        }
+
+        fn get_text(&self) -> Result<Option<String>, ClipboardError> {
+            Ok(Some(self.0.lock().unwrap().clone()))
+        }
+    }
+
+    #[test]
+    fn test_base64_and_osc52_sequence() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode("héllo\n".as_bytes()), "aMOpbGxvCg==");
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
+
+    #[test]
+    fn test_command_provider_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("clip").display().to_string();
+        let provider = CommandProvider {
+            copy: vec!["sh".into(), "-c".into(), format!("cat > {}", file)],
+            paste: vec!["cat".into(), file],
+        };
+        provider.set_text("one\ntwo").unwrap();
+        assert_eq!(provider.get_text().unwrap(), Some("one\ntwo".to_string()));
+
+        let missing = CommandProvider {
+            copy: vec!["calliglyph-no-such-program".into()],
+            paste: vec![],
+        };
+        assert!(missing.set_text("x").is_err());
+        assert_eq!(missing.get_text().unwrap(), None);
+    }
+
+    #[test]
+    fn test_clipboard_shares_copies_with_provider() {
+        let provider = Arc::new(FakeProvider::default());
+        let mut clipboard = Clipboard::default().with_provider(provider.clone());
+        clipboard.copy(&["a".to_string(), "b".to_string()]);
+        assert_eq!(*provider.0.lock().unwrap(), "a\nb");
+
+        // a pick from the history is kept over the last copy
+        clipboard.copy(&["c".to_string()]);
+        clipboard.use_history_entry(1);
+        clipboard.sync_from_provider();
+        assert_eq!(clipboard.copied_text, vec!["a", "b"]);
+
+        // text copied elsewhere is pasted and joins the history
+        provider.set_text("x\r\ny").unwrap();
+        clipboard.sync_from_provider();
+        assert_eq!(clipboard.copied_text, vec!["x", "y"]);
+        assert_eq!(clipboard.history().count(), 3);
+    }
+
+    #[test]
+    fn test_command_without_copy_command_falls_back_to_internal() {
+        let config = EditorConfig {
+            clipboard_provider: ClipboardProviderKind::Command,
+            ..EditorConfig::default()
+        };
+        assert_eq!(from_config(&config).name(), "internal");
+    }
+}
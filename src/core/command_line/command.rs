@@ -1,4 +1,5 @@
 use crate::core::command_line::command_binds::command_binds::*;
+use crate::core::editor::transform::CaseConversion;
 use std::collections::HashSet;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Command {
@@ -31,10 +32,142 @@ pub enum Command {
         args: Vec<String>,
         flags: HashSet<CommandFlag>,
     },
+    Set {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
     Plugin {
         name: String,
         args: Vec<String>,
     },
+    //BUFFERS
+    Edit {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    BufferNext {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    BufferPrev {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    BufferList {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    BufferDelete {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    //SPLITS
+    Split {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    VSplit {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Close {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Only {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    //NAVIGATION
+    Goto {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Mark {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Marks {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    DeleteMarks {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    //MACROS
+    Record {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Play {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Macros {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    SaveMacro {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    DeleteMacro {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    //REGISTERS
+    Registers {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Yank {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Put {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    //UNDO
+    Undo {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Redo {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    UndoTree {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Earlier {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Later {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    //TEXT
+    Sort {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Unique {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Reverse {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    ConvertCase {
+        case: CaseConversion,
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
@@ -87,6 +220,83 @@ pub fn parse_command(bind: String, raw_args: Vec<String>) -> Command {
         _ if COMMAND_HELP.contains(&bind.as_str()) => Command::Help { args, flags },
         _ if COMMAND_DEBUG.contains(&bind.as_str()) => Command::Debug { args, flags },
         _ if COMMAND_CONFIG.contains(&bind.as_str()) => Command::Config { args, flags },
+        _ if COMMAND_SET.contains(&bind.as_str()) => Command::Set { args, flags },
+        _ if COMMAND_EDIT.contains(&bind.as_str()) => Command::Edit { args, flags },
+        _ if COMMAND_BUFFER_NEXT.contains(&bind.as_str()) => Command::BufferNext { args, flags },
+        _ if COMMAND_BUFFER_PREV.contains(&bind.as_str()) => Command::BufferPrev { args, flags },
+        _ if COMMAND_BUFFER_LIST.contains(&bind.as_str()) => Command::BufferList { args, flags },
+        _ if COMMAND_BUFFER_DELETE.contains(&bind.as_str()) => {
+            Command::BufferDelete { args, flags }
+        }
+        _ if COMMAND_BUFFER_DELETE_FORCE.contains(&bind.as_str()) => {
+            flags.insert(CommandFlag::Force);
+            Command::BufferDelete { args, flags }
+        }
+        _ if COMMAND_SPLIT.contains(&bind.as_str()) => Command::Split { args, flags },
+        _ if COMMAND_VSPLIT.contains(&bind.as_str()) => Command::VSplit { args, flags },
+        _ if COMMAND_CLOSE.contains(&bind.as_str()) => Command::Close { args, flags },
+        _ if COMMAND_ONLY.contains(&bind.as_str()) => Command::Only { args, flags },
+        _ if COMMAND_GOTO.contains(&bind.as_str()) => Command::Goto { args, flags },
+        // a bare line number or mark, as in :42, :42:7, :+5 or :'a
+        _ if bind.starts_with(|c: char| c.is_ascii_digit() || "+-'`".contains(c)) => {
+            Command::Goto {
+                args: vec![bind],
+                flags,
+            }
+        }
+        _ if COMMAND_MARK.contains(&bind.as_str()) => Command::Mark { args, flags },
+        _ if COMMAND_MARKS.contains(&bind.as_str()) => Command::Marks { args, flags },
+        _ if COMMAND_DELETE_MARKS.contains(&bind.as_str()) => Command::DeleteMarks { args, flags },
+        _ if COMMAND_RECORD.contains(&bind.as_str()) => Command::Record { args, flags },
+        _ if COMMAND_PLAY.contains(&bind.as_str()) => Command::Play { args, flags },
+        // a macro played vim style, as in :@a or :@a 10
+        _ if bind.len() > 1 && bind.starts_with('@') => {
+            let mut play_args = vec![bind[1..].to_string()];
+            play_args.extend(args);
+            Command::Play {
+                args: play_args,
+                flags,
+            }
+        }
+        _ if COMMAND_MACROS.contains(&bind.as_str()) => Command::Macros { args, flags },
+        _ if COMMAND_SAVE_MACRO.contains(&bind.as_str()) => Command::SaveMacro { args, flags },
+        _ if COMMAND_DELETE_MACRO.contains(&bind.as_str()) => Command::DeleteMacro { args, flags },
+        _ if COMMAND_REGISTERS.contains(&bind.as_str()) => Command::Registers { args, flags },
+        _ if COMMAND_YANK.contains(&bind.as_str()) => Command::Yank { args, flags },
+        _ if COMMAND_PUT.contains(&bind.as_str()) => Command::Put { args, flags },
+        _ if COMMAND_UNDO.contains(&bind.as_str()) => Command::Undo { args, flags },
+        _ if COMMAND_REDO.contains(&bind.as_str()) => Command::Redo { args, flags },
+        _ if COMMAND_UNDO_TREE.contains(&bind.as_str()) => Command::UndoTree { args, flags },
+        _ if COMMAND_EARLIER.contains(&bind.as_str()) => Command::Earlier { args, flags },
+        _ if COMMAND_LATER.contains(&bind.as_str()) => Command::Later { args, flags },
+        _ if COMMAND_SORT.contains(&bind.as_str()) => Command::Sort { args, flags },
+        _ if COMMAND_UNIQUE.contains(&bind.as_str()) => Command::Unique { args, flags },
+        _ if COMMAND_REVERSE.contains(&bind.as_str()) => Command::Reverse { args, flags },
+        _ if COMMAND_UPPER_CASE.contains(&bind.as_str()) => Command::ConvertCase {
+            case: CaseConversion::Upper,
+            args,
+            flags,
+        },
+        _ if COMMAND_LOWER_CASE.contains(&bind.as_str()) => Command::ConvertCase {
+            case: CaseConversion::Lower,
+            args,
+            flags,
+        },
+        _ if COMMAND_TITLE_CASE.contains(&bind.as_str()) => Command::ConvertCase {
+            case: CaseConversion::Title,
+            args,
+            flags,
+        },
+        _ if COMMAND_SNAKE_CASE.contains(&bind.as_str()) => Command::ConvertCase {
+            case: CaseConversion::Snake,
+            args,
+            flags,
+        },
+        _ if COMMAND_CAMEL_CASE.contains(&bind.as_str()) => Command::ConvertCase {
+            case: CaseConversion::Camel,
+            args,
+            flags,
+        },
         _ =>
         // Unknown commands are tried as plugins first
         {
@@ -12,4 +12,47 @@ pub mod command_binds {
 
     pub const COMMAND_DEBUG: &[&str] = &["debug", "dbg"];
     pub const COMMAND_CONFIG: &[&str] = &["config", "cfg"];
+    pub const COMMAND_SET: &[&str] = &["set", "se"];
+
+    pub const COMMAND_EDIT: &[&str] = &["e", "edit"];
+    pub const COMMAND_BUFFER_NEXT: &[&str] = &["bn", "bnext"];
+    pub const COMMAND_BUFFER_PREV: &[&str] = &["bp", "bprev", "bprevious"];
+    pub const COMMAND_BUFFER_LIST: &[&str] = &["ls", "buffers"];
+    pub const COMMAND_BUFFER_DELETE: &[&str] = &["bd", "bdelete"];
+    pub const COMMAND_BUFFER_DELETE_FORCE: &[&str] = &["bd!", "bdelete!"];
+
+    pub const COMMAND_SPLIT: &[&str] = &["sp", "split"];
+    pub const COMMAND_VSPLIT: &[&str] = &["vs", "vsplit"];
+    pub const COMMAND_CLOSE: &[&str] = &["clo", "close"];
+    pub const COMMAND_ONLY: &[&str] = &["on", "only"];
+
+    pub const COMMAND_GOTO: &[&str] = &["goto", "go"];
+    pub const COMMAND_MARK: &[&str] = &["mark", "ma"];
+    pub const COMMAND_MARKS: &[&str] = &["marks"];
+    pub const COMMAND_DELETE_MARKS: &[&str] = &["delmarks", "delm"];
+
+    pub const COMMAND_RECORD: &[&str] = &["record", "rec"];
+    pub const COMMAND_PLAY: &[&str] = &["play"];
+    pub const COMMAND_MACROS: &[&str] = &["macros"];
+    pub const COMMAND_SAVE_MACRO: &[&str] = &["savemacro"];
+    pub const COMMAND_DELETE_MACRO: &[&str] = &["delmacro"];
+
+    pub const COMMAND_REGISTERS: &[&str] = &["registers", "reg"];
+    pub const COMMAND_YANK: &[&str] = &["yank", "y"];
+    pub const COMMAND_PUT: &[&str] = &["put", "pu"];
+
+    pub const COMMAND_UNDO: &[&str] = &["undo", "u"];
+    pub const COMMAND_REDO: &[&str] = &["redo", "red"];
+    pub const COMMAND_UNDO_TREE: &[&str] = &["undotree", "ut"];
+    pub const COMMAND_EARLIER: &[&str] = &["earlier", "ea"];
+    pub const COMMAND_LATER: &[&str] = &["later", "lat"];
+
+    pub const COMMAND_SORT: &[&str] = &["sort"];
+    pub const COMMAND_UNIQUE: &[&str] = &["uniq", "unique"];
+    pub const COMMAND_REVERSE: &[&str] = &["rev", "reverse"];
+    pub const COMMAND_UPPER_CASE: &[&str] = &["upper", "uppercase"];
+    pub const COMMAND_LOWER_CASE: &[&str] = &["lower", "lowercase"];
+    pub const COMMAND_TITLE_CASE: &[&str] = &["title", "titlecase"];
+    pub const COMMAND_SNAKE_CASE: &[&str] = &["snake", "snakecase"];
+    pub const COMMAND_CAMEL_CASE: &[&str] = &["camel", "camelcase"];
 }
@@ -29,6 +29,55 @@ pub fn execute_command(app: &mut App, command: Command) -> Result<(), CommandErr
             }
             Ok(())
         }
+        Command::Set { args, flags } => commands::set::set_command(app, args, flags),
+        Command::Edit { args, flags } => commands::buffer::edit_command(app, args, flags),
+        Command::BufferNext { args, flags } => {
+            commands::buffer::buffer_next_command(app, args, flags)
+        }
+        Command::BufferPrev { args, flags } => {
+            commands::buffer::buffer_prev_command(app, args, flags)
+        }
+        Command::BufferList { args, flags } => {
+            commands::buffer::buffer_list_command(app, args, flags)
+        }
+        Command::BufferDelete { args, flags } => {
+            commands::buffer::buffer_delete_command(app, args, flags)
+        }
+        Command::Split { args, flags } => commands::split::split_command(app, args, flags),
+        Command::VSplit { args, flags } => commands::split::vsplit_command(app, args, flags),
+        Command::Close { args, flags } => commands::split::close_command(app, args, flags),
+        Command::Only { args, flags } => commands::split::only_command(app, args, flags),
+        Command::Goto { args, flags } => commands::goto::goto_command(app, args, flags),
+        Command::Mark { args, flags } => commands::marks::mark_command(app, args, flags),
+        Command::Marks { args, flags } => commands::marks::marks_command(app, args, flags),
+        Command::DeleteMarks { args, flags } => {
+            commands::marks::delete_marks_command(app, args, flags)
+        }
+        Command::Record { args, flags } => commands::macros::record_command(app, args, flags),
+        Command::Play { args, flags } => commands::macros::play_command(app, args, flags),
+        Command::Macros { args, flags } => commands::macros::macros_command(app, args, flags),
+        Command::SaveMacro { args, flags } => {
+            commands::macros::save_macro_command(app, args, flags)
+        }
+        Command::DeleteMacro { args, flags } => {
+            commands::macros::delete_macro_command(app, args, flags)
+        }
+        Command::Registers { args, flags } => {
+            commands::registers::registers_command(app, args, flags)
+        }
+        Command::Yank { args, flags } => commands::registers::yank_command(app, args, flags),
+        Command::Put { args, flags } => commands::registers::put_command(app, args, flags),
+        Command::Undo { args, flags } => commands::undo::undo_command(app, args, flags),
+        Command::Redo { args, flags } => commands::undo::redo_command(app, args, flags),
+        Command::UndoTree { args, flags } => commands::undo::undo_tree_command(app, args, flags),
+        Command::Earlier { args, flags } => commands::undo::earlier_command(app, args, flags),
+        Command::Later { args, flags } => commands::undo::later_command(app, args, flags),
+        Command::Sort { args, flags } => commands::text::sort_command(app, args, flags),
+        Command::Unique { args, flags } => commands::text::unique_command(app, args, flags),
+        Command::Reverse { args, flags } => commands::text::reverse_command(app, args, flags),
+        Command::ConvertCase { case, args, flags } => {
+            commands::text::convert_case_command(app, case, args, flags)
+        }
         Command::Plugin { name, args } => app
             .execute_plugin_command(&name, args)
             .map_err(|e| CommandError::ExecutionFailed(e.to_string())),
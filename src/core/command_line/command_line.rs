@@ -62,7 +62,7 @@ impl CommandLine {
         // cursor calc based on character count for multibyte chars
         let char_len = line.chars().count();
         if (self.cursor.x as usize) > char_len {
-            self.cursor.x = char_len as i16;
+            self.cursor.x = char_len as i32;
         }
 
         let char_idx = self.cursor.x as usize;
@@ -76,7 +76,7 @@ impl CommandLine {
     ///backspaces on x position
     pub fn backspace(&mut self) {
         let line = &mut self.input;
-        if self.cursor.x > 0 && self.cursor.x <= line.chars().count() as i16 {
+        if self.cursor.x > 0 && self.cursor.x <= line.chars().count() as i32 {
             let char_idx = self.cursor.x as usize;
 
             let byte_idx = Self::get_byte_idx(char_idx - 1, line);
@@ -90,7 +90,7 @@ impl CommandLine {
     pub fn delete(&mut self) {
         let line = &mut self.input;
         let char_len = line.chars().count();
-        if char_len > 0 && self.cursor.x < char_len as i16 {
+        if char_len > 0 && self.cursor.x < char_len as i32 {
             let char_idx = self.cursor.x as usize;
 
             let byte_idx = Self::get_byte_idx(char_idx, line);
@@ -100,8 +100,8 @@ impl CommandLine {
 
     //cursor
     ///moves cursor by x amounts in commandline
-    pub fn move_cursor(&mut self, x: i16) {
-        let max_x_pos: i16 = self.input.chars().count() as i16;
+    pub fn move_cursor(&mut self, x: i32) {
+        let max_x_pos: i32 = self.input.chars().count() as i32;
         self.cursor.x = (self.cursor.x + x).clamp(0, max_x_pos);
     }
 
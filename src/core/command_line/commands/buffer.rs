@@ -0,0 +1,91 @@
+//buffer related commands: edit, next, previous, list and delete
+
+use crate::core::app::App;
+use crate::core::command_line::command::CommandFlag;
+use crate::errors::command_errors::CommandError;
+use crate::ui::popups::scrollable_text_popup::ScrollableTextPopup;
+use ratatui::text::Line;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+///opens file in new buffer or switches to it if already open
+pub fn edit_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let Some(path) = args.first() else {
+        return Err(CommandError::InvalidArguments {
+            command: "edit".to_string(),
+            reason: "expected a file path, e.g. :e notes.txt".to_string(),
+        });
+    };
+
+    app.open_buffer(PathBuf::from(path))
+        .map_err(|e| CommandError::ExecutionFailed(format!("Failed to open '{}': {}", path, e)))
+}
+
+///switches to next buffer
+pub fn buffer_next_command(
+    app: &mut App,
+    _args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    app.next_buffer();
+    Ok(())
+}
+
+///switches to previous buffer
+pub fn buffer_prev_command(
+    app: &mut App,
+    _args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    app.prev_buffer();
+    Ok(())
+}
+
+///shows open buffers in popup
+pub fn buffer_list_command(
+    app: &mut App,
+    _args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let lines: Vec<Line> = app
+        .buffer_summaries()
+        .into_iter()
+        .map(|buffer| {
+            let path = buffer
+                .file_path
+                .map(|p| p.display().to_string())
+                .unwrap_or(buffer.name);
+            Line::from(format!(
+                "{:>3} {}{} \"{}\" {} lines",
+                buffer.index + 1,
+                if buffer.active { "%" } else { " " },
+                if buffer.modified { "+" } else { " " },
+                path,
+                buffer.line_count
+            ))
+        })
+        .collect();
+
+    let popup = Box::new(ScrollableTextPopup::new("Buffers".to_string(), lines));
+    app.open_popup(popup);
+    Ok(())
+}
+
+///closes active buffer, refuses if it has unsaved changes unless forced
+pub fn buffer_delete_command(
+    app: &mut App,
+    _args: Vec<String>,
+    flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    if app.content_modified && !flags.contains(&CommandFlag::Force) {
+        return Err(CommandError::InvalidState(
+            "buffer has unsaved changes, save first or use :bd! to discard them".to_string(),
+        ));
+    }
+    app.close_active_buffer();
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use crate::config::Config;
 use crate::core::app::{App, OpCallback, PendingState};
 use crate::core::command_line::command::CommandFlag;
+use crate::core::editor::{TextBuffer, TextStorage};
 use crate::errors::command_errors::CommandError;
 use crate::ui::popups::config_validation_result_popup::ValidationResultPopup;
 use crate::ui::popups::confirmation_popup::ConfirmationPopup;
@@ -146,9 +147,10 @@ fn execute_edit_config_command(app: &mut App) -> Result<(), CommandError> {
     // Load config content into editor
     match std::fs::read_to_string(&config_path) {
         Ok(content) => {
-            app.editor.editor_content = content.lines().map(String::from).collect();
+            app.editor.editor_content = TextBuffer::from_text(&content);
+            app.editor.set_file_format_from(&content);
             if app.editor.editor_content.is_empty() {
-                app.editor.editor_content.push(String::new());
+                app.editor.editor_content.push_line(String::new());
             }
             app.editor.cursor.x = 0;
             app.editor.cursor.y = 0;
@@ -250,7 +252,7 @@ pub fn set_config_command(app: &mut App, key: String, value: String) -> Result<(
 mod tests {
     use crate::config::{Config, KeymapConfig};
     use crate::errors::config_errors::ConfigError;
-    use crate::input::actions::{DebugAction, Direction, EditorAction, InputAction};
+    use crate::input::actions::{DebugAction, EditorAction, InputAction, Motion};
     use crossterm::event::{KeyCode, KeyModifiers};
 
     // Helper to easily create KeyModifiers with Control, Alt, and Shift bits set
@@ -323,7 +325,7 @@ mod tests {
         let result = KeymapConfig::parse_editor_action("move_up").unwrap();
         assert_eq!(
             result,
-            InputAction::Editor(EditorAction::MoveCursor(Direction::Up))
+            InputAction::Editor(EditorAction::MoveCursor(Motion::Up))
         );
     }
 
@@ -373,9 +375,7 @@ mod tests {
         let up = (KeyModifiers::empty(), KeyCode::Up);
         assert_eq!(
             runtime_maps.editor.get(&up),
-            Some(&InputAction::Editor(EditorAction::MoveCursor(
-                Direction::Up
-            )))
+            Some(&InputAction::Editor(EditorAction::MoveCursor(Motion::Up)))
         );
 
         // Check q -> exit_debug in debug map
@@ -106,8 +106,8 @@ pub fn debug_command(
                 app.editor.editor_content.clone(),
                 app.editor.scroll_offset,
                 app.editor.clipboard.copied_text.clone(),
-                app.editor.undo_redo_manager.undo_stack.clone(),
-                app.editor.undo_redo_manager.redo_stack.clone(),
+                app.editor.undo_redo_manager.undo_stack(),
+                app.editor.undo_redo_manager.redo_stack(),
                 app.file_path.clone(),
             );
             log_info!("Manual snapshot captured");
@@ -23,7 +23,7 @@ pub fn save_command(
         PathBuf::from("untitled")
     };
 
-    let new_content = app.editor.editor_content.join("\n");
+    let new_content = app.editor.file_text();
     let path_ref = Path::new(&path_buf);
 
     // If flag force is not inputted and file exists and is different, prompt confirmation
@@ -0,0 +1,34 @@
+//goto command, jumping to a line and column or a mark
+
+use crate::core::app::App;
+use crate::core::command_line::command::CommandFlag;
+use crate::core::editor::goto::GotoTarget;
+use crate::errors::command_errors::CommandError;
+use std::collections::HashSet;
+
+///moves the cursor to a line, line:column, +N/-N lines from the cursor or a mark
+pub fn goto_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let Some(target) = args.first().and_then(|arg| GotoTarget::parse(arg)) else {
+        return Err(CommandError::InvalidArguments {
+            command: "goto".to_string(),
+            reason: "expected a line, line:column, +N, -N or 'mark, e.g. :goto 42:7".to_string(),
+        });
+    };
+    if let GotoTarget::Mark(name) = target {
+        if app.editor.editor_content.mark(name).is_none() {
+            return Err(CommandError::InvalidState(format!(
+                "mark '{}' is not set",
+                name
+            )));
+        }
+    }
+
+    app.editor.clear_block_selection();
+    app.editor.clear_secondary_cursors();
+    app.editor.goto(target);
+    Ok(())
+}
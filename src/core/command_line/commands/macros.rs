@@ -0,0 +1,166 @@
+//keyboard macro commands: record, play, list, save and delete
+
+use crate::core::app::App;
+use crate::core::command_line::command::CommandFlag;
+use crate::core::keyboard_macros::{actions_to_steps, register_name, DEFAULT_MACRO_REGISTER};
+use crate::errors::command_errors::CommandError;
+use crate::ui::popups::scrollable_text_popup::ScrollableTextPopup;
+use ratatui::text::Line;
+use std::collections::HashSet;
+
+///starts recording editor input into a register, :record without a name
+/// stops the recording running or records into the default register
+pub fn record_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    match args.first() {
+        Some(name) => {
+            let register = parse_register("record", name)?;
+            app.macros.stop_recording();
+            app.macros.start_recording(register);
+        }
+        None => app.toggle_macro_recording(),
+    }
+    Ok(())
+}
+
+///replays a macro, optionally a number of times, e.g. :play a 10.
+/// Without a name the macro recorded or played last is replayed
+pub fn play_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let register = match args.first() {
+        Some(name) => parse_register("play", name)?,
+        None => app
+            .macros
+            .last_used()
+            .ok_or_else(|| CommandError::InvalidState("no macro recorded to play".to_string()))?,
+    };
+    let count = match args.get(1) {
+        Some(count) => count
+            .parse::<usize>()
+            .ok()
+            .filter(|count| *count > 0)
+            .ok_or_else(|| CommandError::InvalidArguments {
+                command: "play".to_string(),
+                reason: format!("'{}' is not a count, e.g. :play a 10", count),
+            })?,
+        None => 1,
+    };
+    if app.macros.recording() == Some(register) {
+        return Err(CommandError::InvalidState(format!(
+            "macro '{}' is being recorded",
+            register
+        )));
+    }
+    if !app.play_macro(register, count) {
+        return Err(CommandError::InvalidState(format!(
+            "no macro recorded in '{}'",
+            register
+        )));
+    }
+    Ok(())
+}
+
+///lists the recorded macros with their steps
+pub fn macros_command(
+    app: &mut App,
+    _args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let lines: Vec<Line> = app
+        .macros
+        .iter()
+        .map(|(register, actions)| {
+            let saved = if app.config.macros.contains_key(&register.to_string()) {
+                "saved"
+            } else {
+                ""
+            };
+            Line::from(format!(
+                " {} {:>5}  {}",
+                register,
+                saved,
+                actions_to_steps(actions).join(", ")
+            ))
+        })
+        .collect();
+    if lines.is_empty() {
+        return Err(CommandError::InvalidState(format!(
+            "no macros recorded, start one with :record {}",
+            DEFAULT_MACRO_REGISTER
+        )));
+    }
+
+    let popup = Box::new(ScrollableTextPopup::new("Macros".to_string(), lines));
+    app.open_popup(popup);
+    Ok(())
+}
+
+///writes a macro to the config file, so it is loaded again on the next start
+pub fn save_macro_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let register = required_register("savemacro", &args)?;
+    let Some(actions) = app.macros.get(register) else {
+        return Err(CommandError::InvalidState(format!(
+            "no macro recorded in '{}'",
+            register
+        )));
+    };
+    let steps = actions_to_steps(actions);
+    app.config.macros.insert(register.to_string(), steps);
+    save_config(app)
+}
+
+///deletes a macro, and removes it from the config file if it was saved there
+pub fn delete_macro_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let register = required_register("delmacro", &args)?;
+    let recorded = app.macros.remove(register).is_some();
+    if app.config.macros.remove(&register.to_string()).is_some() {
+        return save_config(app);
+    }
+    if !recorded {
+        return Err(CommandError::InvalidState(format!(
+            "no macro recorded in '{}'",
+            register
+        )));
+    }
+    Ok(())
+}
+
+fn required_register(command: &str, args: &[String]) -> Result<char, CommandError> {
+    match args.first() {
+        Some(name) => parse_register(command, name),
+        None => Err(CommandError::InvalidArguments {
+            command: command.to_string(),
+            reason: format!("expected a macro name, e.g. :{} a", command),
+        }),
+    }
+}
+
+fn parse_register(command: &str, name: &str) -> Result<char, CommandError> {
+    register_name(name).ok_or_else(|| CommandError::InvalidArguments {
+        command: command.to_string(),
+        reason: format!(
+            "'{}' is not a macro name, use a single letter or digit",
+            name
+        ),
+    })
+}
+
+fn save_config(app: &mut App) -> Result<(), CommandError> {
+    app.config
+        .save()
+        .map_err(|e| CommandError::ExecutionFailed(format!("Failed to save config: {}", e)))
+}
@@ -0,0 +1,92 @@
+//named mark commands: set, list and delete
+
+use crate::core::app::App;
+use crate::core::command_line::command::CommandFlag;
+use crate::core::editor::marks::is_valid_mark_name;
+use crate::errors::command_errors::CommandError;
+use crate::ui::popups::scrollable_text_popup::ScrollableTextPopup;
+use ratatui::text::Line;
+use std::collections::HashSet;
+
+///sets a named mark at the cursor, jumped back to with :'name
+pub fn mark_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let name = parse_mark_names("mark", &args)?;
+    let [name] = name[..] else {
+        return Err(CommandError::InvalidArguments {
+            command: "mark".to_string(),
+            reason: "expected one mark name, a letter or digit, e.g. :mark a".to_string(),
+        });
+    };
+    app.editor.set_mark(name);
+    Ok(())
+}
+
+///lists the marks of the active buffer with their position and line
+pub fn marks_command(
+    app: &mut App,
+    _args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let content = &app.editor.editor_content;
+    let lines: Vec<Line> = content
+        .marks()
+        .map(|(name, pos)| {
+            let text = content.get(pos.y).map(|line| line.trim()).unwrap_or("");
+            Line::from(format!(
+                " {} {:>6} {:>4}  {}",
+                name,
+                pos.y + 1,
+                pos.x + 1,
+                text
+            ))
+        })
+        .collect();
+    if lines.is_empty() {
+        return Err(CommandError::InvalidState("no marks set".to_string()));
+    }
+
+    let mut rows = vec![Line::from("mark   line  col  text")];
+    rows.extend(lines);
+    let popup = Box::new(ScrollableTextPopup::new("Marks".to_string(), rows));
+    app.open_popup(popup);
+    Ok(())
+}
+
+///deletes the named marks, given as separate args or together as in :delmarks abc
+pub fn delete_marks_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let names = parse_mark_names("delmarks", &args)?;
+    if names.is_empty() {
+        return Err(CommandError::InvalidArguments {
+            command: "delmarks".to_string(),
+            reason: "expected the marks to delete, e.g. :delmarks ab".to_string(),
+        });
+    }
+    for name in names {
+        app.editor.editor_content.remove_mark(name);
+    }
+    Ok(())
+}
+
+fn parse_mark_names(command: &str, args: &[String]) -> Result<Vec<char>, CommandError> {
+    args.iter()
+        .flat_map(|arg| arg.chars())
+        .map(|c| {
+            if is_valid_mark_name(c) {
+                Ok(c)
+            } else {
+                Err(CommandError::InvalidArguments {
+                    command: command.to_string(),
+                    reason: format!("'{}' is not a mark name, marks are letters or digits", c),
+                })
+            }
+        })
+        .collect()
+}
@@ -1,5 +1,14 @@
+pub mod buffer;
 pub mod config;
 pub mod debug;
 pub mod file;
+pub mod goto;
 pub mod help;
+pub mod macros;
+pub mod marks;
 pub mod quit;
+pub mod registers;
+pub mod set;
+pub mod split;
+pub mod text;
+pub mod undo;
@@ -40,7 +40,7 @@ pub(crate) fn exit_command(
 ) -> Result<(), CommandError> {
     // If flag force is not inputted
     if !flags.contains(&CommandFlag::Force) {
-        if app.content_modified && app.popup_result == PopupResult::None {
+        if app.has_modified_buffers() && app.popup_result == PopupResult::None {
             let popup = Box::new(ConfirmationPopup::new(
                 "YOU HAVE UNSAVED CHANGES, Confirm quit",
             ));
@@ -0,0 +1,96 @@
+//register commands: list, yank into and put from
+
+use crate::core::app::App;
+use crate::core::clipboard::{is_named_register, is_register_name, ClipboardEntry};
+use crate::core::command_line::command::CommandFlag;
+use crate::errors::command_errors::CommandError;
+use crate::ui::popups::scrollable_text_popup::ScrollableTextPopup;
+use ratatui::text::Line;
+use std::collections::HashSet;
+
+/// Chars of an entry shown when listing registers
+const PREVIEW_WIDTH: usize = 60;
+
+///lists what paste pastes, the clipboard history by number and the named registers
+pub fn registers_command(
+    app: &mut App,
+    _args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let clipboard = &app.editor.clipboard;
+    let row = |name: String, entry: &ClipboardEntry| {
+        Line::from(format!(" {:<4} {}", name, entry.preview(PREVIEW_WIDTH)))
+    };
+
+    let mut rows = vec![Line::from("reg  text")];
+    let current = clipboard.current();
+    if !current.text.is_empty() {
+        rows.push(row("\"".to_string(), &current));
+    }
+    rows.extend(
+        clipboard
+            .history()
+            .enumerate()
+            .map(|(idx, entry)| row(idx.to_string(), entry)),
+    );
+    rows.extend(
+        clipboard
+            .registers()
+            .map(|(name, entry)| row(name.to_string(), entry)),
+    );
+    if rows.len() == 1 {
+        return Err(CommandError::InvalidState(
+            "registers are empty".to_string(),
+        ));
+    }
+
+    let popup = Box::new(ScrollableTextPopup::new("Registers".to_string(), rows));
+    app.open_popup(popup);
+    Ok(())
+}
+
+///copies the selection into a named register, e.g. :yank a
+pub fn yank_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let name = parse_register("yank", &args)?;
+    if !is_named_register(name) {
+        return Err(CommandError::InvalidArguments {
+            command: "yank".to_string(),
+            reason: format!("'{}' is not a named register, use a letter", name),
+        });
+    }
+    app.editor
+        .copy_to_register(name)
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))
+}
+
+///pastes a named register, or an entry of the clipboard history by number,
+/// e.g. :put a or :put 2
+pub fn put_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let name = parse_register("put", &args)?;
+    app.editor.clear_block_selection();
+    app.editor.clear_secondary_cursors();
+    app.editor
+        .paste_from_register(name)
+        .map_err(|_| CommandError::InvalidState(format!("register '{}' is empty", name)))?;
+    app.content_modified = app.editor.is_modified();
+    Ok(())
+}
+
+fn parse_register(command: &str, args: &[String]) -> Result<char, CommandError> {
+    let mut chars = args.first().map(|arg| arg.chars()).into_iter().flatten();
+    match (chars.next(), chars.next(), args.len()) {
+        (Some(name), None, 1) if is_register_name(name) => Ok(name),
+        _ => Err(CommandError::InvalidArguments {
+            command: command.to_string(),
+            reason: format!("expected one register name, e.g. :{} a", command),
+        }),
+    }
+}
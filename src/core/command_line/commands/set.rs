@@ -0,0 +1,44 @@
+//buffer options: file format of the active buffer
+
+use crate::core::app::App;
+use crate::core::command_line::command::CommandFlag;
+use crate::core::editor::file_format::LineEnding;
+use crate::errors::command_errors::CommandError;
+use std::collections::HashSet;
+
+///sets options of the active buffer, converting it on the next save,
+/// e.g. :set fileformat=dos, :set ff=unix or :set noeol
+pub fn set_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let invalid = |reason: String| CommandError::InvalidArguments {
+        command: "set".to_string(),
+        reason,
+    };
+    if args.is_empty() {
+        return Err(invalid(
+            "nothing to set, e.g. :set fileformat=dos".to_string(),
+        ));
+    }
+
+    // every option is checked before any is set
+    let mut format = app.editor.file_format;
+    for arg in &args {
+        match arg.split_once('=') {
+            Some(("fileformat" | "ff", name)) => {
+                format.line_ending = LineEnding::from_name(name).ok_or_else(|| {
+                    invalid(format!("'{}' is not a file format, use unix or dos", name))
+                })?;
+            }
+            None if arg == "endofline" || arg == "eol" => format.final_newline = true,
+            None if arg == "noendofline" || arg == "noeol" => format.final_newline = false,
+            _ => return Err(invalid(format!("unknown option '{}'", arg))),
+        }
+    }
+
+    app.editor.file_format = format;
+    app.content_modified = app.editor.is_modified();
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//split view commands: split, vsplit, close and only
+
+use crate::core::app::App;
+use crate::core::command_line::command::CommandFlag;
+use crate::core::splits::SplitDirection;
+use crate::errors::command_errors::CommandError;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+///splits focused view in views stacked on top of each other, optionally opening a file
+pub fn split_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    split_with_optional_file(app, SplitDirection::Horizontal, args)
+}
+
+///splits focused view in views side by side, optionally opening a file
+pub fn vsplit_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    split_with_optional_file(app, SplitDirection::Vertical, args)
+}
+
+///closes focused view, the last view cannot be closed
+pub fn close_command(
+    app: &mut App,
+    _args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    if app.close_view() {
+        Ok(())
+    } else {
+        Err(CommandError::InvalidState(
+            "cannot close the last view, use :q to quit".to_string(),
+        ))
+    }
+}
+
+///closes all views except the focused one
+pub fn only_command(
+    app: &mut App,
+    _args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    app.close_other_views();
+    Ok(())
+}
+
+fn split_with_optional_file(
+    app: &mut App,
+    direction: SplitDirection,
+    args: Vec<String>,
+) -> Result<(), CommandError> {
+    match args.first() {
+        Some(path) => app
+            .split_view_with_file(direction, PathBuf::from(path))
+            .map_err(|e| {
+                CommandError::ExecutionFailed(format!("Failed to open '{}': {}", path, e))
+            }),
+        None => {
+            app.split_view(direction);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//text transformation commands: sort, uniq, reverse and case conversion
+
+use crate::core::app::App;
+use crate::core::command_line::command::CommandFlag;
+use crate::core::editor::transform::{CaseConversion, SortOptions};
+use crate::errors::command_errors::CommandError;
+use std::collections::HashSet;
+
+///sorts selected lines or the whole buffer, options are letters that can be
+/// combined, n numeric, r reverse, i ignore case and u unique, e.g. :sort nr
+pub fn sort_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let mut options = SortOptions::default();
+    for c in args.iter().flat_map(|arg| arg.chars()) {
+        match c {
+            'n' => options.numeric = true,
+            'r' => options.reverse = true,
+            'i' => options.ignore_case = true,
+            'u' => options.unique = true,
+            _ => {
+                return Err(CommandError::InvalidArguments {
+                    command: "sort".to_string(),
+                    reason: format!("unknown option '{}', expected n, r, i or u", c),
+                })
+            }
+        }
+    }
+    edit_text(app, |app| app.editor.sort_lines(options));
+    Ok(())
+}
+
+///removes lines repeating the line before them in the selection or the whole buffer
+pub fn unique_command(
+    app: &mut App,
+    _args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    edit_text(app, |app| app.editor.unique_lines());
+    Ok(())
+}
+
+///reverses the order of selected lines or the whole buffer
+pub fn reverse_command(
+    app: &mut App,
+    _args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    edit_text(app, |app| app.editor.reverse_lines());
+    Ok(())
+}
+
+///converts the case of the selected text or the whole buffer
+pub fn convert_case_command(
+    app: &mut App,
+    case: CaseConversion,
+    _args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    edit_text(app, |app| app.editor.convert_case(case));
+    Ok(())
+}
+
+fn edit_text(app: &mut App, edit: impl FnOnce(&mut App)) {
+    app.editor.clear_block_selection();
+    app.editor.clear_secondary_cursors();
+    edit(app);
+    app.editor.adjust_view_to_cursor();
+    app.content_modified = app.editor.is_modified();
+}
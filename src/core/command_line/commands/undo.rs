@@ -0,0 +1,154 @@
+//undo tree commands: undo, redo, showing the tree and going back or forward in time
+
+use crate::core::app::{App, PendingState};
+use crate::core::command_line::command::CommandFlag;
+use crate::core::editor::undo_redo::HistoryDistance;
+use crate::core::line_diff::diff_lines;
+use crate::errors::command_errors::CommandError;
+use crate::ui::popups::diff_popup::DiffPopup;
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime};
+
+///undoes the last change, or goes to a state of the undo tree by its number
+/// as shown in :undotree, e.g. :undo 12
+pub fn undo_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let result = match args.first() {
+        Some(state) => {
+            let id = state
+                .parse::<usize>()
+                .map_err(|_| CommandError::InvalidArguments {
+                    command: "undo".to_string(),
+                    reason: format!("'{}' is not a state number, e.g. :undo 12", state),
+                })?;
+            app.editor.goto_undo_state(id)
+        }
+        None => app.editor.undo(),
+    };
+    result.map_err(|e| CommandError::InvalidState(e.to_string()))?;
+    app.content_modified = app.editor.is_modified();
+    Ok(())
+}
+
+///redoes the last undone change
+pub fn redo_command(
+    app: &mut App,
+    _args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    app.editor
+        .redo()
+        .map_err(|e| CommandError::InvalidState(e.to_string()))?;
+    app.content_modified = app.editor.is_modified();
+    Ok(())
+}
+
+///opens the undo tree to pick a state, on any branch, to go to
+pub fn undo_tree_command(
+    app: &mut App,
+    _args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    app.open_undo_tree();
+    Ok(())
+}
+
+///goes back to the text as it was a while ago, previewing the changes first,
+/// e.g. :earlier 10m, :earlier 3f for three saves back, or :earlier 5 states
+pub fn earlier_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let distance = parse_distance("earlier", &args)?;
+    let target = app.editor.undo_redo_manager.earlier_target(distance);
+    preview_state(app, target, "oldest")
+}
+
+///goes forward to later text again, the counterpart of :earlier, e.g. :later 30s
+pub fn later_command(
+    app: &mut App,
+    args: Vec<String>,
+    _flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let distance = parse_distance("later", &args)?;
+    let target = app.editor.undo_redo_manager.later_target(distance);
+    preview_state(app, target, "newest")
+}
+
+/// Opens the diff from the current text to state target, going there once
+/// accepted
+fn preview_state(app: &mut App, target: usize, end: &str) -> Result<(), CommandError> {
+    let manager = &app.editor.undo_redo_manager;
+    if target == manager.current() {
+        return Err(CommandError::InvalidState(format!(
+            "already at the {} state",
+            end
+        )));
+    }
+    let age = manager
+        .node(target)
+        .and_then(|node| SystemTime::now().duration_since(node.time).ok())
+        .unwrap_or_default();
+
+    let text = app
+        .editor
+        .text_at_undo_state(target)
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+    let diff = diff_lines(&app.editor.editor_content.to_vec(), &text.to_vec());
+    let title = format!("Go to state {}, from {} ago?", target, format_age(age));
+    app.open_popup(Box::new(DiffPopup::new(&title, &diff)));
+    app.pending_states
+        .push_back(PendingState::TimeTravel(target));
+    Ok(())
+}
+
+/// Reads a count of states, or a count with a unit: s, m, h and d for time
+/// and f for saves of the file
+fn parse_distance(command: &str, args: &[String]) -> Result<HistoryDistance, CommandError> {
+    let invalid = || CommandError::InvalidArguments {
+        command: command.to_string(),
+        reason: format!(
+            "expected a count like 5, 30s, 10m, 2h, 1d or 3f, e.g. :{} 10m",
+            command
+        ),
+    };
+    let arg = match args {
+        [] => return Ok(HistoryDistance::Steps(1)),
+        [arg] => arg,
+        _ => return Err(invalid()),
+    };
+    let split = arg.find(|c: char| !c.is_ascii_digit()).unwrap_or(arg.len());
+    let (count, unit) = arg.split_at(split);
+    let count: u64 = count
+        .parse()
+        .ok()
+        .filter(|count| *count > 0)
+        .ok_or_else(invalid)?;
+    let time =
+        |seconds: u64| HistoryDistance::Time(Duration::from_secs(count.saturating_mul(seconds)));
+
+    match unit {
+        "" => Ok(HistoryDistance::Steps(count as usize)),
+        "s" => Ok(time(1)),
+        "m" => Ok(time(60)),
+        "h" => Ok(time(60 * 60)),
+        "d" => Ok(time(24 * 60 * 60)),
+        "f" => Ok(HistoryDistance::Saves(count as usize)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Duration in its largest whole unit, like 12m
+fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m", seconds / 60),
+        3600..86400 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86400),
+    }
+}
@@ -1,10 +1,12 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::ops::{Add, Sub};
 
 /// handles cursor
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Cursor {
-    pub x: i16,
-    pub y: i16,
+    pub x: i32,
+    pub y: i32,
 }
 
 impl Cursor {
@@ -13,12 +15,30 @@ impl Cursor {
     }
 }
 
-#[derive(Debug, Copy, Clone, Default)]
+/// Line or char index as a cursor coordinate, saturating at i32::MAX
+pub fn to_coord(idx: usize) -> i32 {
+    i32::try_from(idx).unwrap_or(i32::MAX)
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CursorPosition {
     pub x: usize,
     pub y: usize,
 }
 
+/// Positions are ordered as they appear in the text, line first
+impl Ord for CursorPosition {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl PartialOrd for CursorPosition {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Add<CursorPosition> for CursorPosition {
     type Output = Self;
 
@@ -100,8 +100,8 @@ impl App {
                     self.editor.editor_content.clone(),
                     self.editor.scroll_offset,
                     self.editor.clipboard.copied_text.clone(),
-                    self.editor.undo_redo_manager.undo_stack.clone(),
-                    self.editor.undo_redo_manager.undo_stack.clone(),
+                    self.editor.undo_redo_manager.undo_stack(),
+                    self.editor.undo_redo_manager.redo_stack(),
                     self.file_path.clone(),
                 );
                 log_info!("Manual snapshot captured");
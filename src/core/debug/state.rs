@@ -2,6 +2,7 @@ use super::PerformanceMetrics;
 use crate::core::app::ActiveArea;
 use crate::core::cursor::{Cursor, CursorPosition};
 use crate::core::editor::editor::EditAction;
+use crate::core::editor::{TextBuffer, TextStorage};
 use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
@@ -49,9 +50,9 @@ pub struct AppSnapshot {
     //editor state
     pub cursor_pos: Cursor,
     pub selection: Option<Selection>,
-    pub buffer_content: Vec<String>, // Full buffer content
+    pub buffer_content: TextBuffer, // Full buffer content, shares unchanged chunks with editor
     pub buffer_lines: usize,
-    pub scroll_offset: i16,
+    pub scroll_offset: i32,
 
     //clipboard state
     pub clipboard_entries: Vec<String>,
@@ -104,8 +105,8 @@ impl DebugState {
         trigger: Option<SnapshotTrigger>,
         cursor_pos: Cursor,
         selection: Option<Selection>,
-        buffer_content: Vec<String>,
-        scroll_offset: i16,
+        buffer_content: TextBuffer,
+        scroll_offset: i32,
         clipboard_entries: Vec<String>,
         undo_stack: VecDeque<EditAction>,
         redo_stack: VecDeque<EditAction>,
@@ -144,8 +145,8 @@ impl DebugState {
         active_area: ActiveArea,
         cursor_pos: Cursor,
         selection: Option<Selection>,
-        buffer_content: Vec<String>,
-        scroll_offset: i16,
+        buffer_content: TextBuffer,
+        scroll_offset: i32,
         clipboard_entries: Vec<String>,
         undo_stack: VecDeque<EditAction>,
         redo_stack: VecDeque<EditAction>,
@@ -172,8 +173,8 @@ impl DebugState {
         trigger: SnapshotTrigger,
         cursor_pos: Cursor,
         selection: Option<Selection>,
-        buffer_content: Vec<String>,
-        scroll_offset: i16,
+        buffer_content: TextBuffer,
+        scroll_offset: i32,
         clipboard_entries: Vec<String>,
         undo_stack: VecDeque<EditAction>,
         redo_stack: VecDeque<EditAction>,
@@ -184,8 +185,8 @@ impl DebugState {
             trigger,
             cursor_pos,
             selection,
-            buffer_content: buffer_content.clone(),
-            buffer_lines: buffer_content.len(),
+            buffer_lines: buffer_content.line_count(),
+            buffer_content,
             scroll_offset,
             clipboard_entries: clipboard_entries.clone(),
             clipboard_size: clipboard_entries.len(),
@@ -0,0 +1,440 @@
+//! Rectangular (column) selection.
+//!
+//! A block is given in lines and display columns rather than char indexes, so
+//! it stays a rectangle on screen over tabs and wide characters, and its right
+//! edge can reach past the end of short lines. Edits on a block are made line
+//! by line and recorded as one undo step.
+
+use super::editor::{EditAction, Editor};
+use super::graphemes;
+use super::text_buffer::{char_slice, TextStorage};
+use super::wrap;
+use crate::core::cursor::CursorPosition;
+use crate::errors::editor_errors::EditorError::{ClipboardFailure, TextSelectionFailure};
+use crate::errors::editor_errors::{ClipboardError, EditorError, TextSelectionError};
+use std::ops::{Range, RangeInclusive};
+
+/// Rectangle between the line and column the selection started at and the
+/// line and column the cursor moved to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockSelection {
+    pub anchor_line: usize,
+    pub anchor_col: usize,
+    pub head_line: usize,
+    pub head_col: usize,
+}
+
+impl BlockSelection {
+    pub fn new(line: usize, col: usize) -> Self {
+        Self {
+            anchor_line: line,
+            anchor_col: col,
+            head_line: line,
+            head_col: col,
+        }
+    }
+
+    pub fn lines(&self) -> RangeInclusive<usize> {
+        self.anchor_line.min(self.head_line)..=self.anchor_line.max(self.head_line)
+    }
+
+    /// Display columns covered, end exclusive
+    pub fn columns(&self) -> Range<usize> {
+        self.anchor_col.min(self.head_col)..self.anchor_col.max(self.head_col)
+    }
+
+    /// A block without width, a column cursor over several lines
+    pub fn is_thin(&self) -> bool {
+        self.anchor_col == self.head_col
+    }
+
+    /// Same lines, no width, at column
+    fn collapsed_to(&self, col: usize) -> Self {
+        Self {
+            anchor_col: col,
+            head_col: col,
+            ..*self
+        }
+    }
+}
+
+impl Editor {
+    /// Starts or extends the block selection, the head moves by display column
+    /// so it can go past the end of short lines
+    pub(crate) fn move_block_selection(&mut self, x: i32, y: i32) {
+        if self.editor_content.is_empty() {
+            return;
+        }
+        let cursor_y = self.cursor.y.max(0) as usize;
+        let cursor_col = self.visual_cursor_x.max(0) as usize;
+        let mut block = self
+            .block_selection
+            .unwrap_or_else(|| BlockSelection::new(cursor_y, cursor_col));
+        self.reset_text_selection_cursor();
+        self.clear_secondary_cursors();
+
+        let last_line = self.editor_content.len() - 1;
+        block.head_line = (block.head_line as i64 + y as i64).clamp(0, last_line as i64) as usize;
+        block.head_col = (block.head_col as i64 + x as i64).max(0) as usize;
+        self.set_block_selection(block);
+    }
+
+    /// Drops the block selection
+    pub fn clear_block_selection(&mut self) {
+        self.block_selection = None;
+    }
+
+    /// Char range selected on every line the block reaches. Ranges are empty
+    /// only when the block has no width, marking the column on each line
+    pub fn block_ranges(&self) -> Vec<(CursorPosition, CursorPosition)> {
+        let Some(block) = self.block_selection else {
+            return Vec::new();
+        };
+        block
+            .lines()
+            .filter(|&y| self.line_width(y) >= block.columns().start)
+            .map(|y| (y, self.block_char_range(&block, y)))
+            .filter(|(_, range)| block.is_thin() || !range.is_empty())
+            .map(|(y, range)| {
+                (
+                    CursorPosition { x: range.start, y },
+                    CursorPosition { x: range.end, y },
+                )
+            })
+            .collect()
+    }
+
+    /// Copies the block to the clipboard, one entry per line
+    pub(crate) fn copy_block(&mut self) -> Result<(), EditorError> {
+        let rows = self.block_rows()?;
+        self.clipboard.copy_block(&rows);
+        self.clear_block_selection();
+        Ok(())
+    }
+
+    /// Copies the block to the clipboard and removes it from the text
+    pub(crate) fn cut_block(&mut self) -> Result<(), EditorError> {
+        let rows = self.block_rows()?;
+        self.clipboard.copy_block(&rows);
+        self.replace_block_with("");
+        Ok(())
+    }
+
+    /// Puts text in place of the block on every line it reaches, typing on a
+    /// block without width inserts on every line
+    pub(crate) fn replace_block_with(&mut self, text: &str) {
+        let Some(block) = self.block_selection else {
+            return;
+        };
+        let left = block.columns().start;
+        let mut actions = Vec::new();
+        let mut new_col = left;
+        for y in block.lines() {
+            if self.line_width(y) < left {
+                continue;
+            }
+            let range = self.block_char_range(&block, y);
+            let end = range.start + text.chars().count();
+            actions.push(self.replace_on_line(y, range, text));
+            new_col = self.col_of(&self.editor_content[y], end);
+        }
+        self.record_undo_group(actions);
+        self.set_block_selection(block.collapsed_to(new_col));
+    }
+
+    /// Removes the block, or the grapheme before it on every line when it has no width
+    pub(crate) fn backspace_block(&mut self) {
+        let Some(block) = self.block_selection else {
+            return;
+        };
+        let left = block.columns().start;
+        if !block.is_thin() || left == 0 {
+            self.replace_block_with("");
+            return;
+        }
+        let mut actions = Vec::new();
+        let mut new_col = left - 1;
+        for y in block.lines() {
+            if self.line_width(y) < left {
+                continue;
+            }
+            let line = &self.editor_content[y];
+            let end = self.char_idx_from_col(line, left);
+            let start = graphemes::prev_boundary(line, end);
+            new_col = self.col_of(line, start);
+            actions.push(self.replace_on_line(y, start..end, ""));
+        }
+        self.record_undo_group(actions);
+        self.set_block_selection(block.collapsed_to(new_col));
+    }
+
+    /// Removes the block, or the grapheme after it on every line when it has no width
+    pub(crate) fn delete_block(&mut self) {
+        let Some(block) = self.block_selection else {
+            return;
+        };
+        if !block.is_thin() {
+            self.replace_block_with("");
+            return;
+        }
+        let left = block.columns().start;
+        let mut actions = Vec::new();
+        for y in block.lines() {
+            let line = &self.editor_content[y];
+            let start = self.char_idx_from_col(line, left);
+            let end = graphemes::next_boundary(line, start);
+            if start < end {
+                actions.push(self.replace_on_line(y, start..end, ""));
+            }
+        }
+        self.record_undo_group(actions);
+        self.set_block_selection(block);
+    }
+
+    /// Pastes clipboard lines as a column at the cursor, or in place of the
+    /// block. Short lines are padded with spaces and missing lines are added
+    pub(crate) fn paste_block(&mut self) -> Result<(), EditorError> {
+        let rows = self.clipboard.paste();
+        if rows.is_empty() {
+            return Err(ClipboardFailure(ClipboardError::NoCopiedText));
+        }
+        self.reset_text_selection_cursor();
+        let (top, col) = match self.block_selection {
+            Some(block) => (*block.lines().start(), block.columns().start),
+            None => (
+                self.cursor.y.max(0) as usize,
+                self.visual_cursor_x.max(0) as usize,
+            ),
+        };
+
+        let mut actions = Vec::new();
+        if let Some(block) = self.block_selection {
+            for y in block.lines() {
+                let range = self.block_char_range(&block, y);
+                if !range.is_empty() {
+                    actions.push(self.replace_on_line(y, range, ""));
+                }
+            }
+        }
+
+        let needed = top + rows.len();
+        if needed > self.editor_content.len() {
+            let start = CursorPosition {
+                x: 0,
+                y: self.editor_content.len(),
+            };
+            let lines = vec![String::new(); needed - self.editor_content.len()];
+            self.editor_content.insert_lines(start.y, lines.clone());
+            actions.push(EditAction::InsertLines { start, lines });
+        }
+
+        for (i, row) in rows.iter().enumerate() {
+            let y = top + i;
+            let width = self.line_width(y);
+            let (x, padding) = if width < col {
+                (
+                    self.editor_content.line_char_count(y),
+                    " ".repeat(col - width),
+                )
+            } else {
+                (
+                    self.char_idx_from_col(&self.editor_content[y], col),
+                    String::new(),
+                )
+            };
+            actions.push(self.replace_on_line(y, x..x, &format!("{padding}{row}")));
+        }
+        self.record_undo_group(actions);
+
+        self.clear_block_selection();
+        let last = rows.len() - 1;
+        let line = &self.editor_content[top + last];
+        let x = self.char_idx_from_col(line, col) + rows[last].chars().count();
+        self.set_cursor_position(&CursorPosition { x, y: top + last });
+        Ok(())
+    }
+
+    /// Text of the block, one entry per line
+    fn block_rows(&self) -> Result<Vec<String>, EditorError> {
+        let block = match self.block_selection {
+            Some(block) if !block.is_thin() => block,
+            _ => return Err(TextSelectionFailure(TextSelectionError::NoTextSelected)),
+        };
+        Ok(block
+            .lines()
+            .map(|y| {
+                let range = self.block_char_range(&block, y);
+                char_slice(&self.editor_content[y], range.start, range.end).to_string()
+            })
+            .collect())
+    }
+
+    /// Sets block and puts the cursor at its head
+    fn set_block_selection(&mut self, block: BlockSelection) {
+        let line = &self.editor_content[block.head_line];
+        let x = wrap::char_idx_at_visual_col(line, block.head_col, self.tab_width());
+        self.set_cursor_position(&CursorPosition {
+            x,
+            y: block.head_line,
+        });
+        // the cursor is drawn at the block edge, even past the end of the line
+        self.visual_cursor_x = block.head_col.min(i32::MAX as usize) as i32;
+        self.block_selection = Some(block);
+    }
+
+    /// Chars of line y whose display column lies in the block columns
+    fn block_char_range(&self, block: &BlockSelection, y: usize) -> Range<usize> {
+        let line = self.editor_content.get(y).map(String::as_str).unwrap_or("");
+        let columns = block.columns();
+        self.char_idx_from_col(line, columns.start)..self.char_idx_from_col(line, columns.end)
+    }
+
+    /// Index of the first grapheme starting at or after display column
+    fn char_idx_from_col(&self, line: &str, col: usize) -> usize {
+        let idx = wrap::char_idx_at_visual_col(line, col, self.tab_width());
+        if self.col_of(line, idx) < col {
+            graphemes::next_boundary(line, idx)
+        } else {
+            idx
+        }
+    }
+
+    fn col_of(&self, line: &str, char_idx: usize) -> usize {
+        wrap::visual_col(line, char_idx, self.tab_width())
+    }
+
+    /// Display width of line y
+    fn line_width(&self, y: usize) -> usize {
+        self.editor_content
+            .get(y)
+            .map(|line| self.col_of(line, usize::MAX))
+            .unwrap_or(0)
+    }
+
+    fn tab_width(&self) -> usize {
+        self.editor_config.tab_width as usize
+    }
+
+    /// Replaces chars in range on line y with text, returning the action to record
+    fn replace_on_line(&mut self, y: usize, range: Range<usize>, text: &str) -> EditAction {
+        let start = CursorPosition { x: range.start, y };
+        let end = CursorPosition { x: range.end, y };
+        self.replace_range(start, end, vec![text.to_string()])
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_block_selection_tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::core::clipboard::ClipboardKind;
+    use std::sync::Arc;
+
+    fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = vec.into();
+        editor.editor_height = 10;
+        editor
+    }
+
+    fn table() -> Editor {
+        create_editor_with_editor_content(vec![
+            "id name  age".to_string(),
+            "1  alice 30".to_string(),
+            "2  bob".to_string(),
+        ])
+    }
+
+    #[test]
+    fn test_block_copy_takes_columns_of_every_line() {
+        let mut editor = table();
+        editor.move_cursor(3, 0);
+        for _ in 0..5 {
+            editor.move_block_selection(1, 0);
+        }
+        editor.move_block_selection(0, 2);
+        assert_eq!(editor.block_selection.unwrap().columns(), 3..8);
+
+        editor.copy_block().unwrap();
+        assert_eq!(editor.clipboard.copied_text, vec!["name ", "alice", "bob"]);
+        assert_eq!(editor.clipboard.kind, ClipboardKind::Block);
+    }
+
+    #[test]
+    fn test_block_cut_is_one_undo_step() {
+        let mut editor = table();
+        editor.move_cursor(3, 0);
+        for _ in 0..6 {
+            editor.move_block_selection(1, 0);
+        }
+        editor.move_block_selection(0, 1);
+        editor.cut_block().unwrap();
+        assert_eq!(
+            editor.editor_content.to_lines(),
+            vec!["id age", "1  30", "2  bob"]
+        );
+
+        editor.undo().unwrap();
+        assert_eq!(
+            editor.editor_content.to_lines(),
+            vec!["id name  age", "1  alice 30", "2  bob"]
+        );
+    }
+
+    #[test]
+    fn test_typing_on_thin_block_inserts_on_every_line() {
+        let mut editor = create_editor_with_editor_content(vec![
+            "ab".to_string(),
+            "cd".to_string(),
+            "".to_string(),
+        ]);
+        editor.move_cursor(1, 0);
+        editor.move_block_selection(0, 2);
+        editor.replace_block_with("|");
+        editor.replace_block_with("|");
+        // the empty line does not reach the block column and is left alone
+        assert_eq!(editor.editor_content.to_lines(), vec!["a||b", "c||d", ""]);
+        assert_eq!(editor.block_selection.unwrap().columns(), 3..3);
+
+        editor.backspace_block();
+        assert_eq!(editor.editor_content.to_lines(), vec!["a|b", "c|d", ""]);
+    }
+
+    #[test]
+    fn test_block_paste_pads_short_lines_and_adds_missing_ones() {
+        let mut editor =
+            create_editor_with_editor_content(vec!["abcd".to_string(), "a".to_string()]);
+        editor
+            .clipboard
+            .copy_block(&["X".to_string(), "Y".to_string(), "Z".to_string()]);
+        editor.move_cursor(3, 0);
+        editor.paste_block().unwrap();
+        assert_eq!(
+            editor.editor_content.to_lines(),
+            vec!["abcXd", "a  Y", "   Z"]
+        );
+
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content.to_lines(), vec!["abcd", "a"]);
+    }
+
+    #[test]
+    fn test_block_columns_follow_tabs() {
+        let mut editor =
+            create_editor_with_editor_content(vec!["\tx".to_string(), "abcdx".to_string()]);
+        editor.block_selection = Some(BlockSelection {
+            anchor_line: 0,
+            anchor_col: 4,
+            head_line: 1,
+            head_col: 5,
+        });
+        editor.copy_block().unwrap();
+        assert_eq!(editor.clipboard.copied_text, vec!["x", "x"]);
+    }
+}
@@ -0,0 +1,228 @@
+//! Commenting and uncommenting lines with the comment syntax of the file type.
+
+use super::editor::{EditAction, Editor};
+use super::motions::first_non_blank;
+use super::text_buffer::TextStorage;
+use crate::config::CommentTokens;
+use crate::core::cursor::CursorPosition;
+
+impl Editor {
+    /// Comments the lines of the selection, or the cursor's line, and
+    /// uncomments them when they all are commented already. Line comments are
+    /// used when the language has them, otherwise a block comment wraps the lines.
+    /// All changes are recorded as one Bulk
+    pub fn toggle_comment(&mut self, tokens: &CommentTokens) {
+        let (first, last) = self.selected_lines();
+        let actions = match (&tokens.line, &tokens.block) {
+            (Some(token), _) => self.toggle_line_comments(first, last, token),
+            (None, Some((open, close))) => self.toggle_block_comment(first, last, open, close),
+            (None, None) => return,
+        };
+        if !actions.is_empty() {
+            self.undo_redo_manager
+                .record_undo(EditAction::Bulk(actions));
+        }
+    }
+
+    /// Puts token in front of every non-blank line, aligned at the smallest
+    /// indentation among them, or takes it off again
+    fn toggle_line_comments(&mut self, first: usize, last: usize, token: &str) -> Vec<EditAction> {
+        let lines: Vec<usize> = (first..=last)
+            .filter(|&y| !self.editor_content[y].trim().is_empty())
+            .collect();
+        let commented = lines
+            .iter()
+            .all(|&y| self.editor_content[y].trim_start().starts_with(token));
+
+        let mut actions = Vec::new();
+        let mut shifts = Vec::new();
+        if commented {
+            for y in lines {
+                let line = &self.editor_content[y];
+                let x = first_non_blank(line);
+                let after_token = &line.trim_start()[token.len()..];
+                let width = token.chars().count() + usize::from(after_token.starts_with(' '));
+                actions.push(self.remove_on_line(y, x, width));
+                shifts.push((y, x, -(width as isize)));
+            }
+        } else {
+            let Some(x) = lines
+                .iter()
+                .map(|&y| first_non_blank(&self.editor_content[y]))
+                .min()
+            else {
+                return actions;
+            };
+            let text = format!("{token} ");
+            for y in lines {
+                actions.push(self.insert_on_line(y, x, &text));
+                shifts.push((y, x, text.chars().count() as isize));
+            }
+        }
+        self.shift_positions(&shifts);
+        actions
+    }
+
+    /// Wraps the non-blank lines in open and close, or unwraps them when the
+    /// first one starts with open and the last one ends with close
+    fn toggle_block_comment(
+        &mut self,
+        first: usize,
+        last: usize,
+        open: &str,
+        close: &str,
+    ) -> Vec<EditAction> {
+        let non_blank = |y: &usize| !self.editor_content[*y].trim().is_empty();
+        let (Some(head), Some(tail)) = (
+            (first..=last).find(non_blank),
+            (first..=last).rev().find(non_blank),
+        ) else {
+            return Vec::new();
+        };
+        let x = first_non_blank(&self.editor_content[head]);
+        let head_text = self.editor_content[head].trim_start().to_string();
+        let tail_text = self.editor_content[tail].trim_end().to_string();
+        let tail_end = tail_text.chars().count();
+        let fits = head != tail || head_text.trim_end().len() >= open.len() + close.len();
+        let commented = fits && head_text.starts_with(open) && tail_text.ends_with(close);
+
+        // the tail is edited first, it never lies before the head
+        let mut actions = Vec::new();
+        let head_shift = if commented {
+            let close_width = close.chars().count();
+            let spaced = tail_text[..tail_text.len() - close.len()].ends_with(' ')
+                && (head != tail || tail_end - close_width > x + open.chars().count());
+            let width = close_width + usize::from(spaced);
+            actions.push(self.remove_on_line(tail, tail_end - width, width));
+
+            let after_open = &self.editor_content[head].trim_start()[open.len()..];
+            let width = open.chars().count() + usize::from(after_open.starts_with(' '));
+            actions.push(self.remove_on_line(head, x, width));
+            -(width as isize)
+        } else {
+            actions.push(self.insert_on_line(tail, tail_end, &format!(" {close}")));
+            let text = format!("{open} ");
+            actions.push(self.insert_on_line(head, x, &text));
+            text.chars().count() as isize
+        };
+        self.shift_positions(&[(head, x, head_shift)]);
+        actions
+    }
+
+    fn insert_on_line(&mut self, y: usize, x: usize, text: &str) -> EditAction {
+        let pos = CursorPosition { x, y };
+        self.replace_range(pos, pos, vec![text.to_string()])
+    }
+
+    fn remove_on_line(&mut self, y: usize, x: usize, width: usize) -> EditAction {
+        let end = CursorPosition {
+            x: (x + width).min(self.editor_content.line_char_count(y)),
+            y,
+        };
+        self.replace_range(CursorPosition { x, y }, end, vec![String::new()])
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_comments_tests {
+    use super::*;
+    use crate::config::Config;
+    use std::sync::Arc;
+
+    fn create_editor_with_editor_content(vec: Vec<&str>) -> Editor {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = vec.into_iter().map(String::from).collect();
+        editor
+    }
+
+    fn select_all(editor: &mut Editor) {
+        let last = editor.editor_content.len() - 1;
+        editor.text_selection_start = Some(CursorPosition { x: 0, y: 0 });
+        editor.text_selection_end = Some(CursorPosition {
+            x: editor.editor_content.line_char_count(last),
+            y: last,
+        });
+    }
+
+    fn line_tokens(token: &str) -> CommentTokens {
+        CommentTokens {
+            line: Some(token.to_string()),
+            block: None,
+        }
+    }
+
+    #[test]
+    fn test_line_comments_align_at_smallest_indent_and_skip_blank_lines() {
+        let mut editor = create_editor_with_editor_content(vec!["  if x:", "", "      y()"]);
+        select_all(&mut editor);
+        editor.toggle_comment(&line_tokens("#"));
+        assert_eq!(
+            editor.editor_content.to_lines(),
+            vec!["  # if x:", "", "  #     y()"]
+        );
+
+        editor.toggle_comment(&line_tokens("#"));
+        assert_eq!(
+            editor.editor_content.to_lines(),
+            vec!["  if x:", "", "      y()"]
+        );
+    }
+
+    #[test]
+    fn test_partly_commented_lines_get_commented_and_undo_is_one_step() {
+        let mut editor = create_editor_with_editor_content(vec!["// a", "b"]);
+        select_all(&mut editor);
+        editor.toggle_comment(&line_tokens("//"));
+        assert_eq!(editor.editor_content.to_lines(), vec!["// // a", "// b"]);
+        assert!(matches!(
+            editor.undo_redo_manager.undo_stack().back(),
+            Some(EditAction::Bulk(actions)) if actions.len() == 2
+        ));
+
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content.to_lines(), vec!["// a", "b"]);
+    }
+
+    #[test]
+    fn test_uncomment_without_space_and_cursor_follows_text() {
+        let mut editor = create_editor_with_editor_content(vec!["    --x = 1"]);
+        editor.cursor.x = 7;
+        editor.toggle_comment(&line_tokens("--"));
+        assert_eq!(editor.editor_content.to_lines(), vec!["    x = 1"]);
+        assert_eq!(editor.cursor.x, 5);
+    }
+
+    #[test]
+    fn test_block_comment_wraps_and_unwraps_lines() {
+        let tokens = CommentTokens {
+            line: None,
+            block: Some(("<!--".to_string(), "-->".to_string())),
+        };
+        let mut editor = create_editor_with_editor_content(vec!["  <p>", "  </p>"]);
+        select_all(&mut editor);
+        editor.toggle_comment(&tokens);
+        assert_eq!(
+            editor.editor_content.to_lines(),
+            vec!["  <!-- <p>", "  </p> -->"]
+        );
+
+        editor.toggle_comment(&tokens);
+        assert_eq!(editor.editor_content.to_lines(), vec!["  <p>", "  </p>"]);
+
+        let mut editor = create_editor_with_editor_content(vec!["a {}"]);
+        let css = CommentTokens {
+            line: None,
+            block: Some(("/*".to_string(), "*/".to_string())),
+        };
+        editor.toggle_comment(&css);
+        assert_eq!(editor.editor_content.to_lines(), vec!["/* a {} */"]);
+        editor.toggle_comment(&css);
+        assert_eq!(editor.editor_content.to_lines(), vec!["a {}"]);
+    }
+}
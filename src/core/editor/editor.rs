@@ -1,17 +1,29 @@
-use super::super::super::core::clipboard::Clipboard;
+use super::super::super::core::clipboard::{Clipboard, ClipboardKind};
+use super::super::super::core::clipboard_provider;
+use super::super::cursor::to_coord;
 use super::super::cursor::Cursor;
 use super::super::cursor::CursorPosition;
-use super::undo_redo::UndoRedoManager;
+use super::block_selection::BlockSelection;
+use super::file_format::FileFormat;
+use super::graphemes;
+use super::marks::JumpList;
+use super::modal::ModalState;
+use super::multi_cursor::SecondaryCursor;
+use super::text_buffer::{char_slice, char_to_byte_idx, TextBuffer, TextStorage};
+use super::undo_redo::{CursorState, UndoRedoManager};
+use super::wrap;
 use crate::config::{Config, EditorConfig};
 use crate::errors::editor_errors::EditorError::{
     ClipboardFailure, RedoFailure, TextSelectionFailure, UndoFailure,
 };
 use crate::errors::editor_errors::{ClipboardError, EditorError, TextSelectionError};
 use crate::input::actions::{EditorAction, InputAction};
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use std::sync::Arc;
+use std::time::Duration;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EditAction {
     // single-char operations
     Insert {
@@ -171,16 +183,22 @@ impl Display for EditAction {
 /// handles editor content
 #[derive(Debug)]
 pub struct Editor {
-    pub editor_content: Vec<String>,
-    pub visual_cursor_x: i16,
+    pub editor_content: TextBuffer,
+    pub visual_cursor_x: i32,
     pub cursor: Cursor, //to save position in editor, when toggling area
     pub text_selection_start: Option<CursorPosition>,
     pub text_selection_end: Option<CursorPosition>,
-    pub editor_width: i16,
-    pub scroll_offset: i16,
+    pub secondary_cursors: Vec<SecondaryCursor>, //cursors besides the primary one above
+    pub block_selection: Option<BlockSelection>, //rectangular selection, in place of the one above
+    pub modal: Option<ModalState>,               //vim style mode, when modal editing is enabled
+    pub editor_width: i32,
+    pub scroll_offset: i32,
     pub editor_height: u16,
     pub clipboard: Clipboard,
     pub undo_redo_manager: UndoRedoManager,
+    pub jump_list: JumpList, //positions left by big moves, to jump back to
+    pub file_format: FileFormat, //line endings and final newline written on save
+    pub saved_file_format: FileFormat, //format of the file as last read or saved
 
     //Cached config settings
     pub editor_config: Arc<EditorConfig>,
@@ -189,16 +207,24 @@ pub struct Editor {
 impl Editor {
     pub fn new(config: Arc<EditorConfig>) -> Self {
         Self {
-            editor_content: vec![],
+            editor_content: TextBuffer::new(),
             visual_cursor_x: 0,
             text_selection_start: None,
             text_selection_end: None,
+            secondary_cursors: Vec::new(),
+            block_selection: None,
+            modal: config.modal_editing.then(ModalState::default),
             cursor: Cursor::new(),
             editor_width: 0,
             scroll_offset: 0,
             editor_height: 0,
-            clipboard: Clipboard::new(),
-            undo_redo_manager: UndoRedoManager::new(config.undo_history_limit),
+            clipboard: Clipboard::with_history_limit(config.clipboard_history_size)
+                .with_provider(clipboard_provider::from_config(&config)),
+            undo_redo_manager: UndoRedoManager::new(config.undo_history_limit)
+                .with_idle_threshold(Duration::from_millis(config.undo_group_idle_ms)),
+            jump_list: JumpList::default(),
+            file_format: FileFormat::default(),
+            saved_file_format: FileFormat::default(),
             editor_config: config,
         }
     }
@@ -206,32 +232,194 @@ impl Editor {
     ///function to handle input action on editor,
     /// responsible for dispatching action to correct internal method.
     pub fn handle_input_action(&mut self, action: InputAction) -> Result<(), EditorError> {
+        let revision = self.undo_redo_manager.revision();
+        let before = self.cursor_state();
+        let result = self.dispatch_input_action(action);
+        self.stamp_cursor_states(revision, before);
+        result
+    }
+
+    fn dispatch_input_action(&mut self, action: InputAction) -> Result<(), EditorError> {
+        //outside insert mode, typed chars are vim commands
+        if let Some(result) = self.handle_modal_action(&action) {
+            return result;
+        }
+        //text copied in other programs is pasted too
+        if action == InputAction::Editor(EditorAction::PASTE) {
+            self.clipboard.sync_from_provider();
+        }
         match action {
+            InputAction::TAB if self.block_selection.is_some() => {
+                self.replace_block_with(&self.indent_unit());
+                Ok(())
+            }
+            InputAction::TAB if self.has_secondary_cursors() => {
+                self.tab_at_cursors();
+                Ok(())
+            }
+            InputAction::TAB if self.text_selection_start.is_some() => {
+                self.indent_lines();
+                Ok(())
+            }
             InputAction::TAB => {
                 self.tab();
                 Ok(())
             }
             InputAction::ENTER => {
-                self.enter();
+                self.clear_block_selection();
+                if self.has_secondary_cursors() {
+                    self.enter_at_cursors();
+                } else {
+                    self.enter();
+                }
                 self.adjust_view_to_cursor();
                 self.reset_text_selection_cursor(); //reset selection, to avoid errors
                 Ok(())
             }
             InputAction::Editor(editor_action) => match editor_action {
-                EditorAction::MoveCursor(direction) => {
-                    let (x, y) = direction.to_vector();
-                    self.move_cursor(x, y);
+                EditorAction::MoveCursor(motion) => {
+                    self.clear_block_selection();
+                    self.for_each_secondary_cursor(|editor| {
+                        editor.move_by(motion);
+                        editor.reset_text_selection_cursor();
+                    });
+                    self.move_by(motion);
+                    self.merge_cursors();
                     self.adjust_view_to_cursor();
                     self.reset_text_selection_cursor(); //reset selection, to avoid errors
                     Ok(())
                 }
-                EditorAction::MoveSelectionCursor(direction) => {
+                EditorAction::MoveSelectionCursor(motion) => {
+                    self.clear_block_selection();
+                    self.for_each_secondary_cursor(|editor| editor.select_by(motion));
+                    self.select_by(motion);
+                    self.merge_cursors();
+                    self.adjust_view_to_cursor();
+                    Ok(())
+                }
+                EditorAction::MoveBlockSelection(direction) => {
                     let (x, y) = direction.to_vector();
-                    self.move_selection_cursor(x, y);
+                    self.move_block_selection(x, y);
+                    self.adjust_view_to_cursor();
+                    Ok(())
+                }
+                EditorAction::AddCursorAbove => {
+                    self.add_cursor_vertical(-1);
+                    Ok(())
+                }
+                EditorAction::AddCursorBelow => {
+                    self.add_cursor_vertical(1);
+                    Ok(())
+                }
+                EditorAction::AddCursorAtNextMatch => {
+                    self.add_cursor_at_next_match();
+                    Ok(())
+                }
+                EditorAction::JumpToMatchingBracket => {
+                    self.clear_block_selection();
+                    self.jump_to_matching_bracket();
+                    self.adjust_view_to_cursor();
+                    self.reset_text_selection_cursor();
+                    Ok(())
+                }
+                EditorAction::JumpBack => {
+                    self.clear_block_selection();
+                    self.clear_secondary_cursors();
+                    self.jump_back();
+                    Ok(())
+                }
+                EditorAction::JumpForward => {
+                    self.clear_block_selection();
+                    self.clear_secondary_cursors();
+                    self.jump_forward();
+                    Ok(())
+                }
+                EditorAction::DuplicateLines => {
+                    self.clear_block_selection();
+                    self.clear_secondary_cursors();
+                    self.duplicate_lines();
+                    self.adjust_view_to_cursor();
+                    Ok(())
+                }
+                EditorAction::MoveLinesUp => {
+                    self.clear_block_selection();
+                    self.clear_secondary_cursors();
+                    self.move_lines(-1);
+                    self.adjust_view_to_cursor();
+                    Ok(())
+                }
+                EditorAction::MoveLinesDown => {
+                    self.clear_block_selection();
+                    self.clear_secondary_cursors();
+                    self.move_lines(1);
+                    self.adjust_view_to_cursor();
+                    Ok(())
+                }
+                EditorAction::DeleteLines => {
+                    self.clear_block_selection();
+                    self.clear_secondary_cursors();
+                    self.delete_lines();
+                    self.adjust_view_to_cursor();
+                    Ok(())
+                }
+                EditorAction::JoinLines => {
+                    self.clear_block_selection();
+                    self.clear_secondary_cursors();
+                    self.join_lines();
+                    self.adjust_view_to_cursor();
+                    Ok(())
+                }
+                EditorAction::Outdent => {
+                    self.clear_block_selection();
+                    self.outdent_lines();
                     self.adjust_view_to_cursor();
                     Ok(())
                 }
 
+                // with a block selection, edits apply column-wise on every line of it
+                EditorAction::BACKSPACE if self.block_selection.is_some() => {
+                    self.backspace_block();
+                    Ok(())
+                }
+                EditorAction::DELETE if self.block_selection.is_some() => {
+                    self.delete_block();
+                    Ok(())
+                }
+                EditorAction::COPY if self.block_selection.is_some() => self.copy_block(),
+                EditorAction::CUT if self.block_selection.is_some() => self.cut_block(),
+                EditorAction::PASTE if self.block_selection.is_some() => self.paste_block(),
+                EditorAction::WriteChar(c) if self.block_selection.is_some() => {
+                    self.replace_block_with(&c.to_string());
+                    Ok(())
+                }
+
+                // with secondary cursors, edits apply at every cursor
+                EditorAction::BACKSPACE if self.has_secondary_cursors() => {
+                    self.backspace_at_cursors();
+                    Ok(())
+                }
+                EditorAction::DELETE if self.has_secondary_cursors() => {
+                    self.delete_at_cursors();
+                    Ok(())
+                }
+                EditorAction::COPY if self.has_secondary_cursors() => self.copy_at_cursors(),
+                EditorAction::CUT if self.has_secondary_cursors() => self.cut_at_cursors(),
+                EditorAction::PASTE if self.has_secondary_cursors() => {
+                    self.paste_clipboard_at_cursors()
+                }
+                EditorAction::WriteChar(c) if self.has_secondary_cursors() => {
+                    self.write_char_at_cursors(c);
+                    Ok(())
+                }
+
+                EditorAction::PASTE if self.clipboard.kind == ClipboardKind::Block => {
+                    self.paste_block()
+                }
+
+                EditorAction::BACKSPACE if !self.is_text_selected() && self.in_empty_pair() => {
+                    self.delete_pair();
+                    Ok(())
+                }
                 EditorAction::BACKSPACE => {
                     if self.is_text_selected() {
                         self.backspace_text_is_selected();
@@ -260,6 +448,8 @@ impl Editor {
                     Ok(()) => Ok(()),
                     Err(e) => Err(e),
                 },
+                EditorAction::CopyToRegister(name) => self.copy_to_register(name),
+                EditorAction::PasteFromRegister(name) => self.paste_from_register(name),
                 EditorAction::UNDO => match self.undo() {
                     Ok(()) => Ok(()),
                     Err(e) => Err(e),
@@ -268,14 +458,24 @@ impl Editor {
                     Ok(()) => Ok(()),
                     Err(e) => Err(e),
                 },
-                EditorAction::WriteChar(c) => {
-                    if self.is_text_selected() {
-                        self.write_char_text_is_selected(c)
-                    } else {
-                        self.write_char(c)
+                EditorAction::OlderState => self.older_state(),
+                EditorAction::NewerState => self.newer_state(),
+                // typed brackets and quotes close themselves, and typing the closer steps over it
+                EditorAction::WriteChar(c) if !self.is_text_selected() && self.types_over(c) => {
+                    self.type_over();
+                    Ok(())
+                }
+                EditorAction::WriteChar(c) if !self.is_text_selected() => {
+                    match self.auto_closer(c) {
+                        Some(close) => self.write_pair(c, close),
+                        None => self.write_char(c),
                     }
                     Ok(())
                 }
+                EditorAction::WriteChar(c) => {
+                    self.write_char_text_is_selected(c);
+                    Ok(())
+                }
                 _ => Ok(()),
             },
             _ => Ok(()),
@@ -285,9 +485,15 @@ impl Editor {
     //undo manager
     ///undo wrapper function, that calls the UndoRedoManager
     pub fn undo(&mut self) -> Result<(), EditorError> {
+        let undone = self.undo_redo_manager.current();
         match self.undo_redo_manager.undo() {
             Ok(action) => {
+                //secondary cursors and blocks do not follow the text back, so they are dropped
+                self.clear_secondary_cursors();
+                self.clear_block_selection();
                 self.apply_action(&action);
+                let before = self.undo_redo_manager.node(undone).and_then(|n| n.before);
+                self.restore_cursor_state(before);
                 Ok(())
             }
             Err(e) => Err(UndoFailure(e)),
@@ -297,40 +503,75 @@ impl Editor {
     pub fn redo(&mut self) -> Result<(), EditorError> {
         match self.undo_redo_manager.redo() {
             Ok(action) => {
+                self.clear_secondary_cursors();
+                self.clear_block_selection();
                 self.apply_action(&action);
+                let current = self.undo_redo_manager.current();
+                let after = self.undo_redo_manager.node(current).and_then(|n| n.after);
+                self.restore_cursor_state(after);
                 Ok(())
             }
             Err(e) => Err(RedoFailure(e)),
         }
     }
 
+    /// Cursor and selection, as kept around edits in the undo history
+    pub fn cursor_state(&self) -> CursorState {
+        CursorState {
+            cursor: self.cursor_position(),
+            selection: self.text_selection_start.zip(self.text_selection_end),
+        }
+    }
+
+    /// Puts the cursor and selection back as state has them, when known,
+    /// and scrolls the cursor into view
+    pub(super) fn restore_cursor_state(&mut self, state: Option<CursorState>) {
+        if let Some(state) = state {
+            self.set_cursor_position(&state.cursor);
+            let (start, end) = state.selection.unzip();
+            self.text_selection_start = start;
+            self.text_selection_end = end;
+        }
+        self.adjust_view_to_cursor();
+    }
+
+    /// Gives the edits recorded since revision the cursor states around
+    /// them, before being the one from when they started
+    pub fn stamp_cursor_states(&mut self, revision: usize, before: CursorState) {
+        if self.undo_redo_manager.revision() != revision {
+            let after = self.cursor_state();
+            self.undo_redo_manager.set_cursor_states(before, after);
+        }
+    }
+
     /// applies an EditAction
-    fn apply_action(&mut self, action: &EditAction) {
+    pub(super) fn apply_action(&mut self, action: &EditAction) {
         match action {
             EditAction::Insert { pos, c } => {
-                self.insert_char_at(*pos, *c);
+                // creates target line if missing, and clamps x to line length
+                self.editor_content.insert_char(*pos, *c);
                 let additive_pos = CursorPosition { x: pos.x + 1, y: 0 };
                 let end: CursorPosition = *pos + additive_pos;
                 self.set_cursor_position(&end);
             }
             EditAction::Delete { pos, .. } => {
-                self.delete_char_at(*pos);
+                self.editor_content.remove_char(*pos);
                 self.set_cursor_position(pos);
             }
             EditAction::Replace {
                 start, end, new, ..
             } => {
-                self.replace_selection_with_char(*start, *end, *new);
+                self.editor_content.replace_chars(*start, *end, *new);
                 self.set_cursor_position(start);
             }
             EditAction::ReplaceRange {
                 start,
-                end,
+                end: _end,
                 old,
                 new,
             } => {
-                // replace text from start..end with new
-                self.replace_selection_with_lines(*start, *end, old.clone(), new.clone());
+                // replace old text at start with new
+                self.editor_content.replace_text(*start, old, new.clone());
 
                 // Calculate cursor position after replacement
                 if new.is_empty() {
@@ -359,7 +600,8 @@ impl Editor {
                 }
             }
             EditAction::InsertLines { start, lines } => {
-                self.insert_lines_at(*start, lines.clone());
+                let y = start.y.min(self.editor_content.line_count());
+                self.editor_content.insert_lines(y, lines.clone());
                 //get additive position to get new cursor pos at end of insertion
                 let last_line_len = lines.last().map(|s| s.len()).unwrap_or(0);
                 let additive_pos = CursorPosition {
@@ -380,14 +622,15 @@ impl Editor {
                 let end: CursorPosition = *start - negated_pos;
                 self.set_cursor_position(&end);
 
-                self.delete_lines_at(*start, deleted.len());
+                self.editor_content
+                    .remove_lines_from(start.y, deleted.len());
             }
             EditAction::InsertRange {
                 start,
                 end: _end,
                 lines,
             } => {
-                self.insert_text_at(start, lines);
+                self.editor_content.insert_text(*start, lines);
                 //get additive position to get new cursor pos at end of insertion
                 let last_line_len = lines.last().map(|s| s.len()).unwrap_or(0);
                 let additive_pos = CursorPosition {
@@ -402,7 +645,7 @@ impl Editor {
                 end,
                 deleted: _deleted,
             } => {
-                self.delete_text_at_range(start, end);
+                self.editor_content.delete_text(*start, *end);
 
                 self.set_cursor_position(start);
             }
@@ -411,7 +654,7 @@ impl Editor {
                 self.editor_content[pos.y] = left.clone();
 
                 // insert the right part as a new line
-                self.editor_content.insert(pos.y + 1, right.clone());
+                self.editor_content.insert_line(pos.y + 1, right.clone());
 
                 //at start of last line
                 let new_pos = CursorPosition { x: 0, y: pos.y + 1 };
@@ -423,7 +666,7 @@ impl Editor {
                 self.editor_content[pos.y] = merged.clone();
 
                 // remove next line
-                self.editor_content.remove(pos.y + 1);
+                self.editor_content.remove_line(pos.y + 1);
 
                 self.set_cursor_position(&pos);
             }
@@ -454,33 +697,7 @@ impl Editor {
     ///copies text within bound of text selected to copied_text
     pub fn copy_selected_text(&mut self) -> Result<Vec<String>, EditorError> {
         if let (Some(start), Some(end)) = (self.text_selection_start, self.text_selection_end) {
-            let mut selected_text: Vec<String> = Vec::new();
-            let lines = &self.editor_content[start.y..=end.y];
-
-            if lines.len() > 1 {
-                for (y, line) in lines.iter().enumerate() {
-                    let mut line_chars: Vec<char> = line.chars().collect();
-                    let extracted_text: String;
-
-                    //if first line drain all from start x,
-                    // else if last line drain to end .x, else drain all
-                    if y == 0 {
-                        extracted_text = line_chars.drain(start.x..).collect();
-                    } else if y == lines.len() - 1 {
-                        extracted_text = line_chars.drain(..end.x).collect();
-                    } else {
-                        extracted_text = line_chars.into_iter().collect();
-                    }
-
-                    selected_text.push(extracted_text);
-                }
-            } else {
-                let mut line_chars: Vec<char> = self.editor_content[start.y].chars().collect();
-                let extracted_text: String = line_chars.drain(start.x..end.x).collect();
-                selected_text.push(extracted_text);
-            }
-
-            Ok(selected_text)
+            Ok(self.editor_content.text_in_range(start, end))
         } else {
             Err(TextSelectionFailure(TextSelectionError::NoTextSelected))
         }
@@ -505,12 +722,12 @@ impl Editor {
         if let (Some(start), Some(end)) = (self.text_selection_start, self.text_selection_end) {
             let mut selected_text: Vec<String> = Vec::new();
             let mut lines_to_remove: Vec<usize> = Vec::new(); //lines that should be removed
-            let lines = self.editor_content[start.y..=end.y].as_mut();
-            let line_length = lines.len();
-            if lines.len() > 1 {
+            let line_length = end.y - start.y + 1;
+            if line_length > 1 {
                 // Multi-line cut
-                for (y, line) in lines.iter_mut().enumerate() {
-                    let mut line_chars: Vec<char> = line.as_mut().chars().collect();
+                for y in 0..line_length {
+                    let line = &mut self.editor_content[start.y + y];
+                    let mut line_chars: Vec<char> = line.chars().collect();
                     let extracted_text: String;
 
                     //if first line drain all from start x,
@@ -535,7 +752,7 @@ impl Editor {
             } else {
                 // single-line cut
                 let line = &mut self.editor_content[start.y];
-                let mut line_chars: Vec<char> = line.as_mut().chars().collect();
+                let mut line_chars: Vec<char> = line.chars().collect();
                 let extracted_text: String = line_chars.drain(start.x..end.x).collect();
                 selected_text.push(extracted_text);
 
@@ -544,7 +761,7 @@ impl Editor {
 
             // remove fully cut lines, from last to first to avoid index shift
             for &y in lines_to_remove.iter().rev() {
-                self.editor_content.remove(y);
+                self.editor_content.remove_line(y);
             }
 
             //move content of last line selected to first line start point,
@@ -555,9 +772,12 @@ impl Editor {
 
                 // ensure we do not access outside bounds
                 if merged_y < self.editor_content.len() && start.y < self.editor_content.len() {
-                    let line = self.editor_content.remove(merged_y);
+                    let line = self
+                        .editor_content
+                        .remove_line(merged_y)
+                        .unwrap_or_default();
                     if self.editor_content.len() <= start.y {
-                        self.editor_content.push(line);
+                        self.editor_content.push_line(line);
                     } else {
                         self.editor_content[start.y].push_str(&line);
                     }
@@ -595,35 +815,23 @@ impl Editor {
         let insert_y = self.cursor.y as usize;
         let insert_x = self.cursor.x as usize;
 
-        while self.editor_content.len() <= insert_y {
-            self.editor_content.push(String::new());
-        }
+        self.editor_content.ensure_line(insert_y);
 
-        let current_line = &self.editor_content[insert_y];
+        let current_line = &mut self.editor_content[insert_y];
 
-        // Convert the line into a Vec<char> to handle multibyte characters correctly
-        let chars: Vec<char> = current_line.chars().collect();
-        let (before_cursor, after_cursor) = chars.split_at(insert_x.min(chars.len()));
+        // Split on char index to handle multibyte characters correctly
+        let split_idx = char_to_byte_idx(current_line, insert_x);
 
         if copied_text.len() == 1 {
-            // Single-line paste: correctly insert into character-safe split
-            let new_line = format!(
-                "{}{}{}",
-                before_cursor.iter().collect::<String>(),
-                copied_text[0],
-                after_cursor.iter().collect::<String>()
-            );
-            self.editor_content[insert_y] = new_line;
+            // Single-line paste: insert in place at character-safe split
+            current_line.insert_str(split_idx, &copied_text[0]);
         } else {
+            let (before_cursor, after_cursor) = current_line.split_at(split_idx);
             // Multi-line paste
             let mut new_lines = Vec::new();
 
             // First line: insert copied text at cursor position
-            new_lines.push(format!(
-                "{}{}",
-                before_cursor.iter().collect::<String>(),
-                copied_text[0]
-            ));
+            new_lines.push(format!("{}{}", before_cursor, copied_text[0]));
 
             // Middle lines: insert as separate lines
             for line in &copied_text[1..copied_text.len() - 1] {
@@ -632,14 +840,11 @@ impl Editor {
 
             // Last copied line + remainder of the original line
             let last_copied_line = &copied_text[copied_text.len() - 1];
-            new_lines.push(format!(
-                "{}{}",
-                last_copied_line,
-                after_cursor.iter().collect::<String>()
-            ));
+            new_lines.push(format!("{}{}", last_copied_line, after_cursor));
 
             // Replace the current line and insert new lines
-            self.editor_content.splice(insert_y..=insert_y, new_lines);
+            self.editor_content
+                .replace_lines(insert_y..insert_y + 1, new_lines);
         }
 
         let end: CursorPosition = CursorPosition {
@@ -663,23 +868,18 @@ impl Editor {
     ///writes char to y position line, with x position
     pub fn write_char(&mut self, c: char) {
         //creating lines until y position of cursor
-        while self.editor_content.len() <= self.cursor.y as usize {
-            self.editor_content.push(String::new());
-        }
+        self.editor_content.ensure_line(self.cursor.y as usize);
 
         let line = &mut self.editor_content[self.cursor.y as usize];
 
         let char_count = line.chars().count();
         //position cursor to line end in chars count
         if char_count < self.cursor.x as usize {
-            self.cursor.x = char_count as i16;
+            self.cursor.x = char_count as i32;
         }
 
-        let mut line_chars_vec: Vec<char> = line.chars().collect();
-
-        line_chars_vec.insert(self.cursor.x as usize, c);
-
-        *line = line_chars_vec.into_iter().collect();
+        let byte_idx = char_to_byte_idx(line, self.cursor.x as usize);
+        line.insert(byte_idx, c);
         //record undo action (action done)
         self.undo_redo_manager.record_undo(EditAction::Insert {
             pos: CursorPosition {
@@ -697,11 +897,11 @@ impl Editor {
         let mut selected_text: Vec<String> = Vec::new();
         let start = self.text_selection_start.unwrap();
         let end = self.text_selection_end.unwrap();
-        let lines = &mut self.editor_content[start.y..=end.y];
-        let lines_length = lines.len();
+        let lines_length = end.y - start.y + 1;
         if lines_length > 1 {
             let mut line_indexes_to_remove: Vec<u16> = vec![];
-            for (y, line) in lines.iter_mut().enumerate() {
+            for y in 0..lines_length {
+                let line = &mut self.editor_content[start.y + y];
                 let mut line_chars_vec: Vec<char> = line.chars().collect();
                 let deleted_text: String;
                 //first line
@@ -720,13 +920,14 @@ impl Editor {
             }
             // remove the lines that became empty in reverse order
             for &i in line_indexes_to_remove.iter().rev() {
-                self.editor_content.remove(i as usize);
+                self.editor_content.remove_line(i as usize);
             }
             //move content of last line selected to first line start point
-            let line = &mut self
+            let line = self
                 .editor_content
-                .remove(end.y - line_indexes_to_remove.len());
-            self.editor_content[start.y].push_str(line);
+                .remove_line(end.y - line_indexes_to_remove.len())
+                .unwrap_or_default();
+            self.editor_content[start.y].push_str(&line);
         } else {
             let line = &mut self.editor_content[start.y];
             let mut line_chars_vec: Vec<char> = line.chars().collect();
@@ -735,8 +936,8 @@ impl Editor {
             line_chars_vec.insert(start.x, c);
             *line = line_chars_vec.into_iter().collect();
         }
-        self.cursor.x = self.text_selection_start.unwrap().x as i16;
-        self.cursor.y = self.text_selection_start.unwrap().y as i16;
+        self.cursor.x = self.text_selection_start.unwrap().x as i32;
+        self.cursor.y = self.text_selection_start.unwrap().y as i32;
         self.reset_text_selection_cursor();
         self.move_cursor(1, 0);
 
@@ -775,7 +976,7 @@ impl Editor {
                     },
                     lines: vec![indent],
                 });
-                self.move_cursor(len_of_indent as i16, 0);
+                self.move_cursor(len_of_indent as i32, 0);
                 return;
             }
         }
@@ -790,7 +991,7 @@ impl Editor {
         let tab_width = self.editor_config.tab_width;
         if self.editor_config.use_spaces {
             let spaces = " ".repeat(tab_width as usize);
-            line.insert_str(insert_pos, &spaces);
+            line.insert_str(char_to_byte_idx(line, insert_pos), &spaces);
 
             self.undo_redo_manager.record_undo(EditAction::InsertRange {
                 start: CursorPosition {
@@ -803,13 +1004,10 @@ impl Editor {
                 },
                 lines: vec![spaces],
             });
-            self.move_cursor(tab_width as i16, 0);
+            self.move_cursor(tab_width as i32, 0);
         } else {
-            //since \t is special char, make sure to edit line based on chars in it not str
-            //collect chars from line, insert tab char, and collect back to line again
-            let mut line_chars_vec: Vec<char> = line.chars().collect();
-            line_chars_vec.insert(self.cursor.x as usize, '\t');
-            *line = line_chars_vec.into_iter().collect();
+            //insert on char index, so multibyte chars before cursor are respected
+            line.insert(char_to_byte_idx(line, insert_pos), '\t');
 
             self.undo_redo_manager.record_undo(EditAction::Insert {
                 pos: CursorPosition {
@@ -824,7 +1022,7 @@ impl Editor {
     }
 
     /// Get indentation from line: leading whitespaces
-    fn get_line_indent(&self, line: &str) -> String {
+    pub(crate) fn get_line_indent(&self, line: &str) -> String {
         line.chars().take_while(|c| c.is_whitespace()).collect()
     }
 
@@ -832,11 +1030,18 @@ impl Editor {
     //editor enter
     ///handles enter new line, with possible move of text
     pub fn enter(&mut self) {
+        let line = &self.editor_content[self.cursor.y as usize];
+        //carry the indentation over to the new line, splitting and indenting as one undo step
+        let indent = self.indent_after(char_slice(line, 0, self.cursor.x as usize));
+        if !indent.is_empty() {
+            self.enter_with_indent(indent);
+            return;
+        }
         let line = &mut self.editor_content[self.cursor.y as usize];
         //if at end of line len, then just move cursor and make new line, else move text too
-        if self.cursor.x >= line.chars().count() as i16 {
+        if self.cursor.x >= line.chars().count() as i32 {
             self.editor_content
-                .insert(self.cursor.y as usize + 1, String::new());
+                .insert_line(self.cursor.y as usize + 1, String::new());
             //record undo
             self.undo_redo_manager.record_undo(EditAction::InsertLines {
                 start: CursorPosition {
@@ -857,12 +1062,12 @@ impl Editor {
 
             // insert new line under, move cursor and insert split line to line
             self.editor_content
-                .insert(self.cursor.y as usize + 1, String::new());
+                .insert_line(self.cursor.y as usize + 1, String::new());
             self.move_cursor(0, 1);
             self.editor_content[self.cursor.y as usize] = right.clone();
             //enter to split line, should go to start of line
             self.cursor.x = 0;
-            self.visual_cursor_x = self.calculate_visual_x() as i16;
+            self.visual_cursor_x = self.calculate_visual_x() as i32;
             // record undo
             self.undo_redo_manager.record_undo(EditAction::SplitLine {
                 pos: CursorPosition {
@@ -878,39 +1083,28 @@ impl Editor {
     //editor backspace
     ///handles backspace in editor, removes char at y line x position and sets new cursor position
     pub fn backspace(&mut self) {
-        let deleted_char: Option<char>;
         let y = self.cursor.y as usize;
         let x = self.cursor.x as usize;
         let line_char_count = self.editor_content[y].chars().count();
-        //if x is more than 0 and less than max line index : should delete char and move back
+        //if x is more than 0 and less than max line index : should delete grapheme and move back
         // else if y is more than 0, move line up
         if x > 0 && x <= line_char_count {
-            let line = &mut self.editor_content[y];
-            let mut line_chars_vec: Vec<char> = line.chars().collect();
-            let char = line_chars_vec.remove(x - 1);
-            deleted_char = Some(char);
-
-            *line = line_chars_vec.into_iter().collect();
-            self.move_cursor(-1, 0);
-
-            //record undo if any deleted char
-            if let Some(char) = deleted_char {
-                self.undo_redo_manager.record_undo(EditAction::Delete {
-                    pos: CursorPosition {
-                        x: self.cursor.x as usize,
-                        y: self.cursor.y as usize,
-                    },
-                    deleted_char: char.clone(),
-                });
-            }
+            let start = graphemes::prev_boundary(&self.editor_content[y], x);
+            self.remove_chars_on_line(y, start..x);
+            self.cursor.x = start as i32;
+            self.visual_cursor_x = self.calculate_visual_x() as i32;
         } else if y > 0 {
-            let line = &mut self.editor_content.remove(self.cursor.y as usize);
+            let line = self
+                .editor_content
+                .remove_line(self.cursor.y as usize)
+                .unwrap_or_default();
             let new_x_value = self.editor_content[(self.cursor.y - 1) as usize]
                 .chars()
-                .count() as i16;
+                .count() as i32;
             self.cursor.x = new_x_value;
-            self.move_cursor(0, -1);
-            self.editor_content[self.cursor.y as usize].push_str(line);
+            self.cursor.y -= 1;
+            self.visual_cursor_x = self.calculate_visual_x() as i32;
+            self.editor_content[self.cursor.y as usize].push_str(&line);
             let merged_line: String = self.editor_content[self.cursor.y as usize].clone();
             // Record the join action for undo
             self.undo_redo_manager.record_undo(EditAction::JoinLine {
@@ -928,11 +1122,11 @@ impl Editor {
         let mut selected_text: Vec<String> = Vec::new();
         let start = self.text_selection_start.unwrap();
         let end = self.text_selection_end.unwrap();
-        let lines = &mut self.editor_content[start.y..=end.y];
-        let lines_length = lines.len();
+        let lines_length = end.y - start.y + 1;
         if lines_length > 1 {
             let mut line_indexes_to_remove: Vec<u16> = vec![];
-            for (y, line) in lines.iter_mut().enumerate() {
+            for y in 0..lines_length {
+                let line = &mut self.editor_content[start.y + y];
                 let mut line_chars_vec: Vec<char> = line.chars().collect();
                 let deleted_text: String;
                 //first line
@@ -951,13 +1145,14 @@ impl Editor {
             }
             // remove the lines that became empty in reverse order
             for &i in line_indexes_to_remove.iter().rev() {
-                self.editor_content.remove(i as usize);
+                self.editor_content.remove_line(i as usize);
             }
             //move content of last line selected to first line start point
-            let line = &mut self
+            let line = self
                 .editor_content
-                .remove(end.y - line_indexes_to_remove.len());
-            self.editor_content[start.y].push_str(line);
+                .remove_line(end.y - line_indexes_to_remove.len())
+                .unwrap_or_default();
+            self.editor_content[start.y].push_str(&line);
         } else {
             let line = &mut self.editor_content[start.y];
             let mut line_chars_vec: Vec<char> = line.chars().collect();
@@ -966,11 +1161,11 @@ impl Editor {
 
             *line = line_chars_vec.into_iter().collect();
         }
-        self.cursor.x = self.text_selection_start.unwrap().x as i16;
-        self.cursor.y = self.text_selection_start.unwrap().y as i16;
+        self.cursor.x = self.text_selection_start.unwrap().x as i32;
+        self.cursor.y = self.text_selection_start.unwrap().y as i32;
         self.reset_text_selection_cursor();
         //replace visual cursor
-        self.visual_cursor_x = self.calculate_visual_x() as i16;
+        self.visual_cursor_x = self.calculate_visual_x() as i32;
 
         // record undo (DeleteRange)
         self.undo_redo_manager.record_undo(EditAction::DeleteRange {
@@ -984,17 +1179,22 @@ impl Editor {
 
     ///handles DELETE action, of deleting char in editor at x +1 position
     pub(crate) fn delete(&mut self) {
-        let current_line_len = self.editor_content[self.cursor.y as usize].chars().count() as i16;
+        let current_line_len = self.editor_content[self.cursor.y as usize].chars().count() as i32;
 
         if current_line_len == 0 {
             return;
         }
-        //if at line end, move line below up, else if current line length is bigger than current cursor x pos, remove char
-        if self.cursor.x >= current_line_len - 1
+        let line = &self.editor_content[self.cursor.y as usize];
+        let next = graphemes::next_boundary(line, self.cursor.x as usize);
+        //if at line end, move line below up, else if there is a grapheme after the cursor one, remove it
+        if next as i32 >= current_line_len
             && self.editor_content.len() > (self.cursor.y + 1) as usize
         {
-            let line = &mut self.editor_content.remove((self.cursor.y + 1) as usize);
-            self.editor_content[self.cursor.y as usize].push_str(line);
+            let line = self
+                .editor_content
+                .remove_line((self.cursor.y + 1) as usize)
+                .unwrap_or_default();
+            self.editor_content[self.cursor.y as usize].push_str(&line);
             let merged_line: String = self.editor_content[self.cursor.y as usize].clone();
             // Record the join action for undo
             self.undo_redo_manager.record_undo(EditAction::JoinLine {
@@ -1004,20 +1204,50 @@ impl Editor {
                 },
                 merged: merged_line,
             });
-        } else if current_line_len > (self.cursor.x + 1) {
-            let line = &mut self.editor_content[self.cursor.y as usize];
-            let mut line_chars_vec: Vec<char> = line.chars().collect();
-            let char = line_chars_vec.remove(self.cursor.x as usize + 1);
+        } else if current_line_len > next as i32 {
+            let end = graphemes::next_boundary(line, next);
+            self.remove_chars_on_line(self.cursor.y as usize, next..end);
+        }
+    }
 
-            self.undo_redo_manager.record_undo(EditAction::Delete {
-                pos: CursorPosition {
-                    x: self.cursor.x as usize + 1,
-                    y: self.cursor.y as usize,
-                },
-                deleted_char: char.clone(),
-            });
+    /// Removes chars in range from line, recorded as one undo step.
+    /// Removed from the back, so undo puts the cursor after the restored text
+    fn remove_chars_on_line(&mut self, y: usize, range: std::ops::Range<usize>) {
+        let mut deleted = Vec::new();
+        for x in range.rev() {
+            let pos = CursorPosition { x, y };
+            if let Some(deleted_char) = self.editor_content.remove_char(pos) {
+                deleted.push(EditAction::Delete { pos, deleted_char });
+            }
+        }
+        self.record_undo_group(deleted);
+    }
 
-            *line = line_chars_vec.into_iter().collect();
+    /// Replaces text between start and end with new lines, returning the action to record
+    pub(crate) fn replace_range(
+        &mut self,
+        start: CursorPosition,
+        end: CursorPosition,
+        new: Vec<String>,
+    ) -> EditAction {
+        let old = self.editor_content.text_in_range(start, end);
+        self.editor_content.replace_text(start, &old, new.clone());
+        EditAction::ReplaceRange {
+            start,
+            end,
+            old,
+            new,
+        }
+    }
+
+    /// Records actions as one undo step, a Bulk only when there is more than one
+    pub(crate) fn record_undo_group(&mut self, mut actions: Vec<EditAction>) {
+        match actions.len() {
+            0 => {}
+            1 => self.undo_redo_manager.record_undo(actions.remove(0)),
+            _ => self
+                .undo_redo_manager
+                .record_undo(EditAction::Bulk(actions)),
         }
     }
 
@@ -1026,10 +1256,10 @@ impl Editor {
         let mut selected_text: Vec<String> = Vec::new();
         let start = self.text_selection_start.unwrap();
         let end = self.text_selection_end.unwrap();
-        let lines = &mut self.editor_content[start.y..=end.y];
-        let lines_length = lines.len();
+        let lines_length = end.y - start.y + 1;
         if lines_length > 1 {
-            for (y, line) in lines.iter_mut().enumerate() {
+            for y in 0..lines_length {
+                let line = &mut self.editor_content[start.y + y];
                 let mut line_chars_vec: Vec<char> = line.chars().collect();
                 let deleted_text: String;
                 //first line
@@ -1061,11 +1291,11 @@ impl Editor {
             line_chars_vec[start.x..end.x].fill(' ');
             *line = line_chars_vec.into_iter().collect();
         }
-        self.cursor.x = self.text_selection_end.unwrap().x as i16;
-        self.cursor.y = self.text_selection_end.unwrap().y as i16;
+        self.cursor.x = self.text_selection_end.unwrap().x as i32;
+        self.cursor.y = self.text_selection_end.unwrap().y as i32;
         self.reset_text_selection_cursor();
         //replace visual cursor
-        self.visual_cursor_x = self.calculate_visual_x() as i16;
+        self.visual_cursor_x = self.calculate_visual_x() as i32;
 
         let old_replaced_with_whitespaces: Vec<String> = selected_text
             .iter()
@@ -1088,13 +1318,13 @@ impl Editor {
     //editor cursor moving
 
     /// Moves the cursor in relation to editor content
-    pub fn move_cursor(&mut self, x: i16, y: i16) {
+    pub fn move_cursor(&mut self, x: i32, y: i32) {
         if self.cursor.y == 0 && y == -1 {
             return;
         }
         //if wanting to go beyond current length of editor
         while self.editor_content.len() <= (self.cursor.y + y) as usize {
-            self.editor_content.push(String::new());
+            self.editor_content.push_line(String::new());
             //record undo
             self.undo_redo_manager.record_undo(EditAction::InsertLines {
                 start: CursorPosition {
@@ -1107,51 +1337,125 @@ impl Editor {
 
         let max_x_pos = self.editor_content[(self.cursor.y + y) as usize]
             .chars()
-            .count() as i16;
+            .count() as i32;
         //let current_line = &self.editor.editor_content[self.editor.cursor.y as usize];
 
-        // Moving Right →
+        // Moving Right →, a whole grapheme at a time
         if x > 0 && self.cursor.x < max_x_pos {
-            self.cursor.x += x;
+            let line = &self.editor_content[self.cursor.y as usize];
+            let mut next = self.cursor.x as usize;
+            for _ in 0..x {
+                next = graphemes::next_boundary(line, next);
+            }
+            self.cursor.x = next as i32;
         } else if x == 1
-            && self.cursor.x >= self.editor_content[self.cursor.y as usize].chars().count() as i16
+            && self.cursor.x >= self.editor_content[self.cursor.y as usize].chars().count() as i32
             && self.editor_content.len() > self.cursor.y as usize + 1
         {
             //else if end of line and more lines
             self.cursor.y += 1;
             self.cursor.x = 0;
-            self.visual_cursor_x = self.calculate_visual_x() as i16;
+            self.visual_cursor_x = self.calculate_visual_x() as i32;
             return;
         }
 
-        // Moving Left ←
+        // Moving Left ←, a whole grapheme at a time
         if x < 0 && self.cursor.x > 0 {
-            self.cursor.x += x;
+            let line = &self.editor_content[self.cursor.y as usize];
+            let mut prev = self.cursor.x as usize;
+            for _ in x..0 {
+                prev = graphemes::prev_boundary(line, prev);
+            }
+            self.cursor.x = prev as i32;
         } else if self.cursor.x == 0 && x == -1 && self.cursor.y != 0 {
             //else if start of line and more lines
             self.cursor.y -= 1;
-            self.cursor.x = self.editor_content[self.cursor.y as usize].chars().count() as i16;
-            self.visual_cursor_x = self.calculate_visual_x() as i16;
+            self.cursor.x = self.editor_content[self.cursor.y as usize].chars().count() as i32;
+            self.visual_cursor_x = self.calculate_visual_x() as i32;
             return;
         }
 
         /* let (top, bottom) = self.is_cursor_top_or_bottom_of_editor();
         //to offset scroll
         if (y == 1 && bottom) || (y == -1 && top) {
-            self.scroll_offset = (self.scroll_offset + y).clamp(0, i16::MAX);
+            self.scroll_offset = (self.scroll_offset + y).clamp(0, i32::MAX);
             return;
         }*/
 
-        self.cursor.x = self.cursor.x.clamp(0, max_x_pos);
-        self.cursor.y = (self.cursor.y + y).clamp(0, i16::MAX);
-        self.visual_cursor_x = self.calculate_visual_x() as i16;
+        if y != 0 {
+            // keep the display column when moving between lines
+            let tab_width = self.editor_config.tab_width as usize;
+            let col = self
+                .editor_content
+                .get(self.cursor.y as usize)
+                .map(|line| wrap::visual_col(line, self.cursor.x as usize, tab_width))
+                .unwrap_or(0);
+            self.cursor.y = (self.cursor.y + y).clamp(0, i32::MAX);
+            let line = &self.editor_content[self.cursor.y as usize];
+            self.cursor.x = wrap::char_idx_at_visual_col(line, col, tab_width) as i32;
+        } else {
+            self.cursor.x = self.cursor.x.clamp(0, max_x_pos);
+        }
+        self.visual_cursor_x = self.calculate_visual_x() as i32;
+    }
+
+    /// Moves the cursor one step, vertical steps go by visual row when lines are wrapped
+    pub(crate) fn step_cursor(&mut self, x: i32, y: i32) {
+        match self.wrap_width() {
+            Some(wrap_width) if y != 0 => self.move_cursor_visual_row(y, wrap_width),
+            _ => self.move_cursor(x, y),
+        }
+    }
+
+    /// Moves the cursor one visual row up or down within wrapped lines,
+    /// keeping its column in the row where the row is long enough
+    fn move_cursor_visual_row(&mut self, y: i32, wrap_width: usize) {
+        let tab_width = self.editor_config.tab_width as usize;
+        let line = &self.editor_content[self.cursor.y as usize];
+        let (row, col) =
+            wrap::visual_position(line, self.cursor.x as usize, Some(wrap_width), tab_width);
+        let rows = wrap::wrapped_rows(line, wrap_width, tab_width);
+
+        if y > 0 && row + 1 < rows {
+            self.set_cursor_x_at_row_col(row + 1, col, wrap_width);
+        } else if y < 0 && row > 0 {
+            self.set_cursor_x_at_row_col(row - 1, col, wrap_width);
+        } else {
+            // crossing into the next or previous logical line
+            let old_y = self.cursor.y;
+            self.move_cursor(0, y.signum());
+            if self.cursor.y == old_y {
+                return;
+            }
+            let target_row = if y > 0 {
+                0
+            } else {
+                let line = &self.editor_content[self.cursor.y as usize];
+                wrap::wrapped_rows(line, wrap_width, tab_width) - 1
+            };
+            self.set_cursor_x_at_row_col(target_row, col, wrap_width);
+        }
+    }
+
+    /// Puts the cursor on the grapheme drawn at row and column of its wrapped line
+    fn set_cursor_x_at_row_col(&mut self, row: usize, col: usize, wrap_width: usize) {
+        let line = &self.editor_content[self.cursor.y as usize];
+        let tab_width = self.editor_config.tab_width as usize;
+        self.cursor.x =
+            wrap::char_idx_at_position(line, row, col, Some(wrap_width), tab_width) as i32;
+        self.visual_cursor_x = self.calculate_visual_x() as i32;
     }
 
     /// Moves selection cursor
-    pub(crate) fn move_selection_cursor(&mut self, x: i16, y: i16) {
+    pub(crate) fn move_selection_cursor(&mut self, x: i32, y: i32) {
+        self.extend_selection_by(|editor| editor.step_cursor(x, y));
+    }
+
+    /// Moves the cursor with movement, extending the selection to where it ends up
+    pub(crate) fn extend_selection_by(&mut self, movement: impl FnOnce(&mut Editor)) {
         let old_x = self.cursor.x;
         let old_y = self.cursor.y;
-        self.move_cursor(x, y);
+        movement(self);
         let new_x = self.cursor.x;
         let new_y = self.cursor.y;
 
@@ -1176,7 +1480,7 @@ impl Editor {
 
         let (at_start, at_end) = self.is_selection_cursor_start_or_end(old_pos);
 
-        if x > 0 || y > 0 {
+        if new_pos > old_pos {
             // Moving right/down → Extend selection
 
             if at_start && !at_end {
@@ -1189,7 +1493,7 @@ impl Editor {
                 //is at both start and end, should move end
                 self.text_selection_end = Some(new_pos);
             }
-        } else if x < 0 || y < 0 {
+        } else if new_pos < old_pos {
             // Moving left/up → Adjust start instead of resetting
             if at_start && !at_end {
                 //is at start pos and should move start instead of end
@@ -1204,7 +1508,7 @@ impl Editor {
         }
 
         // Check if we are on selection end y and line is empty, if so, move visual x
-        if self.cursor.y == self.text_selection_end.unwrap().y as i16
+        if self.cursor.y == self.text_selection_end.unwrap().y as i32
             && !self.editor_content.is_empty()
             && self.editor_content[self.text_selection_end.unwrap().y].is_empty()
             && self.text_selection_start.unwrap().y != self.text_selection_end.unwrap().y
@@ -1224,17 +1528,17 @@ impl Editor {
 
     //SCROLL
     /// moves scroll offset and config defined scroll amount and scrolloff
-    pub fn move_scroll_offset(&mut self, direction: i16) {
-        let scroll_amount = (self.editor_config.scroll_lines as i16) * direction.signum();
-        let scrolloff = self.editor_config.scrolloff as i16;
-        let last_file_line = (self.editor_content.len() as i16 - 1).max(0);
+    pub fn move_scroll_offset(&mut self, direction: i32) {
+        let scroll_amount = (self.editor_config.scroll_lines as i32) * direction.signum();
+        let scrolloff = self.editor_config.scrolloff as i32;
+        let last_file_line = self.last_line();
 
         // Calculate viewport bounds with bottom margin
-        let viewport_height = self.editor_height as i16;
+        let viewport_height = self.editor_height as i32;
         let max_scroll = self.calculate_max_scroll();
 
         // Calculate cursor position relative to viewport
-        let cursor_viewport_pos = self.cursor.y - self.scroll_offset;
+        let cursor_viewport_pos = self.cursor_viewport_row();
 
         // if direction > 0 = scrolling down
         if direction > 0 {
@@ -1242,14 +1546,12 @@ impl Editor {
             if cursor_viewport_pos >= viewport_height - scrolloff - 1
                 || self.cursor.y == last_file_line
             {
-                let new_cursor_y =
-                    (self.cursor.y + scroll_amount).min(self.editor_content.len() as i16 - 1);
+                let new_cursor_y = (self.cursor.y + scroll_amount).min(last_file_line);
                 self.cursor.y = new_cursor_y;
 
                 self.scroll_offset = (self.scroll_offset + scroll_amount).clamp(0, max_scroll);
             } else {
-                self.cursor.y =
-                    (self.cursor.y + scroll_amount).min(self.editor_content.len() as i16 - 1);
+                self.cursor.y = (self.cursor.y + scroll_amount).min(last_file_line);
             }
         // if direction < 0 = scrolling up
         } else if direction < 0 {
@@ -1268,18 +1570,30 @@ impl Editor {
         // Clamping
 
         self.scroll_offset = self.scroll_offset.clamp(0, max_scroll);
+        if self.wrap_width().is_some() {
+            // wrapped lines can push the cursor below the viewport, keep it on the last line shown
+            while self.cursor.y > self.scroll_offset
+                && self.cursor_viewport_row() >= viewport_height
+            {
+                self.cursor.y -= 1;
+            }
+        }
         self.clamp_cursor_to_line();
     }
 
     /// Sets scroll offset to provided offset
-    pub fn set_scroll_offset(&mut self, offset: i16) {
+    pub fn set_scroll_offset(&mut self, offset: i32) {
         self.scroll_offset = offset;
     }
 
     /// Adjusts view scroll offset to show cursor considering margin and scrolloff
     pub fn adjust_view_to_cursor(&mut self) {
-        let scrolloff = self.editor_config.scrolloff as i16;
-        let viewport_height = self.editor_height as i16;
+        if let Some(wrap_width) = self.wrap_width() {
+            self.adjust_wrapped_view_to_cursor(wrap_width);
+            return;
+        }
+        let scrolloff = self.editor_config.scrolloff as i32;
+        let viewport_height = self.editor_height as i32;
         let cursor_v_pos = self.cursor.y - self.scroll_offset;
 
         if cursor_v_pos < scrolloff {
@@ -1290,44 +1604,117 @@ impl Editor {
         }
     }
 
+    /// Wrap aware version of adjust_view_to_cursor, scroll offset stays a line index
+    /// but the space below it is counted in visual rows
+    fn adjust_wrapped_view_to_cursor(&mut self, wrap_width: usize) {
+        let scrolloff = self.editor_config.scrolloff as usize;
+        let viewport_height = self.editor_height as usize;
+        let tab_width = self.editor_config.tab_width as usize;
+        let cursor_y = self.cursor.y.max(0) as usize;
+
+        if self.cursor.y < self.scroll_offset + scrolloff as i32 {
+            self.scroll_offset = cursor_y.saturating_sub(scrolloff) as i32;
+            return;
+        }
+
+        // every line takes at least one row, so lines further up can never be in view
+        let mut first =
+            (self.scroll_offset.max(0) as usize).max(cursor_y.saturating_sub(viewport_height));
+        let cursor_line = &self.editor_content[cursor_y];
+        let (cursor_row, _) = wrap::visual_position(
+            cursor_line,
+            self.cursor.x as usize,
+            Some(wrap_width),
+            tab_width,
+        );
+        let mut rows = (first..cursor_y)
+            .map(|y| wrap::wrapped_rows(&self.editor_content[y], wrap_width, tab_width))
+            .sum::<usize>()
+            + cursor_row
+            + 1
+            + scrolloff;
+        while rows > viewport_height && first < cursor_y {
+            rows -= wrap::wrapped_rows(&self.editor_content[first], wrap_width, tab_width);
+            first += 1;
+        }
+        self.scroll_offset = first as i32;
+    }
+
+    /// Width lines are wrapped at, None when wrapping is off or the viewport is unknown
+    pub fn wrap_width(&self) -> Option<usize> {
+        (self.editor_config.wrap_lines && self.editor_width > 0)
+            .then_some(self.editor_width as usize)
+    }
+
+    /// Row of the cursor counted from the top of the viewport, in visual rows when wrapping
+    pub fn cursor_viewport_row(&self) -> i32 {
+        let Some(wrap_width) = self.wrap_width() else {
+            return self.cursor.y - self.scroll_offset;
+        };
+        let tab_width = self.editor_config.tab_width as usize;
+        let first = self.scroll_offset.max(0) as usize;
+        let cursor_y = self.cursor.y.max(0) as usize;
+        if cursor_y < first || cursor_y - first > self.editor_height as usize {
+            // every line takes at least a row, exact count does not matter out of view
+            return self.cursor.y - self.scroll_offset;
+        }
+        let rows_above: usize = (first..cursor_y)
+            .filter_map(|y| self.editor_content.get(y))
+            .map(|line| wrap::wrapped_rows(line, wrap_width, tab_width))
+            .sum();
+        let (cursor_row, _) = self.cursor_wrapped_position(wrap_width);
+        (rows_above + cursor_row).min(i32::MAX as usize) as i32
+    }
+
+    /// Column of the cursor in the viewport, within its visual row when wrapping
+    pub fn cursor_viewport_col(&self) -> i32 {
+        let Some(wrap_width) = self.wrap_width() else {
+            return self.visual_cursor_x;
+        };
+        self.cursor_wrapped_position(wrap_width).1 as i32
+    }
+
+    /// Row within its line and column of the cursor when wrapped at width
+    fn cursor_wrapped_position(&self, wrap_width: usize) -> (usize, usize) {
+        let tab_width = self.editor_config.tab_width as usize;
+        self.editor_content
+            .get(self.cursor.y.max(0) as usize)
+            .map(|line| {
+                wrap::visual_position(line, self.cursor.x as usize, Some(wrap_width), tab_width)
+            })
+            .unwrap_or((0, 0))
+    }
+
     /// Calculate the maximum scroll offset with bottom margin
-    fn calculate_max_scroll(&self) -> i16 {
-        let viewport_height = self.editor_height as i16;
-        let content_height = self.editor_content.len() as i16;
-        let bottom_margin = self.editor_config.scroll_margin_bottom as i16;
+    pub(crate) fn calculate_max_scroll(&self) -> i32 {
+        let viewport_height = self.editor_height as i32;
+        let content_height = to_coord(self.editor_content.len());
+        let bottom_margin = self.editor_config.scroll_margin_bottom as i32;
 
         // Maximum scroll is content height minus viewport height, plus bottom margin
         // This allows scrolling past the end to show empty space
-        (content_height - viewport_height + bottom_margin).max(0)
+        content_height
+            .saturating_sub(viewport_height)
+            .saturating_add(bottom_margin)
+            .max(0)
     }
 
     /// Ensure cursor X is within the current line bounds
     fn clamp_cursor_to_line(&mut self) {
         if self.cursor.y >= 0 && (self.cursor.y as usize) < self.editor_content.len() {
-            let line_len = self.editor_content[self.cursor.y as usize].len() as i16;
-            self.cursor.x = self.cursor.x.min(line_len);
+            let line = &self.editor_content[self.cursor.y as usize];
+            self.cursor.x = graphemes::floor_boundary(line, self.cursor.x.max(0) as usize) as i32;
         }
     }
 
     ///calculates the visual position of the cursor
-    fn calculate_visual_x(&mut self) -> usize {
+    pub(crate) fn calculate_visual_x(&mut self) -> usize {
         let line = &self.editor_content[self.cursor.y as usize];
-        let cursor_x = self.cursor.x as usize;
-        let tab_width = self.editor_config.tab_width as usize;
-        let mut visual_x = 0;
-        for (i, c) in line.chars().enumerate() {
-            if i == cursor_x {
-                break;
-            }
-
-            if c == '\t' {
-                visual_x += tab_width - (visual_x % tab_width);
-            } else {
-                visual_x += 1;
-            }
-        }
-
-        visual_x
+        wrap::visual_col(
+            line,
+            self.cursor.x as usize,
+            self.editor_config.tab_width as usize,
+        )
     }
 }
 
@@ -1339,6 +1726,11 @@ impl Editor {
 //╚═╝  ╚═╝╚══════╝╚══════╝╚═╝     ╚══════╝╚═╝  ╚═╝╚══════╝
 
 impl Editor {
+    /// Index of the last line as a cursor coordinate, 0 when empty
+    pub(crate) fn last_line(&self) -> i32 {
+        to_coord(self.editor_content.len().saturating_sub(1))
+    }
+
     ///function to check if some text is selected
     fn is_text_selected(&self) -> bool {
         self.text_selection_start.is_some() && self.text_selection_end.is_some()
@@ -1348,16 +1740,15 @@ impl Editor {
     pub(crate) fn set_cursor_position(&mut self, pos: &CursorPosition) {
         //clamp set position to at maximum go to last position available
         //y is len of editor -1
-        self.cursor.y = (pos.y as i16).clamp(0, self.editor_content.len() as i16 - 1);
-        //length of cursor.y line , aka nr. of chars on line.
-        let line_len = self
+        self.cursor.y = to_coord(pos.y.min(self.editor_content.len().saturating_sub(1)));
+        //clamp x to line length in chars, never inside a grapheme cluster
+        self.cursor.x = self
             .editor_content
             .get(self.cursor.y as usize)
-            .map(|s| s.len())
-            .unwrap_or(0);
-        self.cursor.x = (pos.x as i16).clamp(0, line_len as i16);
+            .map(|line| graphemes::floor_boundary(line, pos.x))
+            .unwrap_or(0) as i32;
         //calculate visual x pos again.
-        self.visual_cursor_x = self.calculate_visual_x() as i16;
+        self.visual_cursor_x = self.calculate_visual_x() as i32;
     }
 
     /// resets text selection cursor to none
@@ -1365,296 +1756,6 @@ impl Editor {
         self.text_selection_start = None;
         self.text_selection_end = None;
     }
-    /// Insert a character at the specified position (buffer-only: does not touch undo/redo, does not move main cursor)
-    pub(crate) fn insert_char_at(&mut self, pos: CursorPosition, c: char) {
-        // Ensure the target line exists
-        while self.editor_content.len() <= pos.y {
-            self.editor_content.push(String::new());
-        }
-        let line = &mut self.editor_content[pos.y];
-        let mut chars: Vec<char> = line.chars().collect();
-        // Clamp to actual line length
-        let x = pos.x.min(chars.len());
-        chars.insert(x, c);
-        *line = chars.into_iter().collect();
-        // cursor not updated
-    }
-
-    /// Delete a character at the specified position (buffer-only)
-    pub(crate) fn delete_char_at(&mut self, pos: CursorPosition) {
-        if let Some(line) = self.editor_content.get_mut(pos.y) {
-            let mut chars: Vec<char> = line.chars().collect();
-            if pos.x < chars.len() {
-                chars.remove(pos.x);
-                *line = chars.into_iter().collect();
-            }
-        }
-    }
-
-    /// Replace a text selection (from start to end) with new char
-    pub(crate) fn replace_selection_with_char(
-        &mut self,
-        start: CursorPosition,
-        end: CursorPosition,
-        new: char,
-    ) {
-        if start.y == end.y {
-            if let Some(line) = self.editor_content.get_mut(start.y) {
-                let mut chars: Vec<char> = line.chars().collect();
-                // Clamp positions
-                let start_x = start.x.min(chars.len());
-                let end_x = end.x.min(chars.len());
-                chars.drain(start_x..end_x);
-                chars.insert(start_x, new);
-                *line = chars.into_iter().collect();
-            }
-        }
-    }
-
-    /// Replace a selection with lines of String
-    fn replace_selection_with_lines(
-        &mut self,
-        start: CursorPosition,
-        _end: CursorPosition,
-        old_lines: Vec<String>,
-        new_lines: Vec<String>,
-    ) {
-        // Safety check for empty document
-        if self.editor_content.is_empty() {
-            if !new_lines.is_empty() {
-                self.editor_content = new_lines;
-            } else {
-                self.editor_content.push(String::new());
-            }
-            return;
-        }
-
-        // Clamp to valid indices
-        let max_index = self.editor_content.len().saturating_sub(1);
-        let start_y = start.y.min(max_index);
-        //let end_y = end.y.min(max_index);
-
-        // UTF-8 safe string splitter
-        let split_line = |line: &str, index: usize| -> (String, String) {
-            let chars: Vec<char> = line.chars().collect();
-            let safe_index = index.min(chars.len());
-            let (left, right) = chars.split_at(safe_index);
-            (left.iter().collect(), right.iter().collect())
-        };
-
-        // === USE OLD CONTENT TO DETERMINE CONTEXTS ===
-
-        let left_context: String;
-        let right_context: String;
-
-        if old_lines.is_empty() {
-            // No old content - this is an insertion
-            // Get contexts from current editor content
-            let line = &self.editor_content[start_y];
-            let (left, right) = split_line(line, start.x);
-            left_context = left;
-            right_context = right;
-        } else if old_lines.len() == 1 {
-            // Single line old content
-            let current_line = &self.editor_content[start_y];
-            let (left, _) = split_line(current_line, start.x);
-            left_context = left;
-
-            // Right context: current line after (start.x + old_line.len())
-            let old_len = old_lines[0].chars().count();
-            let (_, right) = split_line(current_line, start.x + old_len);
-            right_context = right;
-        } else {
-            // Multi-line old content
-            let first_line = &self.editor_content[start_y];
-            let (left, _) = split_line(first_line, start.x);
-            left_context = left;
-
-            // Right context from end line
-            let last_old_len = old_lines.last().map(|s| s.chars().count()).unwrap_or(0);
-            let end_line_idx = start_y + old_lines.len() - 1;
-            if end_line_idx < self.editor_content.len() {
-                let end_line = &self.editor_content[end_line_idx];
-                let (_, right) = split_line(end_line, last_old_len);
-                right_context = right;
-            } else {
-                right_context = String::new();
-            }
-        }
-
-        // Build the replacement lines
-        let mut result = Vec::new();
-
-        if new_lines.is_empty() {
-            // Pure deletion - merge contexts
-            result.push(format!("{}{}", left_context, right_context));
-        } else if new_lines.len() == 1 {
-            // Single line replacement
-            result.push(format!("{}{}{}", left_context, new_lines[0], right_context));
-        } else {
-            // Multi-line replacement
-            result.push(format!("{}{}", left_context, new_lines[0]));
-
-            for i in 1..new_lines.len() - 1 {
-                result.push(new_lines[i].clone());
-            }
-
-            result.push(format!(
-                "{}{}",
-                new_lines[new_lines.len() - 1],
-                right_context
-            ));
-        }
-
-        // Calculate how many lines to remove
-        let lines_to_remove = if old_lines.is_empty() {
-            // Insertion: split one line into multiple
-            1
-        } else {
-            // Replacement: remove old_lines.len() lines (or the span from start_y to calculated end)
-            old_lines.len()
-        };
-
-        // Remove the old lines (in reverse to avoid index issues)
-        let end_remove = (start_y + lines_to_remove - 1).min(self.editor_content.len() - 1);
-        for y in (start_y..=end_remove).rev() {
-            if y < self.editor_content.len() {
-                self.editor_content.remove(y);
-            }
-        }
-
-        // Insert the new lines
-        for (i, line) in result.into_iter().enumerate() {
-            self.editor_content.insert(start_y + i, line);
-        }
-    }
-
-    ///insert text lines at position without just inserting as lines,
-    ///but if necessary, between already present text
-    fn insert_text_at(&mut self, start: &CursorPosition, lines: &Vec<String>) {
-        if lines.is_empty() {
-            return;
-        }
-
-        // Ensure there's at least one line at start.y
-        if start.y >= self.editor_content.len() {
-            self.editor_content.resize(start.y + 1, String::new());
-        }
-
-        let original_line = self.editor_content[start.y].clone();
-
-        // Special case: multi-line insert at column 0
-        if lines.len() > 1 && start.x == 0 {
-            for (i, s) in lines.iter().enumerate() {
-                self.editor_content.insert(start.y + i, s.clone());
-            }
-            return;
-        }
-
-        // Split the original line
-        let mut chars: Vec<char> = original_line.chars().collect();
-        let x = start.x.min(chars.len());
-        let first_line_right_half: String = chars.split_off(x).into_iter().collect();
-        let first_line_left_half: String = chars.into_iter().collect();
-
-        if lines.len() == 1 {
-            // Single-line insert: left + insert + right
-            self.editor_content[start.y] = format!(
-                "{}{}{}",
-                first_line_left_half, lines[0], first_line_right_half
-            );
-        } else {
-            // Multi-line insert
-
-            // Replace current line with left + first inserted line
-            self.editor_content[start.y] = format!("{}{}", first_line_left_half, lines[0]);
-
-            // Insert all remaining lines, merging the right half onto the last one
-            let n_lines = lines.len();
-            for i in 1..n_lines {
-                let insertion_index = start.y + i;
-                let line_to_insert = lines[i].clone();
-
-                // if last line being inserted
-                if i == n_lines - 1 {
-                    // append the right half of the split original line
-                    let final_content = format!("{}{}", line_to_insert, first_line_right_half);
-                    self.editor_content.insert(insertion_index, final_content);
-                } else {
-                    // middle lines, insert directly
-                    self.editor_content.insert(insertion_index, line_to_insert);
-                }
-            }
-        }
-    }
-
-    ///delete text lines at start to end,
-    fn delete_text_at_range(&mut self, start: &CursorPosition, end: &CursorPosition) {
-        if start.y == end.y {
-            // deleting within a single line
-            if let Some(line) = self.editor_content.get_mut(start.y) {
-                let mut chars: Vec<char> = line.chars().collect();
-                let range_start = start.x.min(chars.len());
-                let range_end = end.x.min(chars.len());
-                if range_start < range_end {
-                    chars.drain(range_start..range_end);
-                    *line = chars.into_iter().collect();
-                }
-            }
-        } else {
-            // deleting across multiple lines
-
-            // get suffix of last line
-            let suffix = if let Some(last_line) = self.editor_content.get(end.y) {
-                last_line.chars().skip(end.x).collect::<String>()
-            } else {
-                String::new()
-            };
-
-            if start.x == 0 {
-                // delete the first line entirely
-                self.editor_content.drain(start.y..=end.y);
-                if !suffix.is_empty() {
-                    self.editor_content.insert(start.y, suffix);
-                }
-            } else {
-                // truncate first line
-                if let Some(first_line) = self.editor_content.get_mut(start.y) {
-                    let mut first_chars: Vec<char> = first_line.chars().collect();
-                    first_chars.truncate(start.x);
-                    *first_line = first_chars.into_iter().collect();
-                    // append suffix from last line
-                    first_line.push_str(&suffix);
-                }
-
-                // remove all lines in between start.y and end.y (excluding start.y)
-                if end.y > start.y {
-                    self.editor_content.drain(start.y + 1..=end.y);
-                }
-            }
-        }
-    }
-
-    /// Insert multiple lines at a position
-    pub(crate) fn insert_lines_at(&mut self, start: CursorPosition, lines: Vec<String>) {
-        //calculate start y to insert
-        let y = start.y.min(self.editor_content.len());
-        for (i, line) in lines.into_iter().enumerate() {
-            self.editor_content.insert(y + i, line);
-        }
-    }
-
-    /// Delete lines starting at a position
-    pub(crate) fn delete_lines_at(&mut self, start: CursorPosition, count: usize) {
-        let y = start.y;
-        for _ in 0..count {
-            if y < self.editor_content.len() {
-                self.editor_content.remove(y);
-            } else {
-                break;
-            }
-        }
-    }
 }
 
 impl Default for Editor {
@@ -1678,7 +1779,7 @@ mod unit_editor_write_tests {
     fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
         let config = Config::default();
         let mut editor = Editor::new(Arc::new(config.editor));
-        editor.editor_content = vec;
+        editor.editor_content = vec.into();
         editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
         editor
     }
@@ -1811,7 +1912,7 @@ mod unit_editor_write_tests {
 
         assert_eq!(editor.cursor.y, 0); // Cursor should stay on line
         assert_eq!(editor.editor_content.len(), 1); // New line added
-        assert_eq!(editor.visual_cursor_x, config.editor.tab_width as i16);
+        assert_eq!(editor.visual_cursor_x, config.editor.tab_width as i32);
     }
 
     #[test]
@@ -1822,7 +1923,7 @@ mod unit_editor_write_tests {
 
         assert_eq!(editor.cursor.y, 0); // Cursor should stay on line
         assert_eq!(editor.editor_content.len(), 1); // New line added
-        assert_eq!(editor.visual_cursor_x, config.editor.tab_width as i16);
+        assert_eq!(editor.visual_cursor_x, config.editor.tab_width as i32);
     }
 
     #[test]
@@ -1850,7 +1951,7 @@ mod unit_editor_write_tests {
         assert_eq!(editor.visual_cursor_x, 4);
         editor.move_cursor(10, 0); //move to end
         assert_eq!(editor.editor_content[0].chars().count(), 5); //should contain special plus \t char
-        assert_eq!(editor.visual_cursor_x, 6); //at end of line should be 6
+        assert_eq!(editor.visual_cursor_x, 7); //at end of line should be 7, as 😎 is 2 wide
     }
 
     //ENTER in editor
@@ -1858,7 +1959,7 @@ mod unit_editor_write_tests {
     #[test]
     fn test_enter_in_editor_at_end_of_line() {
         let mut editor = create_editor_with_editor_content(vec!["Hello World".to_string()]);
-        editor.cursor.x = editor.editor_content[0].len() as i16; // Set cursor to end of line
+        editor.cursor.x = editor.editor_content[0].len() as i32; // Set cursor to end of line
         editor.enter();
 
         assert_eq!(editor.cursor.y, 1); // Cursor should move to the next line
@@ -1885,7 +1986,7 @@ mod unit_editor_delete_tests {
 
     fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
         let mut editor = Editor::new(Arc::new(Config::default().editor));
-        editor.editor_content = vec;
+        editor.editor_content = vec.into();
         editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
         editor
     }
@@ -2083,6 +2184,28 @@ mod unit_editor_delete_tests {
         assert_eq!(editor.cursor.x, 1);
     }
 
+    #[test]
+    fn test_backspace_in_editor_removes_whole_grapheme() {
+        // e followed by combining acute accent is one grapheme of two chars
+        let mut editor = create_editor_with_editor_content(vec!["ae\u{301}b".to_string()]);
+        editor.cursor.x = 3;
+        editor.backspace();
+        assert_eq!(editor.editor_content[0], "ab");
+        assert_eq!(editor.cursor.x, 1);
+
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content[0], "ae\u{301}b");
+    }
+
+    #[test]
+    fn test_delete_in_editor_removes_whole_grapheme() {
+        let mut editor = create_editor_with_editor_content(vec!["ae\u{301}b".to_string()]);
+        editor.cursor.x = 0;
+        editor.delete();
+        assert_eq!(editor.editor_content[0], "ab");
+        assert_eq!(editor.cursor.x, 0);
+    }
+
     //TEXT IS SELECTED
 
     #[test]
@@ -2227,7 +2350,7 @@ mod unit_editor_cursor_tests {
 
     fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
         let mut editor = Editor::new(Arc::new(Config::default().editor));
-        editor.editor_content = vec;
+        editor.editor_content = vec.into();
         editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
         editor
     }
@@ -2395,6 +2518,104 @@ mod unit_editor_cursor_tests {
         assert_eq!(editor.text_selection_end.unwrap().x, 3);
         assert_eq!(editor.text_selection_end.unwrap().y, 0);
     }
+    #[test]
+    fn test_cursor_moves_over_grapheme_clusters() {
+        let mut editor = create_editor_with_editor_content(vec!["e\u{301}👍🏽x".to_string()]);
+        editor.move_cursor(1, 0);
+        assert_eq!(editor.cursor.x, 2);
+        editor.move_cursor(1, 0);
+        assert_eq!(editor.cursor.x, 4);
+        assert_eq!(editor.visual_cursor_x, 3);
+        editor.move_cursor(-1, 0);
+        assert_eq!(editor.cursor.x, 2);
+    }
+
+    #[test]
+    fn test_cursor_visual_x_uses_display_width() {
+        let mut editor = create_editor_with_editor_content(vec!["漢字ab".to_string()]);
+        editor.move_cursor(2, 0);
+        assert_eq!(editor.cursor.x, 2);
+        assert_eq!(editor.visual_cursor_x, 4);
+    }
+
+    #[test]
+    fn test_cursor_vertical_move_keeps_display_column() {
+        let mut editor =
+            create_editor_with_editor_content(vec!["abcd".to_string(), "漢字".to_string()]);
+        editor.move_cursor(2, 0);
+        editor.move_cursor(0, 1);
+        assert_eq!(editor.cursor.x, 1);
+        assert_eq!(editor.visual_cursor_x, 2);
+    }
+
+    #[test]
+    fn test_set_cursor_position_never_splits_grapheme() {
+        let mut editor = create_editor_with_editor_content(vec!["ae\u{301}".to_string()]);
+        editor.set_cursor_position(&CursorPosition { x: 2, y: 0 });
+        assert_eq!(editor.cursor.x, 1);
+    }
+
+    fn create_wrapping_editor(vec: Vec<String>, width: i32) -> Editor {
+        let config = EditorConfig {
+            wrap_lines: true,
+            scrolloff: 0,
+            ..Config::default().editor
+        };
+        let mut editor = Editor::new(Arc::new(config));
+        editor.editor_content = vec.into();
+        editor.editor_height = 4;
+        editor.editor_width = width;
+        editor
+    }
+
+    #[test]
+    fn test_wrapped_cursor_moves_by_visual_row() {
+        let mut editor =
+            create_wrapping_editor(vec!["abcdefghij".to_string(), "xy".to_string()], 4);
+        editor.move_cursor(1, 0);
+
+        editor.step_cursor(0, 1);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (5, 0));
+        editor.step_cursor(0, 1);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (9, 0));
+        editor.step_cursor(0, 1);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (1, 1));
+
+        editor.step_cursor(0, -1);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (9, 0));
+        assert_eq!(editor.cursor_viewport_row(), 2);
+        assert_eq!(editor.cursor_viewport_col(), 1);
+    }
+
+    #[test]
+    fn test_wrapped_cursor_row_col_clamps_to_short_last_row() {
+        let mut editor = create_wrapping_editor(vec!["abcdef".to_string(), "xyz".to_string()], 4);
+        editor.set_cursor_position(&CursorPosition { x: 3, y: 1 });
+
+        editor.step_cursor(0, -1);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (6, 0));
+    }
+
+    #[test]
+    fn test_wrapped_view_scrolls_by_visual_rows() {
+        let mut editor = create_wrapping_editor(
+            vec![
+                "aaaaaaaa".to_string(),
+                "b".to_string(),
+                "cccccccc".to_string(),
+            ],
+            4,
+        );
+        // first line takes three rows, so the cursor on the third line is out of view
+        editor.set_cursor_position(&CursorPosition { x: 0, y: 2 });
+        editor.adjust_view_to_cursor();
+        assert_eq!(editor.scroll_offset, 1);
+        assert_eq!(editor.cursor_viewport_row(), 1);
+
+        editor.set_cursor_position(&CursorPosition { x: 0, y: 0 });
+        editor.adjust_view_to_cursor();
+        assert_eq!(editor.scroll_offset, 0);
+    }
 }
 #[cfg(test)]
 mod unit_editor_cutcopy_tests {
@@ -2405,7 +2626,7 @@ mod unit_editor_cutcopy_tests {
 
     fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
         let mut editor = Editor::new(Arc::new(Config::default().editor));
-        editor.editor_content = vec;
+        editor.editor_content = vec.into();
         editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
         editor
     }
@@ -2686,13 +2907,20 @@ mod unit_editor_undoredo_tests {
     use super::super::super::cursor::CursorPosition;
     use super::super::editor::EditAction;
     use super::super::editor::Editor;
-    use crate::config::Config;
+    use super::super::text_buffer::{TextBuffer, TextStorage};
+    use crate::config::{Config, EditorConfig};
+    use crate::input::actions::{EditorAction, InputAction};
     use std::sync::Arc;
 
     //init functions
     fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
-        let mut editor = Editor::new(Arc::new(Config::default().editor));
-        editor.editor_content = vec;
+        // every char typed is undone on its own, typing isn't coalesced
+        let config = EditorConfig {
+            undo_group_idle_ms: 0,
+            ..Config::default().editor
+        };
+        let mut editor = Editor::new(Arc::new(config));
+        editor.editor_content = vec.into();
         editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
         editor
     }
@@ -2811,7 +3039,7 @@ mod unit_editor_undoredo_tests {
                 start: pos,
                 lines: lines.clone(),
             });
-        editor.editor_content.splice(1..1, lines.clone());
+        editor.editor_content.insert_lines(1, lines.clone());
         assert_eq!(editor.editor_content, vec!["zero", "one", "two", "three"]);
         editor.undo().unwrap();
         assert_eq!(editor.editor_content, vec!["zero", "three"]);
@@ -2832,7 +3060,7 @@ mod unit_editor_undoredo_tests {
                 start: pos_start,
                 lines: start_lines.clone(),
             });
-        editor.editor_content.splice(0..0, start_lines.clone());
+        editor.editor_content.insert_lines(0, start_lines.clone());
         // Insert at end
         let pos_end = CursorPosition { x: 0, y: 3 };
         editor
@@ -2841,7 +3069,7 @@ mod unit_editor_undoredo_tests {
                 start: pos_end,
                 lines: end_lines.clone(),
             });
-        editor.editor_content.splice(3..3, end_lines.clone());
+        editor.editor_content.insert_lines(3, end_lines.clone());
         assert_eq!(editor.editor_content, vec!["a", "b", "mid", "x", "y"]);
         editor.undo().unwrap();
         assert_eq!(editor.editor_content, vec!["a", "b", "mid"]);
@@ -2872,7 +3100,7 @@ mod unit_editor_undoredo_tests {
                 start: pos,
                 deleted: removed.clone(),
             });
-        editor.editor_content.drain(1..4);
+        editor.editor_content.remove_lines(1..4);
         assert_eq!(editor.editor_content, vec!["a", "e"]);
         editor.undo().unwrap();
         assert_eq!(editor.editor_content, vec!["a", "b", "c", "d", "e"]);
@@ -2887,7 +3115,7 @@ mod unit_editor_undoredo_tests {
             "2".to_string(),
             "3".to_string(),
         ]);
-        let removed = editor.editor_content.clone();
+        let removed = editor.editor_content.to_vec();
         let pos = CursorPosition { x: 0, y: 0 };
         editor
             .undo_redo_manager
@@ -2896,7 +3124,7 @@ mod unit_editor_undoredo_tests {
                 deleted: removed.clone(),
             });
 
-        editor.editor_content.clear();
+        editor.editor_content = TextBuffer::new();
         assert_eq!(editor.editor_content, Vec::<String>::new());
         editor.undo().unwrap();
         assert_eq!(editor.editor_content, vec!["1", "2", "3"]);
@@ -3019,7 +3247,7 @@ mod unit_editor_undoredo_tests {
             });
 
         // apply manually: "abc", "def", "ghi" => delete selection => "ai"
-        editor.editor_content = vec!["ai".to_string()];
+        editor.editor_content = vec!["ai".to_string()].into();
         assert_eq!(editor.editor_content, vec!["ai"]);
 
         editor.undo().unwrap();
@@ -3047,7 +3275,7 @@ mod unit_editor_undoredo_tests {
 
         // Apply manually
         editor.editor_content[0] = left.clone();
-        editor.editor_content.insert(1, right.clone());
+        editor.editor_content.insert_line(1, right.clone());
 
         assert_eq!(editor.editor_content, vec!["hello", " world"]);
 
@@ -3074,7 +3302,7 @@ mod unit_editor_undoredo_tests {
         });
 
         editor.editor_content[0] = left.clone();
-        editor.editor_content.insert(1, right.clone());
+        editor.editor_content.insert_line(1, right.clone());
 
         assert_eq!(editor.editor_content, vec!["", "abc"]);
 
@@ -3099,7 +3327,7 @@ mod unit_editor_undoredo_tests {
         });
 
         editor.editor_content[0] = left.clone();
-        editor.editor_content.insert(1, right.clone());
+        editor.editor_content.insert_line(1, right.clone());
 
         assert_eq!(editor.editor_content, vec!["abc", ""]);
 
@@ -3123,7 +3351,7 @@ mod unit_editor_undoredo_tests {
         });
 
         editor.editor_content[0] = merged.clone();
-        editor.editor_content.remove(1);
+        editor.editor_content.remove_line(1);
 
         assert_eq!(editor.editor_content, vec!["foobar"]);
 
@@ -3150,7 +3378,7 @@ mod unit_editor_undoredo_tests {
         });
 
         editor.editor_content[0] = left.clone();
-        editor.editor_content.insert(1, right.clone());
+        editor.editor_content.insert_line(1, right.clone());
 
         assert_eq!(editor.editor_content, vec!["", ""]);
 
@@ -3173,7 +3401,7 @@ mod unit_editor_undoredo_tests {
         });
 
         editor.editor_content[0] = merged.clone();
-        editor.editor_content.remove(1);
+        editor.editor_content.remove_line(1);
 
         assert_eq!(editor.editor_content, vec![""]);
 
@@ -3199,7 +3427,7 @@ mod unit_editor_undoredo_tests {
         });
 
         editor.editor_content[1] = left.clone();
-        editor.editor_content.insert(2, right.clone());
+        editor.editor_content.insert_line(2, right.clone());
 
         assert_eq!(editor.editor_content, vec!["first", "sec", "ond"]);
 
@@ -3223,7 +3451,7 @@ mod unit_editor_undoredo_tests {
         });
 
         editor.editor_content[0] = merged.clone();
-        editor.editor_content.remove(1);
+        editor.editor_content.remove_line(1);
 
         assert_eq!(editor.editor_content, vec!["foo  bar"]);
 
@@ -3248,7 +3476,7 @@ mod unit_editor_undoredo_tests {
             right: right.clone(),
         });
         editor.editor_content[0] = left.clone();
-        editor.editor_content.insert(1, right.clone());
+        editor.editor_content.insert_line(1, right.clone());
 
         // Join immediately after
         let join_pos = CursorPosition { x: 1, y: 0 };
@@ -3258,7 +3486,7 @@ mod unit_editor_undoredo_tests {
             merged: merged.clone(),
         });
         editor.editor_content[0] = merged.clone();
-        editor.editor_content.remove(1);
+        editor.editor_content.remove_line(1);
 
         assert_eq!(editor.editor_content, vec!["abc"]);
 
@@ -3310,4 +3538,52 @@ mod unit_editor_undoredo_tests {
         editor.redo().unwrap();
         assert_eq!(editor.editor_content[0], "ab");
     }
+
+    // ========== Cursor state ==========
+    #[test]
+    fn undo_redo_restore_cursor_and_scroll_to_change() {
+        let lines = (0..60).map(|i| format!("line {}", i)).collect();
+        let mut editor = create_editor_with_editor_content(lines);
+        editor.cursor.y = 40;
+        editor.cursor.x = 4;
+        editor
+            .handle_input_action(InputAction::Editor(EditorAction::WriteChar('!')))
+            .unwrap();
+
+        // wander off to the top of the file
+        editor.cursor.y = 0;
+        editor.cursor.x = 0;
+        editor.scroll_offset = 0;
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content[40], "line 40");
+        assert_eq!((editor.cursor.x, editor.cursor.y), (4, 40));
+        assert!(editor.scroll_offset > 30);
+
+        editor.cursor.y = 0;
+        editor.scroll_offset = 0;
+        editor.redo().unwrap();
+        assert_eq!((editor.cursor.x, editor.cursor.y), (5, 40));
+        assert!(editor.scroll_offset > 30);
+    }
+
+    #[test]
+    fn undo_restores_selection_that_was_cut() {
+        let mut editor = create_editor_with_editor_content(vec!["one two".to_string()]);
+        editor.text_selection_start = Some(CursorPosition { x: 4, y: 0 });
+        editor.text_selection_end = Some(CursorPosition { x: 7, y: 0 });
+        editor.cursor.x = 7;
+        editor
+            .handle_input_action(InputAction::Editor(EditorAction::CUT))
+            .unwrap();
+        assert_eq!(editor.editor_content[0], "one ");
+        assert_eq!(editor.text_selection_start, None);
+
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content[0], "one two");
+        assert_eq!(editor.cursor.x, 7);
+        assert_eq!(
+            editor.text_selection_start.zip(editor.text_selection_end),
+            Some((CursorPosition { x: 4, y: 0 }, CursorPosition { x: 7, y: 0 }))
+        );
+    }
 }
@@ -0,0 +1,162 @@
+//! Line endings and final newline of the file a buffer was read from.
+//!
+//! The text buffer keeps bare lines, so how they were ended is detected when
+//! a file is opened and used again when it is saved, leaving files written
+//! the way they were found unless converted with :set fileformat.
+
+use super::Editor;
+use super::TextBuffer;
+
+/// What ends each line of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    /// \n
+    #[default]
+    Unix,
+    /// \r\n
+    Dos,
+}
+
+impl LineEnding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Unix => "\n",
+            LineEnding::Dos => "\r\n",
+        }
+    }
+
+    /// Name shown in the status bar and taken by :set fileformat
+    pub fn name(self) -> &'static str {
+        match self {
+            LineEnding::Unix => "unix",
+            LineEnding::Dos => "dos",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "unix" => Some(LineEnding::Unix),
+            "dos" => Some(LineEnding::Dos),
+            _ => None,
+        }
+    }
+}
+
+/// How the lines of a file are written out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileFormat {
+    pub line_ending: LineEnding,
+    /// Whether the last line is ended too
+    pub final_newline: bool,
+}
+
+/// New buffers are unix with every line ended, the last one included
+impl Default for FileFormat {
+    fn default() -> Self {
+        Self {
+            line_ending: LineEnding::Unix,
+            final_newline: true,
+        }
+    }
+}
+
+impl FileFormat {
+    /// Format of text read from a file. Files mixing both endings take the
+    /// one most lines end with
+    pub fn detect(text: &str) -> Self {
+        let newlines = text.matches('\n').count();
+        let dos = text.matches("\r\n").count();
+        Self {
+            line_ending: if dos * 2 > newlines {
+                LineEnding::Dos
+            } else {
+                LineEnding::Unix
+            },
+            final_newline: text.ends_with('\n'),
+        }
+    }
+
+    /// Lines joined into the text of a file in this format
+    pub fn join(&self, lines: &TextBuffer) -> String {
+        let ending = self.line_ending.as_str();
+        let mut text = lines.join(ending);
+        if self.final_newline {
+            text.push_str(ending);
+        }
+        text
+    }
+}
+
+impl Editor {
+    /// Text as it is written to the file, in the buffer's file format
+    pub fn file_text(&self) -> String {
+        self.file_format.join(&self.editor_content)
+    }
+
+    /// Uses the format of text, just read from the buffer's file
+    pub fn set_file_format_from(&mut self, text: &str) {
+        self.file_format = FileFormat::detect(text);
+        self.saved_file_format = self.file_format;
+    }
+
+    /// Whether saving would change the file, by edits or a converted format
+    pub fn is_modified(&self) -> bool {
+        self.undo_redo_manager.is_dirty() || self.file_format != self.saved_file_format
+    }
+
+    /// Marks the text and format as written to the file
+    pub fn mark_saved(&mut self) {
+        self.undo_redo_manager.mark_saved();
+        self.saved_file_format = self.file_format;
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_file_format_tests {
+    use super::*;
+
+    fn round_trip(text: &str) -> String {
+        FileFormat::detect(text).join(&TextBuffer::from_text(text))
+    }
+
+    #[test]
+    fn test_detect_line_ending_and_final_newline() {
+        let dos = FileFormat::detect("a\r\nb\r\n");
+        assert_eq!(dos.line_ending, LineEnding::Dos);
+        assert!(dos.final_newline);
+
+        let unix = FileFormat::detect("a\nb");
+        assert_eq!(unix.line_ending, LineEnding::Unix);
+        assert!(!unix.final_newline);
+
+        // mostly unix with one dos line
+        assert_eq!(
+            FileFormat::detect("a\nb\nc\r\n").line_ending,
+            LineEnding::Unix
+        );
+        // an empty file stays empty rather than gaining a line ending
+        assert!(!FileFormat::detect("").final_newline);
+    }
+
+    #[test]
+    fn test_text_round_trips_unchanged() {
+        for text in [
+            "a\nb\n",
+            "a\nb",
+            "a\r\nb\r\n",
+            "a\r\nb",
+            "a\n\n",
+            "\r\n\r\n",
+            "\n",
+            "",
+        ] {
+            assert_eq!(round_trip(text), text);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Jumping to a line, and column, by number or relative to the cursor.
+
+use super::editor::Editor;
+use super::marks::is_valid_mark_name;
+use super::motions::first_non_blank;
+use crate::core::cursor::CursorPosition;
+
+/// Where a goto jumps to, lines and columns count from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GotoTarget {
+    /// A line, and optionally a column on it
+    Line { line: usize, column: Option<usize> },
+    /// Lines up, when negative, or down from the cursor
+    Relative(isize),
+    /// Where a named mark was set
+    Mark(char),
+}
+
+impl GotoTarget {
+    /// Parses `42`, `42:7`, `+5`, `-5` and marks as `'a`
+    pub fn parse(input: &str) -> Option<GotoTarget> {
+        let input = input.trim();
+        if let Some(name) = input.strip_prefix(['\'', '`']) {
+            let mut chars = name.chars();
+            return match (chars.next(), chars.next()) {
+                (Some(name), None) if is_valid_mark_name(name) => Some(GotoTarget::Mark(name)),
+                _ => None,
+            };
+        }
+        if let Some(n) = input.strip_prefix('+') {
+            return n
+                .parse::<usize>()
+                .ok()
+                .map(|n| GotoTarget::Relative(n as isize));
+        }
+        if let Some(n) = input.strip_prefix('-') {
+            return n
+                .parse::<usize>()
+                .ok()
+                .map(|n| GotoTarget::Relative(-(n as isize)));
+        }
+        let (line, column) = match input.split_once(':') {
+            Some((line, column)) => (line, Some(column.parse().ok()?)),
+            None => (input, None),
+        };
+        Some(GotoTarget::Line {
+            line: line.parse().ok()?,
+            column,
+        })
+    }
+}
+
+impl Editor {
+    /// Moves the cursor to target and centers its line in the view, leaving
+    /// the old position in the jump list. Lines past the end go to the last
+    /// line, a jump without a column lands on the first non-blank char. A
+    /// mark that is not set leaves the cursor where it is
+    pub fn goto(&mut self, target: GotoTarget) {
+        let last = self.editor_content.len().saturating_sub(1);
+        let pos = match target {
+            GotoTarget::Line { line, column } => {
+                let y = line.saturating_sub(1).min(last);
+                let x = match column {
+                    Some(column) => column.saturating_sub(1),
+                    None => first_non_blank(&self.editor_content[y]),
+                };
+                CursorPosition { x, y }
+            }
+            GotoTarget::Relative(delta) => {
+                let pos = self.cursor_position();
+                CursorPosition {
+                    x: pos.x,
+                    y: pos.y.saturating_add_signed(delta).min(last),
+                }
+            }
+            GotoTarget::Mark(name) => match self.editor_content.mark(name) {
+                Some(pos) => CursorPosition {
+                    x: pos.x,
+                    y: pos.y.min(last),
+                },
+                None => return,
+            },
+        };
+        self.record_jump();
+        self.reset_text_selection_cursor();
+        self.set_cursor_position(&pos);
+        self.center_view_on_cursor();
+    }
+
+    /// Scrolls so the cursor's line sits in the middle of the view
+    pub(crate) fn center_view_on_cursor(&mut self) {
+        let half = self.editor_height as i32 / 2;
+        self.scroll_offset = (self.cursor.y - half).clamp(0, self.calculate_max_scroll().max(0));
+        self.adjust_view_to_cursor();
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_goto_tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::input::actions::Motion;
+    use std::sync::Arc;
+
+    fn create_editor_with_lines(count: usize) -> Editor {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = (1..=count).map(|i| format!("  line {i}")).collect();
+        editor.editor_height = 10;
+        editor
+    }
+
+    #[test]
+    fn test_parse_goto_target() {
+        assert_eq!(
+            GotoTarget::parse("42"),
+            Some(GotoTarget::Line {
+                line: 42,
+                column: None
+            })
+        );
+        assert_eq!(
+            GotoTarget::parse("3:7"),
+            Some(GotoTarget::Line {
+                line: 3,
+                column: Some(7)
+            })
+        );
+        assert_eq!(GotoTarget::parse("+5"), Some(GotoTarget::Relative(5)));
+        assert_eq!(GotoTarget::parse("-5"), Some(GotoTarget::Relative(-5)));
+        assert_eq!(GotoTarget::parse("'a"), Some(GotoTarget::Mark('a')));
+        assert_eq!(GotoTarget::parse("`Z"), Some(GotoTarget::Mark('Z')));
+        assert_eq!(GotoTarget::parse("'ab"), None);
+        assert_eq!(GotoTarget::parse("3:"), None);
+        assert_eq!(GotoTarget::parse("x"), None);
+    }
+
+    #[test]
+    fn test_goto_line_centers_view() {
+        let mut editor = create_editor_with_lines(100);
+        editor.goto(GotoTarget::Line {
+            line: 50,
+            column: None,
+        });
+        assert_eq!((editor.cursor.x, editor.cursor.y), (2, 49));
+        assert_eq!(editor.scroll_offset, 44);
+
+        editor.goto(GotoTarget::Line {
+            line: 2,
+            column: Some(5),
+        });
+        assert_eq!((editor.cursor.x, editor.cursor.y), (4, 1));
+        assert_eq!(editor.scroll_offset, 0);
+    }
+
+    #[test]
+    fn test_goto_clamps_to_buffer() {
+        let mut editor = create_editor_with_lines(20);
+        editor.goto(GotoTarget::Line {
+            line: 500,
+            column: Some(99),
+        });
+        assert_eq!((editor.cursor.x, editor.cursor.y), (9, 19));
+
+        editor.goto(GotoTarget::Relative(-5));
+        assert_eq!(editor.cursor.y, 14);
+        editor.goto(GotoTarget::Relative(-50));
+        assert_eq!(editor.cursor.y, 0);
+        editor.goto(GotoTarget::Relative(3));
+        assert_eq!(editor.cursor.y, 3);
+    }
+
+    #[test]
+    fn test_goto_and_document_end_in_buffer_past_i16_lines() {
+        let mut editor = create_editor_with_lines(40_000);
+        editor.goto(GotoTarget::Line {
+            line: 35_000,
+            column: None,
+        });
+        assert_eq!((editor.cursor.x, editor.cursor.y), (2, 34_999));
+        assert_eq!(editor.scroll_offset, 34_994);
+
+        editor.move_by(Motion::DocumentEnd);
+        assert_eq!(editor.cursor.y, 39_999);
+        editor.adjust_view_to_cursor();
+        assert!(editor.scroll_offset > 39_000);
+        assert!((0..10).contains(&editor.cursor_viewport_row()));
+        editor.move_scroll_offset(-1);
+        assert!(editor.cursor.y < 39_999);
+    }
+}
@@ -0,0 +1,100 @@
+//! Grapheme cluster boundaries and display widths.
+//!
+//! Cursor x stays a char index, these keep it on the start of a cluster so
+//! edits never split e.g. an emoji sequence or a letter and its combining mark.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Char index of the grapheme following the one covering `char_idx`,
+/// the line length at the end of the line
+pub fn next_boundary(line: &str, char_idx: usize) -> usize {
+    let mut idx = 0;
+    for grapheme in line.graphemes(true) {
+        idx += grapheme.chars().count();
+        if idx > char_idx {
+            return idx;
+        }
+    }
+    idx
+}
+
+/// Char index of the grapheme before `char_idx`, 0 at the start of the line
+pub fn prev_boundary(line: &str, char_idx: usize) -> usize {
+    let mut idx = 0;
+    for grapheme in line.graphemes(true) {
+        let next = idx + grapheme.chars().count();
+        if next >= char_idx {
+            return idx;
+        }
+        idx = next;
+    }
+    idx
+}
+
+/// Char index of the start of the grapheme covering `char_idx`,
+/// the line length when past the end
+pub fn floor_boundary(line: &str, char_idx: usize) -> usize {
+    let mut idx = 0;
+    for grapheme in line.graphemes(true) {
+        let next = idx + grapheme.chars().count();
+        if next > char_idx {
+            return idx;
+        }
+        idx = next;
+    }
+    idx
+}
+
+/// Terminal cells a grapheme takes, tabs are not handled here as they depend on column
+pub fn display_width(grapheme: &str) -> usize {
+    UnicodeWidthStr::width(grapheme)
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_graphemes_tests {
+    use super::*;
+
+    // "e" followed by a combining acute accent is one grapheme of two chars
+    const COMBINING: &str = "ae\u{301}b";
+
+    #[test]
+    fn test_boundaries_step_over_combining_marks() {
+        assert_eq!(next_boundary(COMBINING, 0), 1);
+        assert_eq!(next_boundary(COMBINING, 1), 3);
+        assert_eq!(next_boundary(COMBINING, 3), 4);
+        assert_eq!(next_boundary(COMBINING, 4), 4);
+
+        assert_eq!(prev_boundary(COMBINING, 4), 3);
+        assert_eq!(prev_boundary(COMBINING, 3), 1);
+        assert_eq!(prev_boundary(COMBINING, 0), 0);
+    }
+
+    #[test]
+    fn test_floor_boundary_snaps_inside_cluster() {
+        assert_eq!(floor_boundary(COMBINING, 2), 1);
+        assert_eq!(floor_boundary(COMBINING, 3), 3);
+        assert_eq!(floor_boundary(COMBINING, 10), 4);
+    }
+
+    #[test]
+    fn test_emoji_sequence_is_one_grapheme() {
+        // family emoji joined by zero width joiners
+        let line = "x\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}y";
+        assert_eq!(next_boundary(line, 1), 6);
+        assert_eq!(prev_boundary(line, 6), 1);
+    }
+
+    #[test]
+    fn test_display_width_of_wide_and_combining() {
+        assert_eq!(display_width("a"), 1);
+        assert_eq!(display_width("漢"), 2);
+        assert_eq!(display_width("e\u{301}"), 1);
+    }
+}
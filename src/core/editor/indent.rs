@@ -0,0 +1,205 @@
+//! Indentation: carrying it over to new lines on Enter, and shifting whole
+//! lines in and out with Tab and Shift+Tab.
+
+use super::editor::Editor;
+use crate::core::cursor::CursorPosition;
+
+/// Chars that open a block, a line ending in one indents the next line further
+const BLOCK_OPENERS: [char; 4] = ['{', '(', '[', ':'];
+
+impl Editor {
+    /// One level of indentation, tab_width spaces or a tab
+    pub(crate) fn indent_unit(&self) -> String {
+        if self.editor_config.use_spaces {
+            " ".repeat(self.editor_config.tab_width as usize)
+        } else {
+            "\t".to_string()
+        }
+    }
+
+    /// Indentation for a line split off after `left`, the text before the cursor
+    pub(crate) fn indent_after(&self, left: &str) -> String {
+        if !self.editor_config.auto_indent {
+            return String::new();
+        }
+        let mut indent = self.get_line_indent(left);
+        let opens_block = left
+            .trim_end()
+            .chars()
+            .last()
+            .is_some_and(|c| BLOCK_OPENERS.contains(&c));
+        if self.editor_config.smart_indent && opens_block {
+            indent.push_str(&self.indent_unit());
+        }
+        indent
+    }
+
+    /// Splits the line at the cursor and indents the new line, as one undo step
+    pub(crate) fn enter_with_indent(&mut self, indent: String) {
+        let pos = CursorPosition {
+            x: self.cursor.x as usize,
+            y: self.cursor.y as usize,
+        };
+        let x = indent.chars().count();
+        let action = self.replace_range(pos, pos, vec![String::new(), indent]);
+        self.undo_redo_manager.record_undo(action);
+        self.set_cursor_position(&CursorPosition { x, y: pos.y + 1 });
+    }
+
+    /// Indents every line touched by the selection one level
+    pub(crate) fn indent_lines(&mut self) {
+        let (first, last) = self.selected_lines();
+        let unit = self.indent_unit();
+        let mut actions = Vec::new();
+        let mut shifts = Vec::new();
+        for y in first..=last {
+            // blank lines inside a larger selection are left alone
+            if first != last && self.editor_content[y].trim().is_empty() {
+                continue;
+            }
+            let pos = CursorPosition { x: 0, y };
+            actions.push(self.replace_range(pos, pos, vec![unit.clone()]));
+            shifts.push((y, 0, unit.chars().count() as isize));
+        }
+        self.shift_positions(&shifts);
+        self.record_undo_group(actions);
+    }
+
+    /// Removes one level of indentation from every line touched by the
+    /// selection, or from the cursor's line
+    pub(crate) fn outdent_lines(&mut self) {
+        let (first, last) = self.selected_lines();
+        let tab_width = self.editor_config.tab_width as usize;
+        let mut actions = Vec::new();
+        let mut shifts = Vec::new();
+        for y in first..=last {
+            let line = &self.editor_content[y];
+            let width = if line.starts_with('\t') {
+                1
+            } else {
+                line.chars()
+                    .take(tab_width)
+                    .take_while(|&c| c == ' ')
+                    .count()
+            };
+            if width == 0 {
+                continue;
+            }
+            let (start, end) = (CursorPosition { x: 0, y }, CursorPosition { x: width, y });
+            actions.push(self.replace_range(start, end, vec![String::new()]));
+            shifts.push((y, 0, -(width as isize)));
+        }
+        self.shift_positions(&shifts);
+        self.record_undo_group(actions);
+    }
+
+    /// First and last line of the selection, or the cursor's line. A
+    /// selection ending at the start of a line does not touch that line
+    pub(crate) fn selected_lines(&self) -> (usize, usize) {
+        let y = self.cursor.y as usize;
+        match (self.text_selection_start, self.text_selection_end) {
+            (Some(a), Some(b)) => {
+                let (start, end) = (a.min(b), a.max(b));
+                if end.x == 0 && end.y > start.y {
+                    (start.y, end.y - 1)
+                } else {
+                    (start.y, end.y)
+                }
+            }
+            _ => (y, y),
+        }
+    }
+
+    /// Moves the cursor and selection along with text inserted or removed at
+    /// a column of a line. Positions right of the column shift by the delta,
+    /// but never to the left of the column
+    pub(crate) fn shift_positions(&mut self, shifts: &[(usize, usize, isize)]) {
+        let shift = |pos: CursorPosition| match shifts.iter().find(|(y, ..)| *y == pos.y) {
+            Some(&(_, column, delta)) if pos.x > column => CursorPosition {
+                x: pos.x.saturating_add_signed(delta).max(column),
+                y: pos.y,
+            },
+            _ => pos,
+        };
+        self.text_selection_start = self.text_selection_start.map(shift);
+        self.text_selection_end = self.text_selection_end.map(shift);
+        let cursor = shift(CursorPosition {
+            x: self.cursor.x as usize,
+            y: self.cursor.y as usize,
+        });
+        self.set_cursor_position(&cursor);
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_indent_tests {
+    use super::*;
+    use crate::config::EditorConfig;
+    use std::sync::Arc;
+
+    fn create_editor(vec: Vec<&str>, use_spaces: bool) -> Editor {
+        let config = EditorConfig {
+            use_spaces,
+            ..EditorConfig::default()
+        };
+        let mut editor = Editor::new(Arc::new(config));
+        editor.editor_content = vec.into_iter().map(String::from).collect();
+        editor
+    }
+
+    #[test]
+    fn test_indent_after_copies_indent_and_opens_blocks() {
+        let editor = create_editor(vec![], true);
+        assert_eq!(editor.indent_after("    let x = 1;"), "    ");
+        assert_eq!(editor.indent_after("  fn main() {"), "      ");
+        assert_eq!(editor.indent_after("if x:  "), "    ");
+        assert_eq!(editor.indent_after("plain"), "");
+    }
+
+    #[test]
+    fn test_enter_with_indent_is_one_undo_step() {
+        let mut editor = create_editor(vec!["\tif x {}"], false);
+        editor.cursor.x = 6;
+        editor.enter_with_indent(editor.indent_after("\tif x "));
+        assert_eq!(editor.editor_content, vec!["\tif x ", "\t{}"]);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (1, 1));
+
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content, vec!["\tif x {}"]);
+    }
+
+    #[test]
+    fn test_indent_and_outdent_selected_lines() {
+        let mut editor = create_editor(vec!["a", "", "b", "c"], true);
+        editor.text_selection_start = Some(CursorPosition { x: 0, y: 0 });
+        editor.text_selection_end = Some(CursorPosition { x: 0, y: 3 });
+        editor.indent_lines();
+        assert_eq!(editor.editor_content, vec!["    a", "", "    b", "c"]);
+
+        editor.outdent_lines();
+        assert_eq!(editor.editor_content, vec!["a", "", "b", "c"]);
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content, vec!["    a", "", "    b", "c"]);
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content, vec!["a", "", "b", "c"]);
+    }
+
+    #[test]
+    fn test_outdent_removes_one_level_and_moves_cursor() {
+        let mut editor = create_editor(vec!["      x", "\t\ty"], true);
+        editor.cursor.x = 6;
+        editor.outdent_lines();
+        assert_eq!(editor.editor_content[0], "  x");
+        assert_eq!(editor.cursor.x, 2);
+
+        editor.cursor.y = 1;
+        editor.outdent_lines();
+        assert_eq!(editor.editor_content[1], "\ty");
+    }
+}
@@ -0,0 +1,207 @@
+//! Whole line operations on the cursor's line or the lines of the selection:
+//! duplicating, moving, deleting and joining them.
+
+use super::editor::{EditAction, Editor};
+use super::text_buffer::TextStorage;
+use crate::core::cursor::CursorPosition;
+
+impl Editor {
+    /// Copies the selected lines in below them, the cursor and selection
+    /// follow onto the copy
+    pub(crate) fn duplicate_lines(&mut self) {
+        let (first, last) = self.selected_lines();
+        let lines = self.lines_between(first, last);
+        let start = CursorPosition { x: 0, y: last + 1 };
+        self.editor_content.insert_lines(start.y, lines.clone());
+        self.undo_redo_manager
+            .record_undo(EditAction::InsertLines { start, lines });
+        self.shift_lines_by((last - first + 1) as isize);
+    }
+
+    /// Swaps the selected lines with the line above or below them
+    pub(crate) fn move_lines(&mut self, direction: isize) {
+        let (first, last) = self.selected_lines();
+        let (first, last, new) = match direction {
+            -1 if first > 0 => {
+                let mut lines = self.lines_between(first, last);
+                lines.push(self.editor_content[first - 1].clone());
+                (first - 1, last, lines)
+            }
+            1 if last + 1 < self.editor_content.len() => {
+                let mut lines = vec![self.editor_content[last + 1].clone()];
+                lines.extend(self.lines_between(first, last));
+                (first, last + 1, lines)
+            }
+            _ => return,
+        };
+        let end = CursorPosition {
+            x: self.editor_content.line_char_count(last),
+            y: last,
+        };
+        let action = self.replace_range(CursorPosition { x: 0, y: first }, end, new);
+        self.undo_redo_manager.record_undo(action);
+        self.shift_lines_by(direction);
+    }
+
+    /// Removes the selected lines, leaving one empty line in an emptied buffer
+    pub(crate) fn delete_lines(&mut self) {
+        let (first, last) = self.selected_lines();
+        let action = if last - first + 1 == self.editor_content.len() {
+            let end = CursorPosition {
+                x: self.editor_content.line_char_count(last),
+                y: last,
+            };
+            self.replace_range(CursorPosition { x: 0, y: 0 }, end, vec![String::new()])
+        } else {
+            let start = CursorPosition { x: 0, y: first };
+            let deleted = self.lines_between(first, last);
+            self.editor_content
+                .remove_lines_from(start.y, deleted.len());
+            EditAction::DeleteLines { start, deleted }
+        };
+        self.undo_redo_manager.record_undo(action);
+        self.reset_text_selection_cursor();
+        let x = self.cursor.x as usize;
+        self.set_cursor_position(&CursorPosition { x, y: first });
+    }
+
+    /// Joins the line after the cursor's line, or the selected lines, into
+    /// one. Indentation of joined lines becomes a single space
+    pub(crate) fn join_lines(&mut self) {
+        let (first, last) = self.selected_lines();
+        let last = last.max(first + 1).min(self.editor_content.len() - 1);
+        if first == last {
+            return;
+        }
+        let mut joined = self.editor_content[first].trim_end().to_string();
+        for y in first + 1..=last {
+            let next = self.editor_content[y].trim();
+            if !joined.is_empty() && !next.is_empty() {
+                joined.push(' ');
+            }
+            joined.push_str(next);
+        }
+        let cursor_x = self.editor_content[first].trim_end().chars().count();
+        let end = CursorPosition {
+            x: self.editor_content.line_char_count(last),
+            y: last,
+        };
+        let action = self.replace_range(CursorPosition { x: 0, y: first }, end, vec![joined]);
+        self.undo_redo_manager.record_undo(action);
+        self.reset_text_selection_cursor();
+        self.set_cursor_position(&CursorPosition {
+            x: cursor_x,
+            y: first,
+        });
+    }
+
+    fn lines_between(&self, first: usize, last: usize) -> Vec<String> {
+        (first..=last)
+            .map(|y| self.editor_content[y].clone())
+            .collect()
+    }
+
+    /// Moves the cursor and selection dy lines, keeping their columns
+    fn shift_lines_by(&mut self, dy: isize) {
+        let shift = |pos: CursorPosition| CursorPosition {
+            x: pos.x,
+            y: pos.y.saturating_add_signed(dy),
+        };
+        self.text_selection_start = self.text_selection_start.map(shift);
+        self.text_selection_end = self.text_selection_end.map(shift);
+        let cursor = shift(CursorPosition {
+            x: self.cursor.x as usize,
+            y: self.cursor.y as usize,
+        });
+        self.set_cursor_position(&cursor);
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_line_ops_tests {
+    use super::*;
+    use crate::config::Config;
+    use std::sync::Arc;
+
+    fn create_editor_with_editor_content(vec: Vec<&str>) -> Editor {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = vec.into_iter().map(String::from).collect();
+        editor
+    }
+
+    #[test]
+    fn test_duplicate_lines_moves_cursor_to_copy() {
+        let mut editor = create_editor_with_editor_content(vec!["a", "b", "c"]);
+        editor.cursor.x = 1;
+        editor.cursor.y = 1;
+        editor.duplicate_lines();
+        assert_eq!(editor.editor_content.to_lines(), vec!["a", "b", "b", "c"]);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (1, 2));
+
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content.to_lines(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_move_selected_lines_up_and_down() {
+        let mut editor = create_editor_with_editor_content(vec!["a", "b", "c", "d"]);
+        editor.text_selection_start = Some(CursorPosition { x: 0, y: 1 });
+        editor.text_selection_end = Some(CursorPosition { x: 1, y: 2 });
+        editor.cursor.x = 1;
+        editor.cursor.y = 2;
+        editor.move_lines(1);
+        assert_eq!(editor.editor_content.to_lines(), vec!["a", "d", "b", "c"]);
+        assert_eq!(editor.cursor.y, 3);
+        assert_eq!(
+            editor.text_selection_start,
+            Some(CursorPosition { x: 0, y: 2 })
+        );
+
+        // the last line stays put
+        editor.move_lines(1);
+        assert_eq!(editor.editor_content.to_lines(), vec!["a", "d", "b", "c"]);
+
+        editor.move_lines(-1);
+        editor.move_lines(-1);
+        assert_eq!(editor.editor_content.to_lines(), vec!["b", "c", "a", "d"]);
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content.to_lines(), vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_delete_lines_keeps_one_line() {
+        let mut editor = create_editor_with_editor_content(vec!["a", "b"]);
+        editor.delete_lines();
+        assert_eq!(editor.editor_content.to_lines(), vec!["b"]);
+        editor.delete_lines();
+        assert_eq!(editor.editor_content.to_lines(), vec![""]);
+
+        editor.undo().unwrap();
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content.to_lines(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_join_lines_collapses_indentation() {
+        let mut editor = create_editor_with_editor_content(vec!["let x = ", "    1;", "", "end"]);
+        editor.join_lines();
+        assert_eq!(
+            editor.editor_content.to_lines(),
+            vec!["let x = 1;", "", "end"]
+        );
+        assert_eq!(editor.cursor.x, 7);
+
+        editor.text_selection_start = Some(CursorPosition { x: 0, y: 0 });
+        editor.text_selection_end = Some(CursorPosition { x: 3, y: 2 });
+        editor.join_lines();
+        assert_eq!(editor.editor_content.to_lines(), vec!["let x = 1; end"]);
+        editor.join_lines();
+        assert_eq!(editor.editor_content.to_lines(), vec!["let x = 1; end"]);
+    }
+}
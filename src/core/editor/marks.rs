@@ -0,0 +1,209 @@
+//! Named marks to return to, and the jump list of places big moves left.
+//!
+//! Marks are kept by the text buffer, so they move along with lines inserted
+//! or removed above them. The jump list belongs to the editor and is walked
+//! back and forth like browser history.
+
+use super::editor::Editor;
+use crate::core::cursor::CursorPosition;
+
+/// Most positions the jump list remembers, the oldest are dropped first
+const JUMP_LIST_LIMIT: usize = 100;
+
+/// Positions left by big moves like goto, search and document start or end
+#[derive(Debug, Default, Clone)]
+pub struct JumpList {
+    entries: Vec<CursorPosition>,
+    // entry walked to last, entries.len() when not walking the list
+    index: usize,
+}
+
+impl JumpList {
+    /// Remembers pos as left behind. Entries ahead of the one walked to are
+    /// dropped, and so is an older entry on the same line
+    pub fn push(&mut self, pos: CursorPosition) {
+        self.entries.truncate(self.index);
+        self.entries.retain(|entry| entry.y != pos.y);
+        self.entries.push(pos);
+        if self.entries.len() > JUMP_LIST_LIMIT {
+            self.entries.remove(0);
+        }
+        self.index = self.entries.len();
+    }
+
+    /// Entry before the one walked to. Walking back from current, it is
+    /// remembered first so forward can return to it
+    pub fn back(&mut self, current: CursorPosition) -> Option<CursorPosition> {
+        if self.index == self.entries.len() {
+            if self.entries.last().is_some_and(|last| last.y == current.y) {
+                self.index -= 1;
+            } else {
+                self.entries.push(current);
+                self.index = self.entries.len() - 1;
+            }
+        }
+        self.index = self.index.checked_sub(1)?;
+        Some(self.entries[self.index])
+    }
+
+    /// Entry after the one walked to
+    pub fn forward(&mut self) -> Option<CursorPosition> {
+        if self.index + 1 >= self.entries.len() {
+            return None;
+        }
+        self.index += 1;
+        Some(self.entries[self.index])
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Names marks can have, letters and digits
+pub fn is_valid_mark_name(name: char) -> bool {
+    name.is_ascii_alphanumeric()
+}
+
+impl Editor {
+    /// Sets mark name at the cursor
+    pub fn set_mark(&mut self, name: char) {
+        let pos = self.cursor_position();
+        self.editor_content.set_mark(name, pos);
+    }
+
+    /// Remembers the cursor position in the jump list, call before a big move
+    pub fn record_jump(&mut self) {
+        let pos = self.cursor_position();
+        self.jump_list.push(pos);
+        self.undo_redo_manager.break_typing();
+    }
+
+    /// Moves the cursor back to where the last big move left from
+    pub fn jump_back(&mut self) {
+        let current = self.cursor_position();
+        if let Some(pos) = self.jump_list.back(current) {
+            self.jump_to(pos);
+        }
+    }
+
+    /// Moves the cursor forward again after jumping back
+    pub fn jump_forward(&mut self) {
+        if let Some(pos) = self.jump_list.forward() {
+            self.jump_to(pos);
+        }
+    }
+
+    fn jump_to(&mut self, pos: CursorPosition) {
+        self.reset_text_selection_cursor();
+        self.undo_redo_manager.break_typing();
+        self.set_cursor_position(&pos);
+        self.adjust_view_to_cursor();
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_marks_tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::core::editor::goto::GotoTarget;
+    use crate::input::actions::Motion;
+    use std::sync::Arc;
+
+    fn create_editor_with_lines(count: usize) -> Editor {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = (1..=count).map(|i| format!("line {i}")).collect();
+        editor.editor_height = 10;
+        editor
+    }
+
+    fn at(y: usize) -> CursorPosition {
+        CursorPosition { x: 0, y }
+    }
+
+    #[test]
+    fn test_jump_list_walks_back_and_forward() {
+        let mut list = JumpList::default();
+        list.push(at(1));
+        list.push(at(5));
+        assert_eq!(list.back(at(9)), Some(at(5)));
+        assert_eq!(list.back(at(5)), Some(at(1)));
+        assert_eq!(list.back(at(1)), None);
+        assert_eq!(list.forward(), Some(at(5)));
+        assert_eq!(list.forward(), Some(at(9)));
+        assert_eq!(list.forward(), None);
+
+        // a new jump drops what lies ahead, and older entries on its line
+        list.back(at(9));
+        list.back(at(5));
+        list.push(at(1));
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn test_jump_list_is_capped() {
+        let mut list = JumpList::default();
+        for y in 0..JUMP_LIST_LIMIT + 10 {
+            list.push(at(y));
+        }
+        assert_eq!(list.len(), JUMP_LIST_LIMIT);
+        assert_eq!(list.back(at(500)), Some(at(JUMP_LIST_LIMIT + 9)));
+    }
+
+    #[test]
+    fn test_goto_and_document_motions_record_jumps() {
+        let mut editor = create_editor_with_lines(50);
+        editor.goto(GotoTarget::Line {
+            line: 30,
+            column: None,
+        });
+        editor.move_by(Motion::DocumentEnd);
+        assert_eq!(editor.cursor.y, 49);
+
+        editor.jump_back();
+        assert_eq!(editor.cursor.y, 29);
+        editor.jump_back();
+        assert_eq!(editor.cursor.y, 0);
+        editor.jump_forward();
+        editor.jump_forward();
+        assert_eq!(editor.cursor.y, 49);
+    }
+
+    #[test]
+    fn test_marks_survive_edits_above_them() {
+        let mut editor = create_editor_with_lines(10);
+        editor.cursor.y = 6;
+        editor.cursor.x = 2;
+        editor.set_mark('a');
+
+        editor.cursor.y = 1;
+        editor.duplicate_lines();
+        assert_eq!(
+            editor.editor_content.mark('a'),
+            Some(CursorPosition { x: 2, y: 7 })
+        );
+        editor.delete_lines();
+        editor.cursor.y = 0;
+        editor.enter();
+        assert_eq!(editor.editor_content.mark('a').map(|pos| pos.y), Some(7));
+
+        editor.undo().unwrap();
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content.mark('a').map(|pos| pos.y), Some(7));
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content.mark('a').map(|pos| pos.y), Some(6));
+
+        editor.goto(GotoTarget::Mark('a'));
+        assert_eq!((editor.cursor.x, editor.cursor.y), (2, 6));
+    }
+}
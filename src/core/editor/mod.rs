@@ -1,7 +1,44 @@
+//rectangular selection and column editing
+pub mod block_selection;
+//toggling line and block comments
+pub mod comments;
 //main core editor
 pub mod editor;
+//line endings and final newline kept from the file
+pub mod file_format;
+//jumping to a line and column
+pub mod goto;
+//grapheme boundaries and display widths
+pub mod graphemes;
+//auto-indent, indenting and outdenting lines
+pub mod indent;
+//duplicating, moving, deleting and joining whole lines
+pub mod line_ops;
+//named marks and the jump list
+pub mod marks;
+//opt-in vim style normal, insert and visual modes
+pub mod modal;
+//word, line, document and page motions
+pub mod motions;
+//bracket and quote auto-pairing and bracket matching
+pub mod pairs;
+//named registers and pasting from the clipboard history
+pub mod registers;
+//secondary cursors and editing at every cursor
+pub mod multi_cursor;
+//text storage of editor content
+pub mod text_buffer;
+//sorting, deduplicating and reversing lines, converting case
+pub mod transform;
+//undo history kept between sessions in undo files
+pub mod undo_file;
 //undo redo utility
 pub mod undo_redo;
+//moving between branches of the undo tree
+pub mod undo_tree;
+//tab expansion and soft wrap layout
+pub mod wrap;
 
 // Re-export the Editor struct for simpler imports elsewhere
 pub use editor::Editor;
+pub use text_buffer::{TextBuffer, TextStorage};
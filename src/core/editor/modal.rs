@@ -0,0 +1,957 @@
+//! Opt-in vim style modal editing.
+//!
+//! When `modal_editing` is enabled the editor starts in normal mode, where
+//! typed chars are commands: motions, operators taking a motion, and counts
+//! in front of either. Insert mode hands input back to the regular modeless
+//! editing, and visual mode extends the text selection with motions. Keys
+//! that are not chars, like arrows and Ctrl shortcuts, keep their bindings in
+//! every mode.
+
+use super::editor::Editor;
+use super::goto::GotoTarget;
+use super::graphemes;
+use super::marks::is_valid_mark_name;
+use super::motions::{char_class, first_non_blank, CharClass};
+use super::text_buffer::TextStorage;
+use crate::core::clipboard::ClipboardKind;
+use crate::core::cursor::CursorPosition;
+use crate::errors::editor_errors::EditorError;
+use crate::input::actions::{EditorAction, InputAction};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Normal,
+    Insert,
+    Visual,
+}
+
+impl Mode {
+    /// Name shown in the info bar
+    pub fn label(&self) -> &'static str {
+        match self {
+            Mode::Normal => "NORMAL",
+            Mode::Insert => "INSERT",
+            Mode::Visual => "VISUAL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VimMotion {
+    Left,
+    Right,
+    Up,
+    Down,
+    WordStart,
+    WordBack,
+    WordEnd,
+    LineStart,
+    LineEnd,
+    FirstLine,
+    LastLine,
+    MatchingBracket,
+}
+
+impl VimMotion {
+    /// Operators on these motions act on whole lines
+    fn is_linewise(&self) -> bool {
+        matches!(
+            self,
+            VimMotion::Up | VimMotion::Down | VimMotion::FirstLine | VimMotion::LastLine
+        )
+    }
+
+    /// Operators on these motions include the char the motion lands on
+    fn is_inclusive(&self) -> bool {
+        matches!(
+            self,
+            VimMotion::WordEnd | VimMotion::LineEnd | VimMotion::MatchingBracket
+        )
+    }
+}
+
+/// Text an operator acts on
+enum OperatorRange {
+    Chars(CursorPosition, CursorPosition),
+    Lines(usize, usize),
+}
+
+/// Mode and the keys typed so far of a command not yet complete
+#[derive(Debug, Default, Clone)]
+pub struct ModalState {
+    pub mode: Mode,
+    count: Option<usize>,
+    operator: Option<(Operator, Option<usize>)>,
+    pending_g: bool,
+    // m, ' or ` typed, waiting for the mark name
+    pending_mark: Option<char>,
+    pending_keys: String,
+    visual_anchor: CursorPosition,
+}
+
+impl ModalState {
+    /// Keys of the command being typed, like `2d`
+    pub fn pending_keys(&self) -> &str {
+        &self.pending_keys
+    }
+
+    fn has_pending(&self) -> bool {
+        !self.pending_keys.is_empty()
+    }
+
+    fn clear_pending(&mut self) {
+        self.count = None;
+        self.operator = None;
+        self.pending_g = false;
+        self.pending_mark = None;
+        self.pending_keys.clear();
+    }
+}
+
+impl Editor {
+    /// Current mode, None when modal editing is off
+    pub fn mode(&self) -> Option<Mode> {
+        self.modal.as_ref().map(|state| state.mode)
+    }
+
+    /// Whether Esc should return to normal mode, rather than leave the editor
+    pub fn escape_returns_to_normal_mode(&self) -> bool {
+        self.modal
+            .as_ref()
+            .is_some_and(|state| state.mode != Mode::Normal || state.has_pending())
+    }
+
+    /// Whether a `:` typed now starts a command line command
+    pub fn colon_opens_command_line(&self) -> bool {
+        self.modal
+            .as_ref()
+            .is_some_and(|state| state.mode == Mode::Normal && !state.has_pending())
+    }
+
+    /// Leaves insert or visual mode and drops any half typed command
+    pub fn enter_normal_mode(&mut self) {
+        let Some(state) = self.modal.as_mut() else {
+            return;
+        };
+        let was_insert = state.mode == Mode::Insert;
+        state.mode = Mode::Normal;
+        state.clear_pending();
+        self.reset_text_selection_cursor();
+        // like vim, leaving insert mode steps back onto the last char typed
+        if was_insert && self.cursor.x > 0 {
+            self.move_cursor(-1, 0);
+        }
+        self.clamp_to_last_char();
+    }
+
+    /// Handles action when in normal or visual mode.
+    /// None means the action is left to the regular dispatch
+    pub(crate) fn handle_modal_action(
+        &mut self,
+        action: &InputAction,
+    ) -> Option<Result<(), EditorError>> {
+        let mode = self.mode()?;
+        if mode == Mode::Insert {
+            return None;
+        }
+        let key = match action {
+            InputAction::Editor(EditorAction::WriteChar(c)) => *c,
+            InputAction::ENTER => 'j',
+            InputAction::Editor(EditorAction::BACKSPACE) => 'h',
+            InputAction::Editor(EditorAction::DELETE) => 'x',
+            InputAction::Editor(EditorAction::MoveCursor(motion)) if mode == Mode::Visual => {
+                self.move_by(*motion);
+                self.update_visual_selection();
+                self.adjust_view_to_cursor();
+                return Some(Ok(()));
+            }
+            InputAction::TAB => return Some(Ok(())),
+            _ => return None,
+        };
+        Some(self.handle_modal_key(key))
+    }
+
+    fn handle_modal_key(&mut self, key: char) -> Result<(), EditorError> {
+        let state = self.modal.as_mut().expect("modal key without modal state");
+        if state.pending_g {
+            state.pending_g = false;
+            return match key {
+                'g' => self.apply_motion(VimMotion::FirstLine),
+                '-' | '+' => {
+                    let count = state.count.unwrap_or(1);
+                    state.clear_pending();
+                    for _ in 0..count {
+                        if key == '-' {
+                            self.older_state()?;
+                        } else {
+                            self.newer_state()?;
+                        }
+                    }
+                    self.clamp_to_last_char();
+                    Ok(())
+                }
+                _ => {
+                    state.clear_pending();
+                    Ok(())
+                }
+            };
+        }
+        if let Some(prefix) = state.pending_mark {
+            let operator = state.operator.is_some();
+            state.clear_pending();
+            if operator || !is_valid_mark_name(key) {
+                return Ok(());
+            }
+            match prefix {
+                'm' => self.set_mark(key),
+                '\'' => {
+                    // ' lands on the first non-blank of the mark's line
+                    if let Some(pos) = self.editor_content.mark(key) {
+                        self.goto(GotoTarget::Line {
+                            line: pos.y + 1,
+                            column: None,
+                        });
+                    }
+                }
+                _ => self.goto(GotoTarget::Mark(key)),
+            }
+            return Ok(());
+        }
+
+        match key {
+            '1'..='9' | '0' if key != '0' || state.count.is_some() => {
+                let digit = key.to_digit(10).unwrap_or(0) as usize;
+                state.count = Some(state.count.unwrap_or(0).saturating_mul(10) + digit);
+                state.pending_keys.push(key);
+                Ok(())
+            }
+            'g' => {
+                state.pending_g = true;
+                state.pending_keys.push(key);
+                Ok(())
+            }
+            'm' | '\'' | '`' => {
+                state.pending_mark = Some(key);
+                state.pending_keys.push(key);
+                Ok(())
+            }
+            'h' => self.apply_motion(VimMotion::Left),
+            'l' => self.apply_motion(VimMotion::Right),
+            'k' => self.apply_motion(VimMotion::Up),
+            'j' => self.apply_motion(VimMotion::Down),
+            'w' => self.apply_motion(VimMotion::WordStart),
+            'b' => self.apply_motion(VimMotion::WordBack),
+            'e' => self.apply_motion(VimMotion::WordEnd),
+            '0' => self.apply_motion(VimMotion::LineStart),
+            '$' => self.apply_motion(VimMotion::LineEnd),
+            'G' => self.apply_motion(VimMotion::LastLine),
+            '%' => self.apply_motion(VimMotion::MatchingBracket),
+            'd' => self.operator_key(Operator::Delete, key),
+            'c' => self.operator_key(Operator::Change, key),
+            'y' => self.operator_key(Operator::Yank, key),
+            'x' if state.mode == Mode::Visual => self.operator_key(Operator::Delete, key),
+            _ if state.operator.is_some() => {
+                state.clear_pending();
+                Ok(())
+            }
+            _ => {
+                let count = state.count.take().unwrap_or(1);
+                state.clear_pending();
+                self.normal_command(key, count)
+            }
+        }
+    }
+
+    /// Commands that take no motion
+    fn normal_command(&mut self, key: char, count: usize) -> Result<(), EditorError> {
+        let mode = self.mode().unwrap_or_default();
+        match (mode, key) {
+            (Mode::Visual, 'v') => self.enter_normal_mode(),
+            (Mode::Visual, _) => {}
+            (_, 'i') => self.set_mode(Mode::Insert),
+            (_, 'a') => {
+                let line = &self.editor_content[self.cursor.y as usize];
+                self.cursor.x = graphemes::next_boundary(line, self.cursor.x as usize) as i32;
+                self.set_mode(Mode::Insert);
+            }
+            (_, 'I') => {
+                let x = first_non_blank(&self.editor_content[self.cursor.y as usize]);
+                self.set_cursor_position(&CursorPosition {
+                    x,
+                    y: self.cursor.y as usize,
+                });
+                self.set_mode(Mode::Insert);
+            }
+            (_, 'A') => {
+                self.move_to_line_end();
+                self.set_mode(Mode::Insert);
+            }
+            (_, 'o') => {
+                self.move_to_line_end();
+                self.enter();
+                self.set_mode(Mode::Insert);
+            }
+            (_, 'O') => {
+                self.cursor.x = 0;
+                self.enter();
+                self.move_cursor(0, -1);
+                self.set_mode(Mode::Insert);
+            }
+            (_, 'x') => {
+                let line = &self.editor_content[self.cursor.y as usize];
+                let start = self.cursor.x as usize;
+                let mut end = start;
+                for _ in 0..count {
+                    end = graphemes::next_boundary(line, end);
+                }
+                let y = self.cursor.y as usize;
+                self.apply_operator(
+                    Operator::Delete,
+                    OperatorRange::Chars(
+                        CursorPosition { x: start, y },
+                        CursorPosition { x: end, y },
+                    ),
+                )?;
+            }
+            (_, 'p') => return self.modal_paste(true),
+            (_, 'P') => return self.modal_paste(false),
+            (_, 'u') => return self.undo().map(|_| self.clamp_to_last_char()),
+            (_, 'v') => {
+                let anchor = self.cursor_position();
+                if let Some(state) = self.modal.as_mut() {
+                    state.mode = Mode::Visual;
+                    state.visual_anchor = anchor;
+                }
+                self.update_visual_selection();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// An operator applies to the visual selection, or waits for its motion.
+    /// Typed twice it applies to whole lines
+    fn operator_key(&mut self, operator: Operator, key: char) -> Result<(), EditorError> {
+        let state = self.modal.as_mut().expect("operator without modal state");
+        if state.mode == Mode::Visual {
+            state.clear_pending();
+            let (start, end) = self.visual_range();
+            return self.apply_operator(operator, OperatorRange::Chars(start, end));
+        }
+        match state.operator {
+            Some((pending, op_count)) if pending == operator => {
+                let count = combined_count(op_count, state.count).unwrap_or(1);
+                state.clear_pending();
+                let y = self.cursor.y as usize;
+                let last = (y + count - 1).min(self.editor_content.len() - 1);
+                self.apply_operator(operator, OperatorRange::Lines(y, last))
+            }
+            Some(_) => {
+                state.clear_pending();
+                Ok(())
+            }
+            None => {
+                state.operator = Some((operator, state.count.take()));
+                state.pending_keys.push(key);
+                Ok(())
+            }
+        }
+    }
+
+    /// Moves the cursor by motion, or applies the pending operator over it
+    fn apply_motion(&mut self, mut motion: VimMotion) -> Result<(), EditorError> {
+        let state = self.modal.as_mut().expect("motion without modal state");
+        let count = state.count.take();
+        let operator = state.operator.take();
+        let mode = state.mode;
+        state.clear_pending();
+
+        let Some((operator, op_count)) = operator else {
+            let steps = count.unwrap_or(1);
+            match motion {
+                // vertical moves keep the display column, like the arrow keys
+                VimMotion::Up | VimMotion::Down => {
+                    let y = if motion == VimMotion::Up { -1 } else { 1 };
+                    for _ in 0..steps {
+                        if self.cursor.y + y < 0 || self.cursor.y + y > self.last_line() {
+                            break;
+                        }
+                        self.move_cursor(0, y);
+                    }
+                }
+                _ => {
+                    if matches!(motion, VimMotion::FirstLine | VimMotion::LastLine) {
+                        self.record_jump();
+                    }
+                    let target = self.motion_target(motion, count, false);
+                    self.set_cursor_position(&target);
+                }
+            }
+            if mode == Mode::Visual {
+                self.update_visual_selection();
+            } else {
+                self.clamp_to_last_char();
+            }
+            self.adjust_view_to_cursor();
+            return Ok(());
+        };
+
+        let count = combined_count(op_count, count);
+        let from = self.cursor_position();
+        // like vim, cw changes to the end of the word rather than to the next one
+        let target = if operator == Operator::Change
+            && motion == VimMotion::WordStart
+            && char_class(self.char_at(from)) != CharClass::Blank
+        {
+            motion = VimMotion::WordEnd;
+            (1..count.unwrap_or(1)).fold(self.current_word_end(from), |p, _| self.word_end(p))
+        } else {
+            self.motion_target(motion, count, true)
+        };
+
+        let range = if motion.is_linewise() {
+            OperatorRange::Lines(from.y.min(target.y), from.y.max(target.y))
+        } else {
+            let (start, mut end) = (from.min(target), from.max(target));
+            if motion.is_inclusive() {
+                end.x = graphemes::next_boundary(&self.editor_content[end.y], end.x);
+            }
+            if motion == VimMotion::WordStart && end.y > start.y {
+                // a word motion run off the line stops at the end of it
+                end.y -= 1;
+                end.x = self.editor_content.line_char_count(end.y);
+            }
+            OperatorRange::Chars(start, end)
+        };
+        self.apply_operator(operator, range)
+    }
+
+    /// Where motion leads from the cursor, count times
+    fn motion_target(
+        &self,
+        motion: VimMotion,
+        count: Option<usize>,
+        for_operator: bool,
+    ) -> CursorPosition {
+        let steps = count.unwrap_or(1);
+        let pos = self.cursor_position();
+        let line = &self.editor_content[pos.y];
+        let last_line = self.editor_content.len() - 1;
+        match motion {
+            VimMotion::Left => {
+                let mut x = pos.x;
+                for _ in 0..steps {
+                    x = graphemes::prev_boundary(line, x);
+                }
+                CursorPosition { x, ..pos }
+            }
+            VimMotion::Right => {
+                let mut x = pos.x;
+                for _ in 0..steps {
+                    x = graphemes::next_boundary(line, x);
+                }
+                CursorPosition { x, ..pos }
+            }
+            VimMotion::Up => CursorPosition {
+                x: pos.x,
+                y: pos.y.saturating_sub(steps),
+            },
+            VimMotion::Down => CursorPosition {
+                x: pos.x,
+                y: (pos.y + steps).min(last_line),
+            },
+            VimMotion::WordStart => (0..steps).fold(pos, |p, _| self.next_word_start(p)),
+            VimMotion::WordBack => (0..steps).fold(pos, |p, _| self.prev_word_start(p)),
+            VimMotion::WordEnd => (0..steps).fold(pos, |p, _| self.word_end(p)),
+            VimMotion::LineStart => CursorPosition { x: 0, ..pos },
+            VimMotion::LineEnd => {
+                let y = (pos.y + steps - 1).min(last_line);
+                let len = self.editor_content.line_char_count(y);
+                // an operator takes the last char too, since $ is inclusive
+                let x = if for_operator {
+                    graphemes::prev_boundary(&self.editor_content[y], len)
+                } else {
+                    len
+                };
+                CursorPosition { x, y }
+            }
+            VimMotion::FirstLine => CursorPosition {
+                x: 0,
+                y: count.map_or(0, |n| n.saturating_sub(1).min(last_line)),
+            },
+            VimMotion::LastLine => CursorPosition {
+                x: 0,
+                y: count.map_or(last_line, |n| n.saturating_sub(1).min(last_line)),
+            },
+            VimMotion::MatchingBracket => self.matching_bracket().map_or(pos, |(_, other)| other),
+        }
+    }
+
+    /// Deletes, changes or yanks range, recorded as one undo step
+    fn apply_operator(
+        &mut self,
+        operator: Operator,
+        range: OperatorRange,
+    ) -> Result<(), EditorError> {
+        match range {
+            OperatorRange::Chars(start, end) => {
+                let text = self.editor_content.text_in_range(start, end);
+                if start != end {
+                    self.clipboard.copy(&text);
+                }
+                if operator != Operator::Yank && start != end {
+                    let action = self.replace_range(start, end, vec![String::new()]);
+                    self.undo_redo_manager.record_undo(action);
+                }
+                self.set_cursor_position(&start);
+            }
+            OperatorRange::Lines(first, last) => {
+                let lines: Vec<String> = (first..=last)
+                    .map(|y| self.editor_content[y].clone())
+                    .collect();
+                self.clipboard.copy_lines(&lines);
+                match operator {
+                    Operator::Yank => self.set_cursor_position(&CursorPosition {
+                        x: self.cursor.x as usize,
+                        y: first,
+                    }),
+                    Operator::Delete => {
+                        let (start, end) = self.line_span(first, last);
+                        let action = self.replace_range(start, end, vec![String::new()]);
+                        self.undo_redo_manager.record_undo(action);
+                        let y = first.min(self.editor_content.len() - 1);
+                        let x = first_non_blank(&self.editor_content[y]);
+                        self.set_cursor_position(&CursorPosition { x, y });
+                    }
+                    Operator::Change => {
+                        let start = CursorPosition { x: 0, y: first };
+                        let end = CursorPosition {
+                            x: self.editor_content.line_char_count(last),
+                            y: last,
+                        };
+                        let action = self.replace_range(start, end, vec![String::new()]);
+                        self.undo_redo_manager.record_undo(action);
+                        self.set_cursor_position(&start);
+                    }
+                }
+            }
+        }
+
+        self.reset_text_selection_cursor();
+        match operator {
+            Operator::Change => self.set_mode(Mode::Insert),
+            _ => {
+                if let Some(state) = self.modal.as_mut() {
+                    state.mode = Mode::Normal;
+                }
+                self.clamp_to_last_char();
+            }
+        }
+        self.adjust_view_to_cursor();
+        Ok(())
+    }
+
+    /// Span to remove so that lines first to last disappear whole
+    fn line_span(&self, first: usize, last: usize) -> (CursorPosition, CursorPosition) {
+        if last + 1 < self.editor_content.len() {
+            (
+                CursorPosition { x: 0, y: first },
+                CursorPosition { x: 0, y: last + 1 },
+            )
+        } else if first > 0 {
+            (
+                CursorPosition {
+                    x: self.editor_content.line_char_count(first - 1),
+                    y: first - 1,
+                },
+                CursorPosition {
+                    x: self.editor_content.line_char_count(last),
+                    y: last,
+                },
+            )
+        } else {
+            (
+                CursorPosition { x: 0, y: 0 },
+                CursorPosition {
+                    x: self.editor_content.line_char_count(last),
+                    y: last,
+                },
+            )
+        }
+    }
+
+    /// p pastes after the cursor, P before it. Whole lines go below or above the line
+    fn modal_paste(&mut self, after: bool) -> Result<(), EditorError> {
+        self.clipboard.sync_from_provider();
+        match self.clipboard.kind {
+            ClipboardKind::Lines => {
+                let mut new = self.clipboard.paste();
+                let y = self.cursor.y as usize + usize::from(after);
+                let start = if y < self.editor_content.len() {
+                    new.push(String::new());
+                    CursorPosition { x: 0, y }
+                } else {
+                    // below the last line there is no line start to insert at
+                    new.insert(0, String::new());
+                    CursorPosition {
+                        x: self.editor_content.line_char_count(y - 1),
+                        y: y - 1,
+                    }
+                };
+                let action = self.replace_range(start, start, new);
+                self.undo_redo_manager.record_undo(action);
+                let x = first_non_blank(&self.editor_content[y]);
+                self.set_cursor_position(&CursorPosition { x, y });
+                Ok(())
+            }
+            kind => {
+                if after && self.editor_content.line_char_count(self.cursor.y as usize) > 0 {
+                    let line = &self.editor_content[self.cursor.y as usize];
+                    self.cursor.x = graphemes::next_boundary(line, self.cursor.x as usize) as i32;
+                }
+                let result = if kind == ClipboardKind::Block {
+                    self.visual_cursor_x = self.calculate_visual_x() as i32;
+                    self.paste_block()
+                } else {
+                    self.paste()
+                };
+                self.clamp_to_last_char();
+                result
+            }
+        }
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        if let Some(state) = self.modal.as_mut() {
+            state.mode = mode;
+        }
+    }
+
+    /// Selects from the visual anchor to the cursor, both chars included
+    fn update_visual_selection(&mut self) {
+        let (start, end) = self.visual_range();
+        self.text_selection_start = Some(start);
+        self.text_selection_end = Some(end);
+    }
+
+    fn visual_range(&self) -> (CursorPosition, CursorPosition) {
+        let anchor = self
+            .modal
+            .as_ref()
+            .map_or(self.cursor_position(), |state| state.visual_anchor);
+        let cursor = self.cursor_position();
+        let (start, mut end) = (anchor.min(cursor), anchor.max(cursor));
+        end.x = graphemes::next_boundary(&self.editor_content[end.y], end.x);
+        (start, end)
+    }
+
+    /// In normal mode the cursor sits on a char, not after the last one
+    fn clamp_to_last_char(&mut self) {
+        if self.mode() != Some(Mode::Normal) || self.editor_content.is_empty() {
+            return;
+        }
+        let line = &self.editor_content[self.cursor.y as usize];
+        let len = line.chars().count();
+        if len > 0 && self.cursor.x as usize >= len {
+            self.cursor.x = graphemes::prev_boundary(line, len) as i32;
+            self.visual_cursor_x = self.calculate_visual_x() as i32;
+        }
+    }
+
+    fn move_to_line_end(&mut self) {
+        let y = self.cursor.y as usize;
+        let x = self.editor_content.line_char_count(y);
+        self.set_cursor_position(&CursorPosition { x, y });
+    }
+
+    /// Primary cursor as a buffer position
+    pub(crate) fn cursor_position(&self) -> CursorPosition {
+        CursorPosition {
+            x: self.cursor.x.max(0) as usize,
+            y: self.cursor.y.max(0) as usize,
+        }
+    }
+
+    /// Char at position, None at the end of a line
+    fn char_at(&self, pos: CursorPosition) -> Option<char> {
+        self.editor_content.get(pos.y)?.chars().nth(pos.x)
+    }
+
+    /// Position after pos, the end of a line counts as a position of its own
+    fn next_position(&self, pos: CursorPosition) -> Option<CursorPosition> {
+        if pos.x < self.editor_content.line_char_count(pos.y) {
+            Some(CursorPosition {
+                x: pos.x + 1,
+                ..pos
+            })
+        } else if pos.y + 1 < self.editor_content.len() {
+            Some(CursorPosition { x: 0, y: pos.y + 1 })
+        } else {
+            None
+        }
+    }
+
+    fn prev_position(&self, pos: CursorPosition) -> Option<CursorPosition> {
+        if pos.x > 0 {
+            Some(CursorPosition {
+                x: pos.x - 1,
+                ..pos
+            })
+        } else if pos.y > 0 {
+            Some(CursorPosition {
+                x: self.editor_content.line_char_count(pos.y - 1),
+                y: pos.y - 1,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn is_empty_line_start(&self, pos: CursorPosition) -> bool {
+        pos.x == 0 && self.editor_content.line_char_count(pos.y) == 0
+    }
+
+    /// Start of the next word, an empty line counts as a word
+    fn next_word_start(&self, pos: CursorPosition) -> CursorPosition {
+        let class = char_class(self.char_at(pos));
+        let mut p = pos;
+        if class != CharClass::Blank {
+            loop {
+                let Some(next) = self.next_position(p) else {
+                    return p;
+                };
+                let crossed_line = next.y != p.y;
+                p = next;
+                if crossed_line || char_class(self.char_at(p)) != class {
+                    break;
+                }
+            }
+        }
+        while char_class(self.char_at(p)) == CharClass::Blank {
+            if p != pos && self.is_empty_line_start(p) {
+                return p;
+            }
+            match self.next_position(p) {
+                Some(next) => p = next,
+                None => return p,
+            }
+        }
+        p
+    }
+
+    /// Start of the word before the cursor, or of the one it is in
+    fn prev_word_start(&self, pos: CursorPosition) -> CursorPosition {
+        let Some(mut p) = self.prev_position(pos) else {
+            return pos;
+        };
+        while char_class(self.char_at(p)) == CharClass::Blank {
+            if self.is_empty_line_start(p) {
+                return p;
+            }
+            match self.prev_position(p) {
+                Some(prev) => p = prev,
+                None => return p,
+            }
+        }
+        let class = char_class(self.char_at(p));
+        while let Some(prev) = self.prev_position(p) {
+            if prev.y != p.y || char_class(self.char_at(prev)) != class {
+                break;
+            }
+            p = prev;
+        }
+        p
+    }
+
+    /// Last char of the word after the cursor, or of the one it is in
+    fn word_end(&self, pos: CursorPosition) -> CursorPosition {
+        let Some(mut p) = self.next_position(pos) else {
+            return pos;
+        };
+        while char_class(self.char_at(p)) == CharClass::Blank {
+            match self.next_position(p) {
+                Some(next) => p = next,
+                None => return p,
+            }
+        }
+        self.current_word_end(p)
+    }
+
+    /// Last char of the word at pos
+    fn current_word_end(&self, pos: CursorPosition) -> CursorPosition {
+        let mut p = pos;
+        let class = char_class(self.char_at(p));
+        while let Some(next) = self.next_position(p) {
+            if next.y != p.y || char_class(self.char_at(next)) != class {
+                break;
+            }
+            p = next;
+        }
+        p
+    }
+}
+
+/// Count of an operator and its motion multiplied, None if neither was given
+fn combined_count(operator_count: Option<usize>, motion_count: Option<usize>) -> Option<usize> {
+    match (operator_count, motion_count) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(1) * b.unwrap_or(1)),
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_modal_tests {
+    use super::*;
+    use crate::config::Config;
+    use std::sync::Arc;
+
+    fn create_modal_editor(vec: Vec<&str>) -> Editor {
+        let mut config = Config::default().editor;
+        config.modal_editing = true;
+        let mut editor = Editor::new(Arc::new(config));
+        editor.editor_content = vec.into_iter().map(String::from).collect();
+        editor.editor_height = 10;
+        editor
+    }
+
+    fn keys(editor: &mut Editor, keys: &str) {
+        for c in keys.chars() {
+            editor
+                .handle_input_action(InputAction::Editor(EditorAction::WriteChar(c)))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_word_motions() {
+        let mut editor = create_modal_editor(vec!["foo.bar baz", "", "  qux"]);
+        keys(&mut editor, "w");
+        assert_eq!(editor.cursor_position(), CursorPosition { x: 3, y: 0 });
+        keys(&mut editor, "2w");
+        assert_eq!(editor.cursor_position(), CursorPosition { x: 8, y: 0 });
+        keys(&mut editor, "w");
+        assert_eq!(editor.cursor_position(), CursorPosition { x: 0, y: 1 });
+        keys(&mut editor, "w");
+        assert_eq!(editor.cursor_position(), CursorPosition { x: 2, y: 2 });
+        keys(&mut editor, "bb");
+        assert_eq!(editor.cursor_position(), CursorPosition { x: 8, y: 0 });
+        keys(&mut editor, "0e");
+        assert_eq!(editor.cursor_position(), CursorPosition { x: 2, y: 0 });
+        keys(&mut editor, "$");
+        assert_eq!(editor.cursor_position(), CursorPosition { x: 10, y: 0 });
+        keys(&mut editor, "G");
+        assert_eq!(editor.cursor.y, 2);
+        keys(&mut editor, "gg");
+        assert_eq!(editor.cursor.y, 0);
+    }
+
+    #[test]
+    fn test_operators_with_motions_and_counts() {
+        let mut editor = create_modal_editor(vec!["one two three four"]);
+        keys(&mut editor, "dw");
+        assert_eq!(editor.editor_content.to_lines(), vec!["two three four"]);
+        keys(&mut editor, "2dw");
+        assert_eq!(editor.editor_content.to_lines(), vec!["four"]);
+        keys(&mut editor, "u");
+        assert_eq!(editor.editor_content.to_lines(), vec!["two three four"]);
+
+        keys(&mut editor, "0wd$");
+        assert_eq!(editor.editor_content.to_lines(), vec!["two "]);
+        assert_eq!(editor.clipboard.copied_text, vec!["three four"]);
+
+        keys(&mut editor, "0ye");
+        assert_eq!(editor.clipboard.copied_text, vec!["two"]);
+    }
+
+    #[test]
+    fn test_older_and_newer_states_reach_undone_branch() {
+        let mut editor = create_modal_editor(vec!["one two three"]);
+        keys(&mut editor, "dwu");
+        keys(&mut editor, "$x");
+        assert_eq!(editor.editor_content.to_lines(), vec!["one two thre"]);
+
+        keys(&mut editor, "g-");
+        assert_eq!(editor.editor_content.to_lines(), vec!["two three"]);
+        keys(&mut editor, "g+");
+        assert_eq!(editor.editor_content.to_lines(), vec!["one two thre"]);
+        keys(&mut editor, "2g-");
+        assert_eq!(editor.editor_content.to_lines(), vec!["one two three"]);
+    }
+
+    #[test]
+    fn test_percent_jumps_to_and_deletes_to_matching_bracket() {
+        let mut editor = create_modal_editor(vec!["f(a, (b)) x"]);
+        keys(&mut editor, "l%");
+        assert_eq!(editor.cursor_position(), CursorPosition { x: 8, y: 0 });
+        keys(&mut editor, "%");
+        assert_eq!(editor.cursor_position(), CursorPosition { x: 1, y: 0 });
+        keys(&mut editor, "d%");
+        assert_eq!(editor.editor_content.to_lines(), vec!["f x"]);
+    }
+
+    #[test]
+    fn test_set_and_jump_to_marks() {
+        let mut editor = create_modal_editor(vec!["a", "  b c", "d", "e"]);
+        keys(&mut editor, "jllllmxG");
+        assert_eq!(editor.cursor.y, 3);
+        keys(&mut editor, "'x");
+        assert_eq!(editor.cursor_position(), CursorPosition { x: 2, y: 1 });
+        keys(&mut editor, "G`x");
+        assert_eq!(editor.cursor_position(), CursorPosition { x: 4, y: 1 });
+        // a mark not set, or a jump after an operator, does nothing
+        keys(&mut editor, "'qd'x");
+        assert_eq!(editor.cursor_position(), CursorPosition { x: 4, y: 1 });
+        assert_eq!(editor.editor_content.len(), 4);
+    }
+
+    #[test]
+    fn test_change_word_enters_insert_mode() {
+        let mut editor = create_modal_editor(vec!["let x = 1;"]);
+        keys(&mut editor, "wcwy");
+        assert_eq!(editor.editor_content.to_lines(), vec!["let y = 1;"]);
+        assert_eq!(editor.mode(), Some(Mode::Insert));
+
+        editor.enter_normal_mode();
+        assert_eq!(editor.mode(), Some(Mode::Normal));
+        assert_eq!(editor.cursor.x, 4);
+    }
+
+    #[test]
+    fn test_linewise_delete_and_paste() {
+        let mut editor = create_modal_editor(vec!["a", "b", "c", "d"]);
+        keys(&mut editor, "j2dd");
+        assert_eq!(editor.editor_content.to_lines(), vec!["a", "d"]);
+        assert_eq!(editor.clipboard.kind, ClipboardKind::Lines);
+        keys(&mut editor, "p");
+        assert_eq!(editor.editor_content.to_lines(), vec!["a", "d", "b", "c"]);
+        assert_eq!(editor.cursor.y, 2);
+
+        keys(&mut editor, "Gdgg");
+        assert_eq!(editor.editor_content.to_lines(), vec![""]);
+    }
+
+    #[test]
+    fn test_visual_mode_yank_and_delete() {
+        let mut editor = create_modal_editor(vec!["hello world"]);
+        keys(&mut editor, "vey");
+        assert_eq!(editor.clipboard.copied_text, vec!["hello"]);
+        assert_eq!(editor.mode(), Some(Mode::Normal));
+
+        keys(&mut editor, "wvlld");
+        assert_eq!(editor.editor_content.to_lines(), vec!["hello ld"]);
+    }
+}
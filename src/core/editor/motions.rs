@@ -0,0 +1,261 @@
+//! Cursor motions beyond single cells: words, line boundaries, document
+//! boundaries and pages. Each one can also extend the text selection.
+
+use super::editor::Editor;
+use super::graphemes;
+use super::wrap;
+use crate::core::cursor::{to_coord, CursorPosition};
+use crate::input::actions::Motion;
+
+/// Char classes words are made of, a word is a run of one class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CharClass {
+    Blank,
+    Word,
+    Punctuation,
+}
+
+/// Class of a char, None stands for a line end and is blank
+pub(crate) fn char_class(c: Option<char>) -> CharClass {
+    match c {
+        None => CharClass::Blank,
+        Some(c) if c.is_whitespace() => CharClass::Blank,
+        Some(c) if c.is_alphanumeric() || c == '_' => CharClass::Word,
+        Some(_) => CharClass::Punctuation,
+    }
+}
+
+/// Index of the first char that is not blank, or the line length
+pub(crate) fn first_non_blank(line: &str) -> usize {
+    line.chars()
+        .position(|c| !c.is_whitespace())
+        .unwrap_or_else(|| line.chars().count())
+}
+
+fn class_at(line: &str, x: usize) -> CharClass {
+    char_class(line.chars().nth(x))
+}
+
+/// End of the word at or after x, skipping blanks in front of it
+fn word_right(line: &str, mut x: usize) -> usize {
+    let len = line.chars().count();
+    while x < len && class_at(line, x) == CharClass::Blank {
+        x = graphemes::next_boundary(line, x);
+    }
+    if x < len {
+        let class = class_at(line, x);
+        while x < len && class_at(line, x) == class {
+            x = graphemes::next_boundary(line, x);
+        }
+    }
+    x
+}
+
+/// Start of the word before x, skipping blanks behind it
+fn word_left(line: &str, mut x: usize) -> usize {
+    while x > 0 && class_at(line, graphemes::prev_boundary(line, x)) == CharClass::Blank {
+        x = graphemes::prev_boundary(line, x);
+    }
+    if x > 0 {
+        let class = class_at(line, graphemes::prev_boundary(line, x));
+        while x > 0 && class_at(line, graphemes::prev_boundary(line, x)) == class {
+            x = graphemes::prev_boundary(line, x);
+        }
+    }
+    x
+}
+
+impl Editor {
+    /// Moves the cursor by motion
+    pub(crate) fn move_by(&mut self, motion: Motion) {
+        let y = self.cursor.y.max(0) as usize;
+        let x = self.cursor.x.max(0) as usize;
+        match motion {
+            Motion::Up | Motion::Down | Motion::Left | Motion::Right => {
+                let (x, y) = motion.to_vector();
+                self.step_cursor(x, y);
+            }
+            Motion::WordLeft if x == 0 && y > 0 => {
+                let x = self.editor_content[y - 1].chars().count();
+                self.set_cursor_position(&CursorPosition { x, y: y - 1 });
+            }
+            Motion::WordLeft => {
+                let x = word_left(&self.editor_content[y], x);
+                self.set_cursor_position(&CursorPosition { x, y });
+            }
+            Motion::WordRight
+                if x >= self.editor_content[y].chars().count()
+                    && y + 1 < self.editor_content.len() =>
+            {
+                self.set_cursor_position(&CursorPosition { x: 0, y: y + 1 });
+            }
+            Motion::WordRight => {
+                let x = word_right(&self.editor_content[y], x);
+                self.set_cursor_position(&CursorPosition { x, y });
+            }
+            // smart Home, the first non-blank char first and the line start from there
+            Motion::LineStart => {
+                let indent = first_non_blank(&self.editor_content[y]);
+                let x = if x == indent { 0 } else { indent };
+                self.set_cursor_position(&CursorPosition { x, y });
+            }
+            Motion::LineEnd => {
+                let x = self.editor_content[y].chars().count();
+                self.set_cursor_position(&CursorPosition { x, y });
+            }
+            Motion::DocumentStart => {
+                self.record_jump();
+                self.set_cursor_position(&CursorPosition { x: 0, y: 0 });
+            }
+            Motion::DocumentEnd => {
+                self.record_jump();
+                let y = self.editor_content.len().saturating_sub(1);
+                let x = self
+                    .editor_content
+                    .get(y)
+                    .map_or(0, |line| line.chars().count());
+                self.set_cursor_position(&CursorPosition { x, y });
+            }
+            Motion::PageUp => self.move_page(-1),
+            Motion::PageDown => self.move_page(1),
+        }
+    }
+
+    /// Moves the cursor by motion, extending the selection to it
+    pub(crate) fn select_by(&mut self, motion: Motion) {
+        match motion.to_vector() {
+            (0, 0) => self.extend_selection_by(|editor| editor.move_by(motion)),
+            (x, y) => self.move_selection_cursor(x, y),
+        }
+    }
+
+    /// Moves the cursor and the view a page, keeping the cursor's column
+    fn move_page(&mut self, direction: i32) {
+        if self.editor_content.is_empty() {
+            return;
+        }
+        let page = (self.editor_height as usize).max(1);
+        let by_page = |from: usize| {
+            if direction < 0 {
+                from.saturating_sub(page)
+            } else {
+                from.saturating_add(page)
+            }
+        };
+        let last_line = self.editor_content.len() - 1;
+        let y = by_page(self.cursor.y.max(0) as usize).min(last_line);
+        let col = self.visual_cursor_x.max(0) as usize;
+        let tab_width = self.editor_config.tab_width as usize;
+        let x = wrap::char_idx_at_visual_col(&self.editor_content[y], col, tab_width);
+
+        let max_scroll = self.calculate_max_scroll().max(0) as usize;
+        let scroll = by_page(self.scroll_offset.max(0) as usize).min(max_scroll);
+        self.scroll_offset = to_coord(scroll);
+        self.set_cursor_position(&CursorPosition { x, y });
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_motions_tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::core::editor::goto::GotoTarget;
+    use std::sync::Arc;
+
+    fn create_editor_with_editor_content(vec: Vec<&str>) -> Editor {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = vec.into_iter().map(String::from).collect();
+        editor.editor_height = 10;
+        editor
+    }
+
+    #[test]
+    fn test_word_right_and_left_stop_at_word_edges() {
+        assert_eq!(word_right("foo.bar  baz", 0), 3);
+        assert_eq!(word_right("foo.bar  baz", 3), 4);
+        assert_eq!(word_right("foo.bar  baz", 7), 12);
+        assert_eq!(word_left("foo.bar  baz", 12), 9);
+        assert_eq!(word_left("foo.bar  baz", 9), 4);
+        // a combining accent stays with its letter
+        assert_eq!(word_right("cafe\u{301} ok", 0), 5);
+    }
+
+    #[test]
+    fn test_word_motions_cross_lines() {
+        let mut editor = create_editor_with_editor_content(vec!["one", "two"]);
+        editor.move_by(Motion::LineEnd);
+        editor.move_by(Motion::WordRight);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (0, 1));
+        editor.move_by(Motion::WordLeft);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (3, 0));
+    }
+
+    #[test]
+    fn test_smart_home_toggles_between_indent_and_line_start() {
+        let mut editor = create_editor_with_editor_content(vec!["    indented"]);
+        editor.move_by(Motion::LineEnd);
+        editor.move_by(Motion::LineStart);
+        assert_eq!(editor.cursor.x, 4);
+        editor.move_by(Motion::LineStart);
+        assert_eq!(editor.cursor.x, 0);
+        editor.move_by(Motion::LineStart);
+        assert_eq!(editor.cursor.x, 4);
+    }
+
+    #[test]
+    fn test_page_down_moves_cursor_and_view() {
+        let lines: Vec<String> = (0..50).map(|i| format!("line {i}")).collect();
+        let mut editor =
+            create_editor_with_editor_content(lines.iter().map(|l| l.as_str()).collect());
+        editor.move_by(Motion::Right);
+        editor.move_by(Motion::PageDown);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (1, 10));
+        assert_eq!(editor.scroll_offset, 10);
+
+        editor.move_by(Motion::DocumentEnd);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (7, 49));
+        editor.move_by(Motion::PageUp);
+        assert_eq!(editor.cursor.y, 39);
+        editor.move_by(Motion::DocumentStart);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (0, 0));
+    }
+
+    #[test]
+    fn test_paging_through_buffer_past_i16_lines() {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = (0..40_000).map(|i| format!("line {i}")).collect();
+        editor.editor_height = 10;
+        editor.goto(GotoTarget::Line {
+            line: 39_995,
+            column: None,
+        });
+        editor.move_by(Motion::PageDown);
+        assert_eq!(editor.cursor.y, 39_999);
+        assert!(editor.scroll_offset <= editor.calculate_max_scroll());
+
+        editor.move_by(Motion::PageUp);
+        assert_eq!(editor.cursor.y, 39_989);
+    }
+
+    #[test]
+    fn test_select_by_word_and_line_end() {
+        let mut editor = create_editor_with_editor_content(vec!["hello big world"]);
+        editor.select_by(Motion::WordRight);
+        editor.select_by(Motion::WordRight);
+        assert_eq!(editor.copy_selected_text().unwrap(), vec!["hello big"]);
+
+        editor.select_by(Motion::WordLeft);
+        assert_eq!(editor.copy_selected_text().unwrap(), vec!["hello "]);
+        editor.select_by(Motion::LineEnd);
+        assert_eq!(
+            editor.copy_selected_text().unwrap(),
+            vec!["hello big world"]
+        );
+    }
+}
@@ -0,0 +1,635 @@
+//! Secondary cursors, placed beside the primary one.
+//!
+//! The primary cursor and its selection stay in `Editor::cursor` and
+//! `text_selection_start`/`text_selection_end`, so everything written for a
+//! single cursor keeps working. With secondary cursors present, edits are made
+//! as one `ReplaceRange` per cursor, recorded together as a single
+//! `EditAction::Bulk` so one undo reverts the edit at every cursor.
+
+use super::editor::{EditAction, Editor};
+use super::graphemes;
+use super::text_buffer::{char_slice, char_to_byte_idx, TextStorage};
+use super::wrap;
+use crate::core::cursor::CursorPosition;
+use crate::errors::editor_errors::EditorError::{ClipboardFailure, TextSelectionFailure};
+use crate::errors::editor_errors::{ClipboardError, EditorError, TextSelectionError};
+
+/// A cursor besides the primary one, with its own selection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SecondaryCursor {
+    pub pos: CursorPosition,
+    pub selection_start: Option<CursorPosition>,
+    pub selection_end: Option<CursorPosition>,
+}
+
+impl SecondaryCursor {
+    pub fn new(pos: CursorPosition) -> Self {
+        Self {
+            pos,
+            selection_start: None,
+            selection_end: None,
+        }
+    }
+
+    /// Selected range, if the cursor has a non-empty selection
+    pub fn selection(&self) -> Option<(CursorPosition, CursorPosition)> {
+        match (self.selection_start, self.selection_end) {
+            (Some(start), Some(end)) if start != end => Some((start.min(end), start.max(end))),
+            _ => None,
+        }
+    }
+
+    /// Where in the text the cursor starts, used to order cursors
+    fn start(&self) -> CursorPosition {
+        self.selection().map(|(start, _)| start).unwrap_or(self.pos)
+    }
+}
+
+/// Range of text to replace at a cursor and the lines replacing it
+type CursorEdit = (CursorPosition, CursorPosition, Vec<String>);
+
+impl Editor {
+    pub fn has_secondary_cursors(&self) -> bool {
+        !self.secondary_cursors.is_empty()
+    }
+
+    /// Drops all secondary cursors, keeping the primary one
+    pub fn clear_secondary_cursors(&mut self) {
+        self.secondary_cursors.clear();
+    }
+
+    /// Adds a cursor on the line above the topmost cursor, or below the
+    /// bottommost one, at the same display column
+    pub fn add_cursor_vertical(&mut self, direction: i32) {
+        let cursors = self.all_cursors();
+        let edge = if direction < 0 {
+            cursors.iter().min_by_key(|c| c.pos)
+        } else {
+            cursors.iter().max_by_key(|c| c.pos)
+        };
+        let Some(edge) = edge.map(|c| c.pos) else {
+            return;
+        };
+        let target_y = edge.y as i64 + direction.signum() as i64;
+        if target_y < 0 || target_y as usize >= self.editor_content.len() {
+            return;
+        }
+        let tab_width = self.editor_config.tab_width as usize;
+        let col = wrap::visual_col(&self.editor_content[edge.y], edge.x, tab_width);
+        let target_line = &self.editor_content[target_y as usize];
+        let pos = CursorPosition {
+            x: wrap::char_idx_at_visual_col(target_line, col, tab_width),
+            y: target_y as usize,
+        };
+        self.secondary_cursors.push(SecondaryCursor::new(pos));
+        self.merge_cursors();
+    }
+
+    /// Adds a cursor selecting the next occurrence of the primary selection,
+    /// searching on from the last added cursor and wrapping around.
+    /// Without a selection the word under the cursor is selected first.
+    /// Returns false if there was nothing to select
+    pub fn add_cursor_at_next_match(&mut self) -> bool {
+        let (Some(start), Some(end)) = (self.text_selection_start, self.text_selection_end) else {
+            return self.select_word_at_cursor();
+        };
+        // only single line selections are searched for
+        if start.y != end.y || start.x == end.x {
+            return false;
+        }
+        let needle = char_slice(&self.editor_content[start.y], start.x, end.x).to_string();
+        let from = self
+            .secondary_cursors
+            .last()
+            .and_then(|c| c.selection())
+            .map(|(_, end)| end)
+            .unwrap_or(end);
+
+        let taken: Vec<CursorPosition> = self.all_cursors().iter().map(|c| c.start()).collect();
+        let Some(found) = self.find_from(&needle, from, &taken) else {
+            return false;
+        };
+        let found_end = CursorPosition {
+            x: found.x + needle.chars().count(),
+            y: found.y,
+        };
+        self.secondary_cursors.push(SecondaryCursor {
+            pos: found_end,
+            selection_start: Some(found),
+            selection_end: Some(found_end),
+        });
+        true
+    }
+
+    /// Runs a movement on every secondary cursor, by loading each into the
+    /// primary cursor fields in turn. The primary cursor is left untouched
+    pub(crate) fn for_each_secondary_cursor(&mut self, mut movement: impl FnMut(&mut Editor)) {
+        if !self.has_secondary_cursors() {
+            return;
+        }
+        let primary = self.primary_cursor();
+        let mut cursors = std::mem::take(&mut self.secondary_cursors);
+        for cursor in cursors.iter_mut() {
+            self.load_cursor(cursor);
+            movement(self);
+            *cursor = self.primary_cursor();
+        }
+        self.load_cursor(&primary);
+        self.secondary_cursors = cursors;
+    }
+
+    /// Inserts char at every cursor, replacing selections
+    pub(crate) fn write_char_at_cursors(&mut self, c: char) {
+        self.insert_at_cursors(vec![c.to_string()]);
+    }
+
+    /// Inserts a tab, or spaces when configured, at every cursor
+    pub(crate) fn tab_at_cursors(&mut self) {
+        self.insert_at_cursors(vec![self.indent_unit()]);
+    }
+
+    /// Splits the line at every cursor
+    pub(crate) fn enter_at_cursors(&mut self) {
+        self.insert_at_cursors(vec![String::new(), String::new()]);
+    }
+
+    /// Removes selections, or the grapheme before every cursor, joining
+    /// lines for cursors at the start of a line
+    pub(crate) fn backspace_at_cursors(&mut self) {
+        self.edit_at_cursors(|editor, cursor, _| {
+            if let Some((start, end)) = cursor.selection() {
+                return Some((start, end, vec![String::new()]));
+            }
+            let pos = cursor.pos;
+            let start = if pos.x > 0 {
+                let line = &editor.editor_content[pos.y];
+                CursorPosition {
+                    x: graphemes::prev_boundary(line, pos.x),
+                    y: pos.y,
+                }
+            } else if pos.y > 0 {
+                CursorPosition {
+                    x: editor.editor_content.line_char_count(pos.y - 1),
+                    y: pos.y - 1,
+                }
+            } else {
+                return None;
+            };
+            Some((start, pos, vec![String::new()]))
+        });
+    }
+
+    /// Removes selections, or deletes at every cursor the same way a single
+    /// cursor delete does
+    pub(crate) fn delete_at_cursors(&mut self) {
+        self.edit_at_cursors(|editor, cursor, _| {
+            if let Some((start, end)) = cursor.selection() {
+                return Some((start, end, vec![String::new()]));
+            }
+            let pos = cursor.pos;
+            let line = &editor.editor_content[pos.y];
+            let len = line.chars().count();
+            if len == 0 {
+                return None;
+            }
+            let next = graphemes::next_boundary(line, pos.x);
+            if next >= len && pos.y + 1 < editor.editor_content.len() {
+                let end = CursorPosition { x: 0, y: pos.y + 1 };
+                Some((
+                    CursorPosition { x: len, y: pos.y },
+                    end,
+                    vec![String::new()],
+                ))
+            } else if next < len {
+                let start = CursorPosition { x: next, y: pos.y };
+                let end = CursorPosition {
+                    x: graphemes::next_boundary(line, next),
+                    y: pos.y,
+                };
+                Some((start, end, vec![String::new()]))
+            } else {
+                None
+            }
+        });
+    }
+
+    /// Pastes lines at every cursor. When there are as many lines as cursors,
+    /// each cursor gets its own line, matching a copy from the same cursors
+    pub(crate) fn paste_at_cursors(&mut self, lines: Vec<String>) {
+        let per_cursor = lines.len() > 1 && lines.len() == self.secondary_cursors.len() + 1;
+        self.edit_at_cursors(|_, cursor, rank| {
+            let (start, end) = cursor.selection().unwrap_or((cursor.pos, cursor.pos));
+            let new = if per_cursor {
+                vec![lines[rank].clone()]
+            } else {
+                lines.clone()
+            };
+            Some((start, end, new))
+        });
+    }
+
+    /// Copies the selections of all cursors to the clipboard, in text order
+    pub(crate) fn copy_at_cursors(&mut self) -> Result<(), EditorError> {
+        let selected_text = self.selected_text_at_cursors();
+        if selected_text.is_empty() {
+            return Err(TextSelectionFailure(TextSelectionError::NoTextSelected));
+        }
+        self.clipboard.copy(&selected_text);
+        self.reset_text_selection_cursor();
+        for cursor in self.secondary_cursors.iter_mut() {
+            *cursor = SecondaryCursor::new(cursor.pos);
+        }
+        Ok(())
+    }
+
+    /// Copies the selections of all cursors to the clipboard and removes them
+    pub(crate) fn cut_at_cursors(&mut self) -> Result<(), EditorError> {
+        let selected_text = self.selected_text_at_cursors();
+        if selected_text.is_empty() {
+            return Err(TextSelectionFailure(TextSelectionError::NoTextSelected));
+        }
+        self.clipboard.copy(&selected_text);
+        self.delete_selections_at_cursors();
+        Ok(())
+    }
+
+    /// Pastes clipboard content at every cursor
+    pub(crate) fn paste_clipboard_at_cursors(&mut self) -> Result<(), EditorError> {
+        let copied_text = self.clipboard.paste();
+        if copied_text.is_empty() {
+            return Err(ClipboardFailure(ClipboardError::NoCopiedText));
+        }
+        self.paste_at_cursors(copied_text);
+        Ok(())
+    }
+
+    /// Text selected at every cursor in text order, one entry per line touched
+    pub(crate) fn selected_text_at_cursors(&self) -> Vec<String> {
+        let mut cursors = self.all_cursors();
+        cursors.sort_by_key(|c| c.start());
+        cursors
+            .iter()
+            .filter_map(|c| c.selection())
+            .flat_map(|(start, end)| self.editor_content.text_in_range(start, end))
+            .collect()
+    }
+
+    /// Removes the selection at every cursor
+    pub(crate) fn delete_selections_at_cursors(&mut self) {
+        self.edit_at_cursors(|_, cursor, _| {
+            cursor
+                .selection()
+                .map(|(start, end)| (start, end, vec![String::new()]))
+        });
+    }
+
+    /// Replaces selection, or inserts at the cursor when there is none, with lines
+    fn insert_at_cursors(&mut self, lines: Vec<String>) {
+        self.edit_at_cursors(|_, cursor, _| {
+            let (start, end) = cursor.selection().unwrap_or((cursor.pos, cursor.pos));
+            Some((start, end, lines.clone()))
+        });
+    }
+
+    /// Applies an edit at every cursor, last one in the text first so earlier
+    /// edits do not move the ranges of those still to come. Cursors already
+    /// edited are shifted along as text before them changes.
+    /// `edit` gets the cursor and its rank in text order, and gives the range
+    /// to replace and the lines to put there, or None to leave it as is
+    fn edit_at_cursors(
+        &mut self,
+        edit: impl Fn(&Editor, &SecondaryCursor, usize) -> Option<CursorEdit>,
+    ) {
+        let mut cursors = self.all_cursors();
+        let mut order: Vec<usize> = (0..cursors.len()).collect();
+        order.sort_by_key(|&i| cursors[i].start());
+
+        let mut actions = Vec::new();
+        for (rank, &i) in order.iter().enumerate().rev() {
+            let Some((start, end, new)) = edit(self, &cursors[i], rank) else {
+                cursors[i] = SecondaryCursor::new(cursors[i].pos);
+                continue;
+            };
+            let old = self.editor_content.text_in_range(start, end);
+            self.editor_content.replace_text(start, &old, new.clone());
+
+            // cursors edited before this one come after it in the text
+            for &j in &order[rank + 1..] {
+                cursors[j].pos = shift_position(cursors[j].pos, start, end, &new);
+            }
+            cursors[i] = SecondaryCursor::new(end_of_insert(start, &new));
+            actions.push(EditAction::ReplaceRange {
+                start,
+                end,
+                old,
+                new,
+            });
+        }
+
+        self.record_undo_group(actions);
+        self.set_all_cursors(cursors);
+    }
+
+    /// Primary cursor followed by the secondary ones
+    fn all_cursors(&self) -> Vec<SecondaryCursor> {
+        let mut cursors = vec![self.primary_cursor()];
+        cursors.extend(self.secondary_cursors.iter().copied());
+        cursors
+    }
+
+    /// Sets cursors from a list with the primary cursor first
+    fn set_all_cursors(&mut self, mut cursors: Vec<SecondaryCursor>) {
+        if cursors.is_empty() {
+            return;
+        }
+        let primary = cursors.remove(0);
+        self.load_cursor(&primary);
+        self.secondary_cursors = cursors;
+        self.merge_cursors();
+    }
+
+    /// Removes secondary cursors sitting on the same position as another cursor
+    pub(crate) fn merge_cursors(&mut self) {
+        let mut seen = vec![self.primary_cursor().pos];
+        self.secondary_cursors.retain(|cursor| {
+            if seen.contains(&cursor.pos) {
+                false
+            } else {
+                seen.push(cursor.pos);
+                true
+            }
+        });
+    }
+
+    fn primary_cursor(&self) -> SecondaryCursor {
+        SecondaryCursor {
+            pos: CursorPosition {
+                x: self.cursor.x.max(0) as usize,
+                y: self.cursor.y.max(0) as usize,
+            },
+            selection_start: self.text_selection_start,
+            selection_end: self.text_selection_end,
+        }
+    }
+
+    fn load_cursor(&mut self, cursor: &SecondaryCursor) {
+        self.set_cursor_position(&cursor.pos);
+        self.text_selection_start = cursor.selection_start;
+        self.text_selection_end = cursor.selection_end;
+    }
+
+    /// Selects the word the primary cursor is on, or right behind
+    fn select_word_at_cursor(&mut self) -> bool {
+        let Some(line) = self.editor_content.get(self.cursor.y.max(0) as usize) else {
+            return false;
+        };
+        let chars: Vec<char> = line.chars().collect();
+        let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+        let x = self.cursor.x.max(0) as usize;
+        let at = if chars.get(x).is_some_and(is_word) {
+            x
+        } else if x > 0 && chars.get(x - 1).is_some_and(is_word) {
+            x - 1
+        } else {
+            return false;
+        };
+        let start = (0..at)
+            .rev()
+            .take_while(|&i| is_word(&chars[i]))
+            .last()
+            .unwrap_or(at);
+        let end = (at..chars.len())
+            .take_while(|&i| is_word(&chars[i]))
+            .last()
+            .unwrap_or(at)
+            + 1;
+
+        let y = self.cursor.y as usize;
+        self.text_selection_start = Some(CursorPosition { x: start, y });
+        self.text_selection_end = Some(CursorPosition { x: end, y });
+        self.cursor.x = end as i32;
+        self.visual_cursor_x =
+            wrap::visual_col(line, end, self.editor_config.tab_width as usize) as i32;
+        true
+    }
+
+    /// Start of the first occurrence of needle at or after `from`, wrapping
+    /// around to the start, skipping occurrences starting at `taken`
+    fn find_from(
+        &self,
+        needle: &str,
+        from: CursorPosition,
+        taken: &[CursorPosition],
+    ) -> Option<CursorPosition> {
+        let line_count = self.editor_content.len();
+        // the line of `from` is searched twice, after it first and before it last
+        for step in 0..=line_count {
+            let y = (from.y + step) % line_count;
+            let line = &self.editor_content[y];
+            let min_x = if step == 0 { from.x } else { 0 };
+            let mut byte_start = char_to_byte_idx(line, min_x);
+            while let Some(found) = line[byte_start..].find(needle) {
+                let byte_idx = byte_start + found;
+                let pos = CursorPosition {
+                    x: line[..byte_idx].chars().count(),
+                    y,
+                };
+                if step == line_count && pos.x >= from.x {
+                    break;
+                }
+                if !taken.contains(&pos) {
+                    return Some(pos);
+                }
+                byte_start = byte_idx + needle.len().max(1);
+            }
+        }
+        None
+    }
+}
+
+/// Where the cursor ends up after lines were put in at start
+fn end_of_insert(start: CursorPosition, new: &[String]) -> CursorPosition {
+    let last_len = new.last().map(|s| s.chars().count()).unwrap_or(0);
+    if new.len() <= 1 {
+        CursorPosition {
+            x: start.x + last_len,
+            y: start.y,
+        }
+    } else {
+        CursorPosition {
+            x: last_len,
+            y: start.y + new.len() - 1,
+        }
+    }
+}
+
+/// New place of a position at or after `end`, once the text from `start`
+/// to `end` is replaced with lines. Positions before `start` do not move
+pub(crate) fn shift_position(
+    pos: CursorPosition,
+    start: CursorPosition,
+    end: CursorPosition,
+    new: &[String],
+) -> CursorPosition {
+    if pos < end {
+        return pos;
+    }
+    let new_end = end_of_insert(start, new);
+    if pos.y == end.y {
+        CursorPosition {
+            x: new_end.x + (pos.x - end.x),
+            y: new_end.y,
+        }
+    } else {
+        CursorPosition {
+            x: pos.x,
+            y: pos.y + new_end.y - end.y,
+        }
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_multi_cursor_tests {
+    use super::*;
+    use crate::config::Config;
+    use std::sync::Arc;
+
+    fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = vec.into();
+        editor.editor_height = 10;
+        editor
+    }
+
+    fn lines(editor: &Editor) -> Vec<String> {
+        editor.editor_content.to_lines()
+    }
+
+    #[test]
+    fn test_add_cursor_below_and_write_is_one_undo_step() {
+        let mut editor =
+            create_editor_with_editor_content(vec!["abc".to_string(), "abc".to_string()]);
+        editor.move_cursor(1, 0);
+        editor.add_cursor_vertical(1);
+        assert_eq!(editor.secondary_cursors.len(), 1);
+
+        editor.write_char_at_cursors('x');
+        assert_eq!(lines(&editor), vec!["axbc", "axbc"]);
+        assert_eq!(editor.cursor.x, 2);
+        assert_eq!(
+            editor.secondary_cursors[0].pos,
+            CursorPosition { x: 2, y: 1 }
+        );
+
+        editor.undo().unwrap();
+        assert_eq!(lines(&editor), vec!["abc", "abc"]);
+    }
+
+    #[test]
+    fn test_cursors_on_same_line_shift_with_edits() {
+        let mut editor = create_editor_with_editor_content(vec!["a b c".to_string()]);
+        editor.secondary_cursors = vec![
+            SecondaryCursor::new(CursorPosition { x: 2, y: 0 }),
+            SecondaryCursor::new(CursorPosition { x: 4, y: 0 }),
+        ];
+        editor.write_char_at_cursors('_');
+        assert_eq!(lines(&editor), vec!["_a _b _c"]);
+        assert_eq!(editor.cursor.x, 1);
+        assert_eq!(editor.secondary_cursors[0].pos.x, 4);
+        assert_eq!(editor.secondary_cursors[1].pos.x, 7);
+    }
+
+    #[test]
+    fn test_backspace_at_line_start_joins_and_shifts_cursors_below() {
+        let mut editor = create_editor_with_editor_content(vec![
+            "ab".to_string(),
+            "cd".to_string(),
+            "ef".to_string(),
+        ]);
+        editor.set_cursor_position(&CursorPosition { x: 0, y: 1 });
+        editor.secondary_cursors = vec![SecondaryCursor::new(CursorPosition { x: 1, y: 2 })];
+        editor.backspace_at_cursors();
+        assert_eq!(lines(&editor), vec!["abcd", "f"]);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (2, 0));
+        assert_eq!(
+            editor.secondary_cursors[0].pos,
+            CursorPosition { x: 0, y: 1 }
+        );
+
+        editor.undo().unwrap();
+        assert_eq!(lines(&editor), vec!["ab", "cd", "ef"]);
+    }
+
+    #[test]
+    fn test_enter_at_cursors_shifts_lines_below() {
+        let mut editor =
+            create_editor_with_editor_content(vec!["ab".to_string(), "cd".to_string()]);
+        editor.move_cursor(1, 0);
+        editor.add_cursor_vertical(1);
+        editor.enter_at_cursors();
+        assert_eq!(lines(&editor), vec!["a", "b", "c", "d"]);
+        assert_eq!(
+            editor.secondary_cursors[0].pos,
+            CursorPosition { x: 0, y: 3 }
+        );
+    }
+
+    #[test]
+    fn test_next_match_selects_word_then_adds_cursors() {
+        let mut editor =
+            create_editor_with_editor_content(vec!["foo bar".to_string(), "bar foo".to_string()]);
+        assert!(editor.add_cursor_at_next_match());
+        assert_eq!(
+            editor.text_selection_start,
+            Some(CursorPosition { x: 0, y: 0 })
+        );
+        assert_eq!(
+            editor.text_selection_end,
+            Some(CursorPosition { x: 3, y: 0 })
+        );
+
+        assert!(editor.add_cursor_at_next_match());
+        assert_eq!(
+            editor.secondary_cursors[0].selection(),
+            Some((CursorPosition { x: 4, y: 1 }, CursorPosition { x: 7, y: 1 }))
+        );
+        // every occurrence has a cursor, nothing left to add
+        assert!(!editor.add_cursor_at_next_match());
+
+        editor.write_char_at_cursors('x');
+        assert_eq!(lines(&editor), vec!["x bar", "bar x"]);
+    }
+
+    #[test]
+    fn test_paste_gives_each_cursor_its_own_line() {
+        let mut editor = create_editor_with_editor_content(vec!["a".to_string(), "b".to_string()]);
+        editor.add_cursor_vertical(1);
+        editor.paste_at_cursors(vec!["1".to_string(), "2".to_string()]);
+        assert_eq!(lines(&editor), vec!["1a", "2b"]);
+    }
+
+    #[test]
+    fn test_shift_position_over_multi_line_replace() {
+        let start = CursorPosition { x: 1, y: 0 };
+        let end = CursorPosition { x: 2, y: 1 };
+        let new = vec!["x".to_string()];
+        assert_eq!(
+            shift_position(CursorPosition { x: 4, y: 1 }, start, end, &new),
+            CursorPosition { x: 4, y: 0 }
+        );
+        assert_eq!(
+            shift_position(CursorPosition { x: 3, y: 5 }, start, end, &new),
+            CursorPosition { x: 3, y: 4 }
+        );
+        assert_eq!(
+            shift_position(CursorPosition { x: 0, y: 0 }, start, end, &new),
+            CursorPosition { x: 0, y: 0 }
+        );
+    }
+}
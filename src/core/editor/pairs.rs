@@ -0,0 +1,240 @@
+//! Bracket and quote pairs: closing them as they are typed, typing over and
+//! deleting the closer, and finding the bracket matching the one at the cursor.
+
+use super::editor::Editor;
+use crate::core::cursor::CursorPosition;
+
+/// Brackets that nest and are matched against each other
+const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+/// How many lines away from the cursor a matching bracket is searched for,
+/// keeping the lookup done on every redraw cheap in large files
+const MATCH_SEARCH_LINES: usize = 2000;
+
+impl Editor {
+    /// Configured pairs that are exactly an opening and a closing char
+    fn auto_pairs(&self) -> impl Iterator<Item = (char, char)> + '_ {
+        self.editor_config.auto_pairs.iter().filter_map(|pair| {
+            let mut chars = pair.chars();
+            match (chars.next(), chars.next(), chars.next()) {
+                (Some(open), Some(close), None) => Some((open, close)),
+                _ => None,
+            }
+        })
+    }
+
+    /// Char at an offset from the cursor on the cursor's line
+    fn char_near_cursor(&self, offset: isize) -> Option<char> {
+        let x = (self.cursor.x as usize).checked_add_signed(offset)?;
+        self.editor_content
+            .get(self.cursor.y as usize)?
+            .chars()
+            .nth(x)
+    }
+
+    /// Whether typing c only steps over the same closer right after the cursor
+    pub(crate) fn types_over(&self, c: char) -> bool {
+        self.char_near_cursor(0) == Some(c) && self.auto_pairs().any(|(_, close)| close == c)
+    }
+
+    /// Closer to insert along with c. Pairs only close in front of blanks or
+    /// closers, and a quote right after a word char is taken as an apostrophe
+    pub(crate) fn auto_closer(&self, c: char) -> Option<char> {
+        let (open, close) = self.auto_pairs().find(|&(open, _)| open == c)?;
+        let next = self.char_near_cursor(0);
+        let before_closer = next.is_some_and(|n| self.auto_pairs().any(|(_, close)| close == n));
+        if !(next.is_none_or(char::is_whitespace) || before_closer) {
+            return None;
+        }
+        let after_word = self
+            .char_near_cursor(-1)
+            .is_some_and(|p| p.is_alphanumeric() || p == '_');
+        if open == close && after_word {
+            return None;
+        }
+        Some(close)
+    }
+
+    /// Whether the cursor sits between an opener and its closer
+    pub(crate) fn in_empty_pair(&self) -> bool {
+        match (self.char_near_cursor(-1), self.char_near_cursor(0)) {
+            (Some(prev), Some(next)) => self.auto_pairs().any(|pair| pair == (prev, next)),
+            _ => false,
+        }
+    }
+
+    /// Writes a pair at the cursor as one undo step, placing the cursor inside
+    pub(crate) fn write_pair(&mut self, open: char, close: char) {
+        let pos = self.cursor_position();
+        let action = self.replace_range(pos, pos, vec![format!("{open}{close}")]);
+        self.undo_redo_manager.record_undo(action);
+        self.set_cursor_position(&CursorPosition {
+            x: pos.x + 1,
+            y: pos.y,
+        });
+    }
+
+    /// Steps over the closer after the cursor instead of writing another one
+    pub(crate) fn type_over(&mut self) {
+        let pos = self.cursor_position();
+        self.set_cursor_position(&CursorPosition {
+            x: pos.x + 1,
+            y: pos.y,
+        });
+    }
+
+    /// Removes the empty pair around the cursor as one undo step
+    pub(crate) fn delete_pair(&mut self) {
+        let pos = self.cursor_position();
+        let start = CursorPosition {
+            x: pos.x - 1,
+            y: pos.y,
+        };
+        let end = CursorPosition {
+            x: pos.x + 1,
+            y: pos.y,
+        };
+        let action = self.replace_range(start, end, vec![String::new()]);
+        self.undo_redo_manager.record_undo(action);
+        self.set_cursor_position(&start);
+    }
+
+    /// The bracket at the cursor, or right before it, and the bracket matching it
+    pub fn matching_bracket(&self) -> Option<(CursorPosition, CursorPosition)> {
+        let pos = self.cursor_position();
+        let line = self.editor_content.get(pos.y)?;
+        let candidates = [Some(pos.x), pos.x.checked_sub(1)];
+        candidates.into_iter().flatten().find_map(|x| {
+            let c = line.chars().nth(x)?;
+            let bracket = CursorPosition { x, y: pos.y };
+            BRACKETS.iter().find_map(|&(open, close)| {
+                let found = if c == open {
+                    self.find_bracket(bracket, open, close, true)
+                } else if c == close {
+                    self.find_bracket(bracket, close, open, false)
+                } else {
+                    None
+                };
+                found.map(|other| (bracket, other))
+            })
+        })
+    }
+
+    /// Moves the cursor onto the bracket matching the one at the cursor
+    pub(crate) fn jump_to_matching_bracket(&mut self) {
+        if let Some((_, other)) = self.matching_bracket() {
+            self.set_cursor_position(&other);
+        }
+    }
+
+    /// Searches from a bracket for its partner, skipping nested pairs of the same kind
+    fn find_bracket(
+        &self,
+        from: CursorPosition,
+        this: char,
+        other: char,
+        forward: bool,
+    ) -> Option<CursorPosition> {
+        let ys: Vec<usize> = if forward {
+            let last = (from.y + MATCH_SEARCH_LINES).min(self.editor_content.len());
+            (from.y..last).collect()
+        } else {
+            (from.y.saturating_sub(MATCH_SEARCH_LINES)..=from.y)
+                .rev()
+                .collect()
+        };
+        let mut depth = 0;
+        for y in ys {
+            let chars: Vec<char> = self.editor_content[y].chars().collect();
+            let xs: Vec<usize> = match (forward, y == from.y) {
+                (true, true) => (from.x + 1..chars.len()).collect(),
+                (true, false) => (0..chars.len()).collect(),
+                (false, true) => (0..from.x).rev().collect(),
+                (false, false) => (0..chars.len()).rev().collect(),
+            };
+            for x in xs {
+                if chars[x] == this {
+                    depth += 1;
+                } else if chars[x] == other {
+                    if depth == 0 {
+                        return Some(CursorPosition { x, y });
+                    }
+                    depth -= 1;
+                }
+            }
+        }
+        None
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_pairs_tests {
+    use super::*;
+    use crate::config::Config;
+    use std::sync::Arc;
+
+    fn create_editor_with_editor_content(vec: Vec<&str>) -> Editor {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = vec.into_iter().map(String::from).collect();
+        editor
+    }
+
+    #[test]
+    fn test_auto_closer_only_in_front_of_blanks_and_closers() {
+        let mut editor = create_editor_with_editor_content(vec!["ab )"]);
+        assert_eq!(editor.auto_closer('('), None);
+        editor.cursor.x = 4;
+        assert_eq!(editor.auto_closer('('), Some(')'));
+        assert_eq!(editor.auto_closer('x'), None);
+        editor.cursor.x = 3;
+        assert_eq!(editor.auto_closer('['), Some(']'));
+        // a quote after a word char is an apostrophe
+        editor.cursor.x = 2;
+        assert_eq!(editor.auto_closer('"'), None);
+    }
+
+    #[test]
+    fn test_write_pair_type_over_and_delete_pair() {
+        let mut editor = create_editor_with_editor_content(vec![""]);
+        editor.write_pair('(', ')');
+        assert_eq!(editor.editor_content[0], "()");
+        assert_eq!(editor.cursor.x, 1);
+        assert!(editor.in_empty_pair());
+        assert!(editor.types_over(')'));
+
+        editor.delete_pair();
+        assert_eq!(editor.editor_content[0], "");
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content[0], "()");
+    }
+
+    #[test]
+    fn test_matching_bracket_skips_nested_pairs_across_lines() {
+        let mut editor = create_editor_with_editor_content(vec!["fn a() {", "  (b[0])", "}"]);
+        editor.cursor.x = 7;
+        assert_eq!(
+            editor.matching_bracket(),
+            Some((CursorPosition { x: 7, y: 0 }, CursorPosition { x: 0, y: 2 }))
+        );
+
+        // right after a closer counts too
+        editor.cursor.x = 8;
+        editor.cursor.y = 1;
+        assert_eq!(
+            editor.matching_bracket(),
+            Some((CursorPosition { x: 7, y: 1 }, CursorPosition { x: 2, y: 1 }))
+        );
+        editor.jump_to_matching_bracket();
+        assert_eq!((editor.cursor.x, editor.cursor.y), (2, 1));
+
+        editor.cursor.x = 0;
+        editor.cursor.y = 0;
+        assert_eq!(editor.matching_bracket(), None);
+    }
+}
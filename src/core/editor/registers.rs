@@ -0,0 +1,134 @@
+//! Named registers, copying into one and pasting from it or from the history.
+
+use super::editor::Editor;
+use crate::core::clipboard::ClipboardEntry;
+use crate::errors::editor_errors::{ClipboardError, EditorError};
+use crate::input::actions::{EditorAction, InputAction};
+
+impl Editor {
+    /// Copies the selected text into register name, leaving what paste
+    /// pastes as it is
+    pub fn copy_to_register(&mut self, name: char) -> Result<(), EditorError> {
+        let text = self.copy_selected_text()?;
+        self.clipboard.set_register(
+            name,
+            ClipboardEntry {
+                text,
+                kind: Default::default(),
+            },
+        );
+        self.reset_text_selection_cursor();
+        Ok(())
+    }
+
+    /// Pastes register name the way paste pastes the clipboard. Digits paste
+    /// entries of the clipboard history
+    pub fn paste_from_register(&mut self, name: char) -> Result<(), EditorError> {
+        let entry = self
+            .clipboard
+            .register(name)
+            .cloned()
+            .ok_or(ClipboardError::NoCopiedText)?;
+        let current = self.clipboard.current();
+        self.clipboard.restore(entry);
+        let result = self.handle_input_action(InputAction::Editor(EditorAction::PASTE));
+        self.clipboard.restore(current);
+        result
+    }
+
+    /// Pastes entry idx of the clipboard history, which becomes the one
+    /// paste pastes from then on
+    pub fn paste_from_history(&mut self, idx: usize) -> Result<(), EditorError> {
+        if !self.clipboard.use_history_entry(idx) {
+            return Err(ClipboardError::NoCopiedText.into());
+        }
+        self.handle_input_action(InputAction::Editor(EditorAction::PASTE))
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_registers_tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::core::clipboard::ClipboardKind;
+    use crate::core::cursor::CursorPosition;
+    use std::sync::Arc;
+
+    fn create_editor_with_editor_content(vec: Vec<&str>) -> Editor {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = vec.into_iter().map(String::from).collect();
+        editor
+    }
+
+    fn select(editor: &mut Editor, y: usize, from: usize, to: usize) {
+        editor.text_selection_start = Some(CursorPosition { x: from, y });
+        editor.text_selection_end = Some(CursorPosition { x: to, y });
+    }
+
+    #[test]
+    fn test_history_keeps_latest_copies_newest_first() {
+        let mut editor = create_editor_with_editor_content(vec!["one two three"]);
+        for (from, to) in [(0, 3), (4, 7), (0, 3), (8, 13)] {
+            select(&mut editor, 0, from, to);
+            editor.copy().unwrap();
+        }
+        let history: Vec<&Vec<String>> = editor.clipboard.history().map(|e| &e.text).collect();
+        assert_eq!(history, vec![&vec!["three"], &vec!["one"], &vec!["two"]]);
+
+        let mut small = crate::core::clipboard::Clipboard::with_history_limit(2);
+        for text in ["a", "b", "c"] {
+            small.copy(&[text.to_string()]);
+        }
+        assert_eq!(small.history().count(), 2);
+        assert_eq!(
+            small.register('1').map(|e| e.text.clone()),
+            Some(vec!["b".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_register_paste_keeps_the_clipboard() {
+        let mut editor = create_editor_with_editor_content(vec!["alpha beta", ""]);
+        select(&mut editor, 0, 0, 5);
+        editor.copy_to_register('a').unwrap();
+        assert!(editor.clipboard.copied_text.is_empty());
+
+        select(&mut editor, 0, 6, 10);
+        editor.copy().unwrap();
+        editor.cursor.y = 1;
+        editor.cursor.x = 0;
+        editor.paste_from_register('a').unwrap();
+        editor.paste().unwrap();
+        // paste leaves the cursor in front of the pasted text
+        assert_eq!(editor.editor_content[1], "betaalpha");
+        assert_eq!(editor.clipboard.kind, ClipboardKind::Text);
+
+        assert!(editor.paste_from_register('z').is_err());
+    }
+
+    #[test]
+    fn test_paste_from_history_moves_entry_to_front() {
+        let mut editor = create_editor_with_editor_content(vec!["x y", ""]);
+        select(&mut editor, 0, 0, 1);
+        editor.copy().unwrap();
+        select(&mut editor, 0, 2, 3);
+        editor.copy().unwrap();
+
+        editor.cursor.y = 1;
+        editor.cursor.x = 0;
+        editor.paste_from_history(1).unwrap();
+        assert_eq!(editor.editor_content[1], "x");
+        assert_eq!(editor.clipboard.copied_text, vec!["x"]);
+        assert_eq!(
+            editor.clipboard.history().next().map(|e| e.text.clone()),
+            Some(vec!["x".to_string()])
+        );
+        assert!(editor.paste_from_history(5).is_err());
+    }
+}
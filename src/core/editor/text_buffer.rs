@@ -0,0 +1,852 @@
+use super::super::cursor::CursorPosition;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Index, IndexMut, Range, RangeBounds};
+use std::sync::Arc;
+
+/// Maximum number of lines a single chunk holds before it is split in two.
+const MAX_CHUNK_LINES: usize = 1024;
+/// Chunks that shrink below this are merged into a neighbour when possible.
+const MIN_CHUNK_LINES: usize = MAX_CHUNK_LINES / 4;
+
+/// Line oriented text storage used by the editor.
+///
+/// Positions are given in chars (not bytes), matching `CursorPosition`.
+/// Only the primitive line operations are required, everything else has a
+/// default implementation built on top of them.
+pub trait TextStorage {
+    /// Number of lines held
+    fn line_count(&self) -> usize;
+
+    /// Line at index, if present
+    fn line(&self, idx: usize) -> Option<&str>;
+
+    /// Mutable line at index, if present
+    fn line_mut(&mut self, idx: usize) -> Option<&mut String>;
+
+    /// Inserts lines so the first of them ends up at `idx`
+    fn insert_lines(&mut self, idx: usize, lines: Vec<String>);
+
+    /// Removes the lines in range, returning them
+    fn remove_lines(&mut self, range: Range<usize>) -> Vec<String>;
+
+    /// Number of chars on line, 0 if line does not exist
+    fn line_char_count(&self, idx: usize) -> usize {
+        self.line(idx).map(|l| l.chars().count()).unwrap_or(0)
+    }
+
+    /// Appends lines until `idx` is a valid line index
+    fn ensure_line(&mut self, idx: usize) {
+        let count = self.line_count();
+        if count <= idx {
+            let missing = idx + 1 - count;
+            self.insert_lines(count, vec![String::new(); missing]);
+        }
+    }
+
+    /// Inserts char at position, clamping x to the line length
+    fn insert_char(&mut self, pos: CursorPosition, c: char) {
+        self.ensure_line(pos.y);
+        if let Some(line) = self.line_mut(pos.y) {
+            let byte_idx = char_to_byte_idx(line, pos.x);
+            line.insert(byte_idx, c);
+        }
+    }
+
+    /// Removes char at position, returning it if one was present
+    fn remove_char(&mut self, pos: CursorPosition) -> Option<char> {
+        let line = self.line_mut(pos.y)?;
+        if pos.x < line.chars().count() {
+            let byte_idx = char_to_byte_idx(line, pos.x);
+            Some(line.remove(byte_idx))
+        } else {
+            None
+        }
+    }
+
+    /// Copy of text between two positions, one entry per line touched
+    fn text_in_range(&self, start: CursorPosition, end: CursorPosition) -> Vec<String> {
+        let mut text = Vec::new();
+        for y in start.y..=end.y {
+            let line = self.line(y).unwrap_or("");
+            let from = if y == start.y { start.x } else { 0 };
+            let to = if y == end.y {
+                end.x
+            } else {
+                line.chars().count()
+            };
+            text.push(char_slice(line, from, to).to_string());
+        }
+        text
+    }
+
+    /// Copy of all lines
+    fn to_lines(&self) -> Vec<String> {
+        (0..self.line_count())
+            .map(|y| self.line(y).unwrap_or("").to_string())
+            .collect()
+    }
+
+    /// Inserts a single line at `idx`
+    fn insert_line(&mut self, idx: usize, line: String) {
+        self.insert_lines(idx, vec![line]);
+    }
+
+    /// Appends a line after the last one
+    fn push_line(&mut self, line: String) {
+        self.insert_lines(self.line_count(), vec![line]);
+    }
+
+    /// Removes the line at `idx`, returning it
+    fn remove_line(&mut self, idx: usize) -> Option<String> {
+        self.remove_lines(idx..idx + 1).pop()
+    }
+
+    /// Replaces the lines in range with the given ones, returning the removed lines
+    fn replace_lines(&mut self, range: Range<usize>, lines: Vec<String>) -> Vec<String> {
+        let removed = self.remove_lines(range.clone());
+        self.insert_lines(range.start, lines);
+        removed
+    }
+
+    /// Removes up to `count` lines from `idx`, fewer if the text ends first
+    fn remove_lines_from(&mut self, idx: usize, count: usize) -> Vec<String> {
+        let end = idx.saturating_add(count).min(self.line_count());
+        if idx >= end {
+            return Vec::new();
+        }
+        self.remove_lines(idx..end)
+    }
+
+    /// Replaces the chars between two positions on one line with a single char
+    fn replace_chars(&mut self, start: CursorPosition, end: CursorPosition, new: char) {
+        if start.y != end.y {
+            return;
+        }
+        if let Some(line) = self.line_mut(start.y) {
+            let mut chars: Vec<char> = line.chars().collect();
+            // Clamp positions
+            let start_x = start.x.min(chars.len());
+            let end_x = end.x.min(chars.len());
+            chars.drain(start_x..end_x);
+            chars.insert(start_x, new);
+            *line = chars.into_iter().collect();
+        }
+    }
+
+    /// Replaces `old_lines`, found at start, with `new_lines`
+    fn replace_text(
+        &mut self,
+        start: CursorPosition,
+        old_lines: &[String],
+        new_lines: Vec<String>,
+    ) {
+        // Safety check for empty document
+        if self.line_count() == 0 {
+            if new_lines.is_empty() {
+                self.push_line(String::new());
+            } else {
+                self.insert_lines(0, new_lines);
+            }
+            return;
+        }
+
+        // Clamp to valid indices
+        let max_index = self.line_count() - 1;
+        let start_y = start.y.min(max_index);
+
+        // UTF-8 safe string splitter
+        let split_line = |line: &str, index: usize| -> (String, String) {
+            let chars: Vec<char> = line.chars().collect();
+            let safe_index = index.min(chars.len());
+            let (left, right) = chars.split_at(safe_index);
+            (left.iter().collect(), right.iter().collect())
+        };
+
+        // === USE OLD CONTENT TO DETERMINE CONTEXTS ===
+
+        let first_line = self.line(start_y).unwrap_or("");
+        let (left_context, _) = split_line(first_line, start.x);
+        let right_context = if old_lines.is_empty() {
+            // No old content - this is an insertion
+            split_line(first_line, start.x).1
+        } else if old_lines.len() == 1 {
+            // Right context: current line after (start.x + old_line.len())
+            let old_len = old_lines[0].chars().count();
+            split_line(first_line, start.x + old_len).1
+        } else {
+            // Multi-line old content, right context from end line
+            let last_old_len = old_lines.last().map(|s| s.chars().count()).unwrap_or(0);
+            let end_line_idx = start_y + old_lines.len() - 1;
+            match self.line(end_line_idx) {
+                Some(end_line) => split_line(end_line, last_old_len).1,
+                None => String::new(),
+            }
+        };
+
+        // Build the replacement lines
+        let mut result = Vec::new();
+
+        if new_lines.is_empty() {
+            // Pure deletion - merge contexts
+            result.push(format!("{}{}", left_context, right_context));
+        } else if new_lines.len() == 1 {
+            // Single line replacement
+            result.push(format!("{}{}{}", left_context, new_lines[0], right_context));
+        } else {
+            // Multi-line replacement
+            let last = new_lines.len() - 1;
+            for (i, line) in new_lines.into_iter().enumerate() {
+                result.push(match i {
+                    0 => format!("{}{}", left_context, line),
+                    i if i == last => format!("{}{}", line, right_context),
+                    _ => line,
+                });
+            }
+        }
+
+        // Insertion splits one line, replacement swaps out old_lines.len() lines
+        let lines_to_remove = old_lines.len().max(1);
+
+        // Swap the old lines for the new ones in one go, so marks on them stay put
+        let end_remove = (start_y + lines_to_remove).min(self.line_count());
+        self.replace_lines(start_y..end_remove, result);
+    }
+
+    /// Inserts text lines at position, splitting the line already there
+    /// around them when needed
+    fn insert_text(&mut self, start: CursorPosition, lines: &[String]) {
+        if lines.is_empty() {
+            return;
+        }
+
+        // Ensure there's at least one line at start.y
+        self.ensure_line(start.y);
+
+        // Special case: multi-line insert at column 0
+        if lines.len() > 1 && start.x == 0 {
+            self.insert_lines(start.y, lines.to_vec());
+            return;
+        }
+
+        // Split the original line
+        let Some(line) = self.line_mut(start.y) else {
+            return;
+        };
+        let mut chars: Vec<char> = line.chars().collect();
+        let x = start.x.min(chars.len());
+        let first_line_right_half: String = chars.split_off(x).into_iter().collect();
+        let first_line_left_half: String = chars.into_iter().collect();
+
+        if lines.len() == 1 {
+            // Single-line insert: left + insert + right
+            *line = format!(
+                "{}{}{}",
+                first_line_left_half, lines[0], first_line_right_half
+            );
+        } else {
+            // Replace current line with left + first inserted line
+            *line = format!("{}{}", first_line_left_half, lines[0]);
+
+            // Insert all remaining lines, merging the right half onto the last one
+            let mut rest = lines[1..].to_vec();
+            if let Some(last) = rest.last_mut() {
+                last.push_str(&first_line_right_half);
+            }
+            self.insert_lines(start.y + 1, rest);
+        }
+    }
+
+    /// Deletes the text between two positions, joining what is left of
+    /// the first and last line
+    fn delete_text(&mut self, start: CursorPosition, end: CursorPosition) {
+        if start.y == end.y {
+            // deleting within a single line
+            if let Some(line) = self.line_mut(start.y) {
+                let mut chars: Vec<char> = line.chars().collect();
+                let range_start = start.x.min(chars.len());
+                let range_end = end.x.min(chars.len());
+                if range_start < range_end {
+                    chars.drain(range_start..range_end);
+                    *line = chars.into_iter().collect();
+                }
+            }
+            return;
+        }
+
+        // get suffix of last line
+        let suffix: String = self
+            .line(end.y)
+            .map(|line| line.chars().skip(end.x).collect())
+            .unwrap_or_default();
+
+        if start.x == 0 {
+            // delete the first line entirely
+            let keep = (!suffix.is_empty()).then_some(suffix);
+            self.replace_lines(start.y..end.y + 1, keep.into_iter().collect());
+        } else {
+            // truncate first line and append suffix from last line
+            if let Some(first_line) = self.line_mut(start.y) {
+                let byte_idx = char_to_byte_idx(first_line, start.x);
+                first_line.truncate(byte_idx);
+                first_line.push_str(&suffix);
+            }
+
+            // remove all lines in between start.y and end.y (excluding start.y)
+            if end.y > start.y {
+                self.remove_lines(start.y + 1..end.y + 1);
+            }
+        }
+    }
+}
+
+/// Rope-style buffer of lines.
+///
+/// Lines are kept in bounded chunks, each behind an `Arc`, so inserting or
+/// removing a line only shifts the lines of one chunk, and cloning the buffer
+/// (snapshots, search) only copies chunk pointers. A chunk is copied lazily the
+/// first time a shared clone of it is written to.
+///
+/// Named marks live with the lines they point at, lines inserted or removed
+/// above a mark move it along.
+#[derive(Clone, Default)]
+pub struct TextBuffer {
+    chunks: Vec<Arc<Vec<String>>>,
+    // index of first line in each chunk, kept in sync with chunks
+    chunk_starts: Vec<usize>,
+    len: usize,
+    marks: BTreeMap<char, CursorPosition>,
+}
+
+impl TextBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds buffer from text, splitting on line endings
+    pub fn from_text(text: &str) -> Self {
+        text.lines().map(String::from).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&String> {
+        if idx >= self.len {
+            return None;
+        }
+        let (chunk, offset) = self.locate(idx);
+        Some(&self.chunks[chunk][offset])
+    }
+
+    pub fn last(&self) -> Option<&String> {
+        self.chunks.last().and_then(|c| c.last())
+    }
+
+    /// Replaces lines in range with the given lines, returning the removed ones
+    fn splice<R, I>(&mut self, range: R, replace_with: I) -> Vec<String>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = String>,
+    {
+        let range = self.resolve_range(range);
+        let removed = self.remove_span(range.clone());
+        let new_lines: Vec<String> = replace_with.into_iter().collect();
+        self.shift_marks(range.start, removed.len(), new_lines.len());
+        if !new_lines.is_empty() {
+            self.insert_span(range.start, new_lines);
+        }
+        removed
+    }
+
+    /// Sets mark name at pos, replacing an earlier mark of that name
+    pub fn set_mark(&mut self, name: char, pos: CursorPosition) {
+        self.marks.insert(name, pos);
+    }
+
+    pub fn mark(&self, name: char) -> Option<CursorPosition> {
+        self.marks.get(&name).copied()
+    }
+
+    /// All marks, ordered by name
+    pub fn marks(&self) -> impl Iterator<Item = (char, CursorPosition)> + '_ {
+        self.marks.iter().map(|(name, pos)| (*name, *pos))
+    }
+
+    pub fn remove_mark(&mut self, name: char) -> Option<CursorPosition> {
+        self.marks.remove(&name)
+    }
+
+    /// Moves marks along with `removed` lines at `at` being replaced by
+    /// `inserted` lines. Marks below shift by the difference, marks on lines
+    /// that are gone move to the last line that took their place
+    fn shift_marks(&mut self, at: usize, removed: usize, inserted: usize) {
+        for pos in self.marks.values_mut() {
+            if pos.y >= at + removed {
+                pos.y = pos.y + inserted - removed;
+            } else if pos.y >= at + inserted {
+                pos.y = (at + inserted).saturating_sub(1).max(at);
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &String> + '_ {
+        self.chunks.iter().flat_map(|chunk| chunk.iter())
+    }
+
+    /// Iterates lines starting at `start`, without walking the lines before it
+    pub fn iter_from(&self, start: usize) -> impl Iterator<Item = &String> + '_ {
+        let (chunk, offset) = if start >= self.len {
+            (self.chunks.len(), 0)
+        } else {
+            self.locate(start)
+        };
+        self.chunks[chunk..]
+            .iter()
+            .flat_map(|chunk| chunk.iter())
+            .skip(offset)
+    }
+
+    pub fn join(&self, separator: &str) -> String {
+        let mut out = String::new();
+        for (i, line) in self.iter().enumerate() {
+            if i > 0 {
+                out.push_str(separator);
+            }
+            out.push_str(line);
+        }
+        out
+    }
+
+    pub fn to_vec(&self) -> Vec<String> {
+        self.iter().cloned().collect()
+    }
+
+    /// Finds chunk index and offset in chunk for a line index
+    fn locate(&self, idx: usize) -> (usize, usize) {
+        let chunk = self.chunk_starts.partition_point(|&start| start <= idx) - 1;
+        (chunk, idx - self.chunk_starts[chunk])
+    }
+
+    fn resolve_range<R: RangeBounds<usize>>(&self, range: R) -> Range<usize> {
+        use std::ops::Bound;
+        let start = match range.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => s + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&e) => e + 1,
+            Bound::Excluded(&e) => e,
+            Bound::Unbounded => self.len,
+        };
+        assert!(
+            start <= end && end <= self.len,
+            "range {start}..{end} out of bounds for buffer of {} lines",
+            self.len
+        );
+        start..end
+    }
+
+    fn remove_span(&mut self, range: Range<usize>) -> Vec<String> {
+        if range.is_empty() {
+            return Vec::new();
+        }
+        let (first_chunk, _) = self.locate(range.start);
+        let mut removed = Vec::with_capacity(range.len());
+        let mut chunk_idx = first_chunk;
+        let mut remaining = range.len();
+        let mut offset = range.start - self.chunk_starts[first_chunk];
+
+        while remaining > 0 {
+            let chunk = Arc::make_mut(&mut self.chunks[chunk_idx]);
+            let take = remaining.min(chunk.len() - offset);
+            removed.extend(chunk.drain(offset..offset + take));
+            remaining -= take;
+            offset = 0;
+            chunk_idx += 1;
+        }
+        self.len -= removed.len();
+
+        // drop chunks emptied by the removal, then merge a small leftover
+        self.chunks.retain(|chunk| !chunk.is_empty());
+        self.merge_small_chunk(first_chunk.min(self.chunks.len().saturating_sub(1)));
+        self.rebuild_starts(0);
+        removed
+    }
+
+    fn insert_span(&mut self, idx: usize, lines: Vec<String>) {
+        self.len += lines.len();
+        if self.chunks.is_empty() {
+            self.chunks = Self::chunk_lines(lines);
+            self.rebuild_starts(0);
+            return;
+        }
+
+        // appending goes to the end of the last chunk
+        let (chunk_idx, offset) = if idx >= self.len - lines.len() {
+            let last = self.chunks.len() - 1;
+            (last, self.chunks[last].len())
+        } else {
+            self.locate(idx)
+        };
+
+        let chunk = Arc::make_mut(&mut self.chunks[chunk_idx]);
+        if chunk.len() + lines.len() <= MAX_CHUNK_LINES {
+            chunk.splice(offset..offset, lines);
+        } else {
+            let tail = chunk.split_off(offset);
+            let mut merged = std::mem::take(chunk);
+            merged.extend(lines);
+            merged.extend(tail);
+            let new_chunks = Self::chunk_lines(merged);
+            self.chunks.splice(chunk_idx..=chunk_idx, new_chunks);
+        }
+        self.rebuild_starts(chunk_idx);
+    }
+
+    /// Merges chunk with a neighbour if it got too small
+    fn merge_small_chunk(&mut self, idx: usize) {
+        if self.chunks.len() < 2 || self.chunks[idx].len() >= MIN_CHUNK_LINES {
+            return;
+        }
+        let (left, right) = if idx + 1 < self.chunks.len() {
+            (idx, idx + 1)
+        } else {
+            (idx - 1, idx)
+        };
+        if self.chunks[left].len() + self.chunks[right].len() <= MAX_CHUNK_LINES {
+            let right_chunk = self.chunks.remove(right);
+            Arc::make_mut(&mut self.chunks[left]).extend(right_chunk.iter().cloned());
+        }
+    }
+
+    fn chunk_lines(lines: Vec<String>) -> Vec<Arc<Vec<String>>> {
+        let mut chunks = Vec::with_capacity(lines.len() / MAX_CHUNK_LINES + 1);
+        let mut lines = lines.into_iter().peekable();
+        while lines.peek().is_some() {
+            // fill chunks to half, leaves room for edits before a split is needed
+            let chunk: Vec<String> = lines.by_ref().take(MAX_CHUNK_LINES / 2).collect();
+            chunks.push(Arc::new(chunk));
+        }
+        chunks
+    }
+
+    fn rebuild_starts(&mut self, from_chunk: usize) {
+        self.chunk_starts.truncate(from_chunk);
+        let mut start = match from_chunk {
+            0 => 0,
+            i => self.chunk_starts[i - 1] + self.chunks[i - 1].len(),
+        };
+        for chunk in &self.chunks[from_chunk..] {
+            self.chunk_starts.push(start);
+            start += chunk.len();
+        }
+    }
+}
+
+impl TextStorage for TextBuffer {
+    fn line_count(&self) -> usize {
+        self.len
+    }
+
+    fn line(&self, idx: usize) -> Option<&str> {
+        self.get(idx).map(String::as_str)
+    }
+
+    fn line_mut(&mut self, idx: usize) -> Option<&mut String> {
+        if idx >= self.len {
+            return None;
+        }
+        let (chunk, offset) = self.locate(idx);
+        Some(&mut Arc::make_mut(&mut self.chunks[chunk])[offset])
+    }
+
+    fn insert_lines(&mut self, idx: usize, lines: Vec<String>) {
+        self.splice(idx..idx, lines);
+    }
+
+    fn remove_lines(&mut self, range: Range<usize>) -> Vec<String> {
+        self.splice(range, std::iter::empty())
+    }
+
+    // swapped in one go, so marks on replaced lines stay on the new ones
+    fn replace_lines(&mut self, range: Range<usize>, lines: Vec<String>) -> Vec<String> {
+        self.splice(range, lines)
+    }
+}
+
+impl TextStorage for Vec<String> {
+    fn line_count(&self) -> usize {
+        self.len()
+    }
+
+    fn line(&self, idx: usize) -> Option<&str> {
+        self.get(idx).map(String::as_str)
+    }
+
+    fn line_mut(&mut self, idx: usize) -> Option<&mut String> {
+        self.get_mut(idx)
+    }
+
+    fn insert_lines(&mut self, idx: usize, lines: Vec<String>) {
+        self.splice(idx..idx, lines);
+    }
+
+    fn remove_lines(&mut self, range: Range<usize>) -> Vec<String> {
+        self.drain(range).collect()
+    }
+}
+
+impl Index<usize> for TextBuffer {
+    type Output = String;
+
+    fn index(&self, idx: usize) -> &String {
+        self.get(idx)
+            .unwrap_or_else(|| panic!("line index (is {idx}) should be < len (is {})", self.len))
+    }
+}
+
+impl IndexMut<usize> for TextBuffer {
+    fn index_mut(&mut self, idx: usize) -> &mut String {
+        let len = self.len;
+        self.line_mut(idx)
+            .unwrap_or_else(|| panic!("line index (is {idx}) should be < len (is {len})"))
+    }
+}
+
+impl From<Vec<String>> for TextBuffer {
+    fn from(lines: Vec<String>) -> Self {
+        let mut buffer = Self {
+            len: lines.len(),
+            chunks: Self::chunk_lines(lines),
+            chunk_starts: Vec::new(),
+            marks: BTreeMap::new(),
+        };
+        buffer.rebuild_starts(0);
+        buffer
+    }
+}
+
+impl FromIterator<String> for TextBuffer {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<String>>())
+    }
+}
+
+impl<T: AsRef<str>> PartialEq<Vec<T>> for TextBuffer {
+    fn eq(&self, other: &Vec<T>) -> bool {
+        self.len == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|(a, b)| a.as_str() == b.as_ref())
+    }
+}
+
+impl PartialEq for TextBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl fmt::Debug for TextBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Byte index of char index in string, clamped to string length
+pub(crate) fn char_to_byte_idx(s: &str, char_idx: usize) -> usize {
+    s.char_indices()
+        .nth(char_idx)
+        .map(|(byte_idx, _)| byte_idx)
+        .unwrap_or(s.len())
+}
+
+/// Sub slice of string between two char indexes, clamped to string length
+pub(crate) fn char_slice(s: &str, from: usize, to: usize) -> &str {
+    let start = char_to_byte_idx(s, from);
+    let end = char_to_byte_idx(s, to).max(start);
+    &s[start..end]
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_text_buffer_tests {
+    use super::*;
+
+    fn numbered_lines(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("line {i}")).collect()
+    }
+
+    #[test]
+    fn test_from_vec_keeps_lines_in_order() {
+        let lines = numbered_lines(5000);
+        let buffer = TextBuffer::from(lines.clone());
+        assert_eq!(buffer.len(), 5000);
+        assert_eq!(buffer, lines);
+        assert_eq!(buffer[4321], "line 4321");
+    }
+
+    #[test]
+    fn test_insert_and_remove_across_chunks() {
+        let mut lines = numbered_lines(3000);
+        let mut buffer = TextBuffer::from(lines.clone());
+
+        for i in (0..3000).step_by(7) {
+            buffer.insert_line(i, format!("new {i}"));
+            lines.insert(i, format!("new {i}"));
+        }
+        assert_eq!(buffer, lines);
+
+        for i in (0..2000).rev().step_by(3) {
+            assert_eq!(buffer.remove_line(i), Some(lines.remove(i)));
+        }
+        assert_eq!(buffer, lines);
+    }
+
+    #[test]
+    fn test_replace_and_remove_lines_spanning_chunks() {
+        let mut lines = numbered_lines(2500);
+        let mut buffer = TextBuffer::from(lines.clone());
+
+        let removed = buffer.remove_lines(100..1900);
+        let expected: Vec<String> = lines.drain(100..1900).collect();
+        assert_eq!(removed, expected);
+        assert_eq!(buffer, lines);
+
+        let replacement = vec!["a".to_string(), "b".to_string()];
+        buffer.replace_lines(10..12, replacement.clone());
+        lines.splice(10..=11, replacement);
+        assert_eq!(buffer, lines);
+    }
+
+    #[test]
+    fn test_marks_follow_inserted_and_removed_lines() {
+        let mut buffer = TextBuffer::from(numbered_lines(10));
+        buffer.set_mark('a', CursorPosition { x: 2, y: 5 });
+        buffer.set_mark('b', CursorPosition { x: 0, y: 1 });
+
+        buffer.insert_lines(2, vec!["x".to_string(), "y".to_string()]);
+        assert_eq!(buffer.mark('a'), Some(CursorPosition { x: 2, y: 7 }));
+        buffer.remove_lines(0..3);
+        assert_eq!(buffer.mark('a'), Some(CursorPosition { x: 2, y: 4 }));
+        // a removed line moves its mark to the line taking its place
+        assert_eq!(buffer.mark('b'), Some(CursorPosition { x: 0, y: 0 }));
+
+        // rewriting the marked line in place keeps the mark on it
+        buffer.replace_lines(4..5, vec!["rewritten".to_string()]);
+        assert_eq!(buffer.mark('a').map(|pos| pos.y), Some(4));
+        buffer.replace_lines(3..5, vec!["joined".to_string()]);
+        assert_eq!(buffer.mark('a').map(|pos| pos.y), Some(3));
+
+        assert_eq!(
+            buffer.marks().map(|(name, _)| name).collect::<Vec<_>>(),
+            vec!['a', 'b']
+        );
+        buffer.remove_mark('a');
+        assert_eq!(buffer.mark('a'), None);
+    }
+
+    #[test]
+    fn test_push_into_empty_buffer() {
+        let mut buffer = TextBuffer::new();
+        assert!(buffer.is_empty());
+        buffer.push_line("first".to_string());
+        buffer.push_line("second".to_string());
+        assert_eq!(buffer, vec!["first", "second"]);
+        assert_eq!(buffer.last().unwrap(), "second");
+    }
+
+    #[test]
+    fn test_clone_is_copy_on_write() {
+        let original = TextBuffer::from(numbered_lines(2000));
+        let mut copy = original.clone();
+        copy[0].push_str(" edited");
+        assert_eq!(original[0], "line 0");
+        assert_eq!(copy[0], "line 0 edited");
+    }
+
+    #[test]
+    fn test_iter_from_starts_at_line() {
+        let buffer = TextBuffer::from(numbered_lines(1500));
+        let visible: Vec<&String> = buffer.iter_from(1200).take(3).collect();
+        assert_eq!(visible, vec!["line 1200", "line 1201", "line 1202"]);
+        assert_eq!(buffer.iter_from(1500).count(), 0);
+    }
+
+    #[test]
+    fn test_ensure_line_grows_and_remove_lines_from_shrinks() {
+        let mut buffer = TextBuffer::from(numbered_lines(3));
+        buffer.ensure_line(4);
+        assert_eq!(buffer, vec!["line 0", "line 1", "line 2", "", ""]);
+        assert_eq!(buffer.remove_lines_from(1, 10).len(), 4);
+        assert_eq!(buffer, vec!["line 0"]);
+        assert!(buffer.remove_lines_from(3, 1).is_empty());
+    }
+
+    #[test]
+    fn test_storage_char_operations_are_multibyte_safe() {
+        let mut buffer = TextBuffer::from(vec!["cøé".to_string()]);
+        buffer.insert_char(CursorPosition { x: 2, y: 0 }, 'X');
+        assert_eq!(buffer[0], "cøXé");
+        assert_eq!(buffer.remove_char(CursorPosition { x: 1, y: 0 }), Some('ø'));
+        assert_eq!(buffer[0], "cXé");
+        assert_eq!(buffer.remove_char(CursorPosition { x: 9, y: 0 }), None);
+    }
+
+    #[test]
+    fn test_storage_insert_char_creates_missing_lines() {
+        let mut buffer = TextBuffer::new();
+        buffer.insert_char(CursorPosition { x: 0, y: 2 }, 'a');
+        assert_eq!(buffer, vec!["", "", "a"]);
+    }
+
+    #[test]
+    fn test_storage_text_in_range() {
+        let buffer = TextBuffer::from(vec![
+            "abcdef".to_string(),
+            "ghijkl".to_string(),
+            "mnopqr".to_string(),
+        ]);
+        let text =
+            buffer.text_in_range(CursorPosition { x: 3, y: 0 }, CursorPosition { x: 2, y: 2 });
+        assert_eq!(text, vec!["def", "ghijkl", "mn"]);
+    }
+
+    #[test]
+    fn test_text_edits_match_on_any_storage() {
+        let lines: Vec<String> = ["one", "twø", "three"].map(String::from).to_vec();
+        let inserted: Vec<String> = ["A", "B"].map(String::from).to_vec();
+        let mut vec = lines.clone();
+        let mut buffer = TextBuffer::from(lines.clone());
+
+        for storage in [&mut vec as &mut dyn TextStorage, &mut buffer] {
+            storage.insert_text(CursorPosition { x: 2, y: 1 }, &inserted);
+            assert_eq!(storage.to_lines(), vec!["one", "twA", "Bø", "three"]);
+            storage.delete_text(CursorPosition { x: 2, y: 1 }, CursorPosition { x: 1, y: 2 });
+            assert_eq!(storage.to_lines(), lines);
+            let old =
+                storage.text_in_range(CursorPosition { x: 1, y: 0 }, CursorPosition { x: 2, y: 2 });
+            storage.replace_text(CursorPosition { x: 1, y: 0 }, &old, vec!["-".to_string()]);
+            assert_eq!(storage.to_lines(), vec!["o-ree"]);
+            storage.replace_chars(
+                CursorPosition { x: 1, y: 0 },
+                CursorPosition { x: 2, y: 0 },
+                '+',
+            );
+            assert_eq!(storage.to_lines(), vec!["o+ree"]);
+        }
+    }
+}
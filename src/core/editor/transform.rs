@@ -0,0 +1,394 @@
+//! Text transformations run from the command line: sorting, deduplicating and
+//! reversing lines, and converting the case of text. They work on the
+//! selection, or the whole buffer when nothing is selected.
+
+use super::editor::Editor;
+use super::text_buffer::TextStorage;
+use crate::core::cursor::CursorPosition;
+use std::cmp::Ordering;
+
+/// How `:sort` compares and orders lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SortOptions {
+    /// Compare by the first number in each line, lines without one go first
+    pub numeric: bool,
+    /// Largest first
+    pub reverse: bool,
+    /// Compare lines lowercased
+    pub ignore_case: bool,
+    /// Drop lines equal to the one before them after sorting
+    pub unique: bool,
+}
+
+/// Cases text can be converted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseConversion {
+    Upper,
+    Lower,
+    Title,
+    Snake,
+    Camel,
+}
+
+/// First number in a line, with its sign and fraction
+fn leading_number(line: &str) -> Option<f64> {
+    let start = line.find(|c: char| c.is_ascii_digit())?;
+    let start = if line[..start].ends_with('-') {
+        start - 1
+    } else {
+        start
+    };
+    let mut end = start + 1;
+    let mut seen_dot = false;
+    for (i, c) in line[start + 1..].char_indices() {
+        match c {
+            '0'..='9' => end = start + 1 + i + 1,
+            '.' if !seen_dot => seen_dot = true,
+            _ => break,
+        }
+    }
+    line[start..end].parse().ok()
+}
+
+fn compare_lines(a: &str, b: &str, options: SortOptions) -> Ordering {
+    if options.numeric {
+        let (a, b) = (leading_number(a), leading_number(b));
+        match (a, b) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            _ => a.is_some().cmp(&b.is_some()),
+        }
+    } else if options.ignore_case {
+        a.to_lowercase().cmp(&b.to_lowercase())
+    } else {
+        a.cmp(b)
+    }
+}
+
+/// Sorts lines stably, equal lines keep their order in both directions
+pub(crate) fn sort_lines(mut lines: Vec<String>, options: SortOptions) -> Vec<String> {
+    lines.sort_by(|a, b| {
+        let order = compare_lines(a, b, options);
+        if options.reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    });
+    if options.unique {
+        lines.dedup_by(|a, b| compare_lines(a, b, options) == Ordering::Equal);
+    }
+    lines
+}
+
+/// Words an identifier is made of, split at underscores and where the case
+/// changes, so `parseHTTPRequest` is parse, HTTP and Request
+fn identifier_words(identifier: &str) -> Vec<String> {
+    let chars: Vec<char> = identifier.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let starts_word = c.is_uppercase()
+            && prev.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_ascii_digit()
+                    || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+            });
+        if starts_word && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Rewrites an identifier in snake or camel case, keeping leading and
+/// trailing underscores
+fn convert_identifier(identifier: &str, case: CaseConversion) -> String {
+    let core = identifier.trim_matches('_');
+    if core.is_empty() {
+        return identifier.to_string();
+    }
+    let head = &identifier[..identifier.find(core).unwrap_or(0)];
+    let tail = &identifier[head.len() + core.len()..];
+    let words = identifier_words(core);
+    let converted = match case {
+        CaseConversion::Snake => words
+            .iter()
+            .map(|w| w.to_lowercase())
+            .collect::<Vec<_>>()
+            .join("_"),
+        _ => words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                if i == 0 {
+                    w.to_lowercase()
+                } else {
+                    capitalize(w)
+                }
+            })
+            .collect(),
+    };
+    format!("{head}{converted}{tail}")
+}
+
+/// Converts the case of a line. Title case capitalizes every word, snake and
+/// camel case rewrite every identifier, a run of letters, digits and underscores
+pub(crate) fn convert_case(line: &str, case: CaseConversion) -> String {
+    match case {
+        CaseConversion::Upper => line.to_uppercase(),
+        CaseConversion::Lower => line.to_lowercase(),
+        CaseConversion::Title => {
+            let mut prev: Option<char> = None;
+            let mut out = String::with_capacity(line.len());
+            for c in line.chars() {
+                let in_word = prev.is_some_and(|p| p.is_alphanumeric() || p == '\'');
+                if in_word {
+                    out.extend(c.to_lowercase());
+                } else {
+                    out.extend(c.to_uppercase());
+                }
+                prev = Some(c);
+            }
+            out
+        }
+        CaseConversion::Snake | CaseConversion::Camel => {
+            let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+            let mut out = String::with_capacity(line.len());
+            let mut rest = line;
+            while let Some(start) = rest.find(is_ident) {
+                out.push_str(&rest[..start]);
+                rest = &rest[start..];
+                let end = rest.find(|c: char| !is_ident(c)).unwrap_or(rest.len());
+                out.push_str(&convert_identifier(&rest[..end], case));
+                rest = &rest[end..];
+            }
+            out.push_str(rest);
+            out
+        }
+    }
+}
+
+impl Editor {
+    /// Sorts the selected lines, or all lines
+    pub fn sort_lines(&mut self, options: SortOptions) {
+        self.transform_lines(|lines| sort_lines(lines, options));
+    }
+
+    /// Drops lines that repeat the line right before them, like uniq does
+    pub fn unique_lines(&mut self) {
+        self.transform_lines(|mut lines| {
+            lines.dedup();
+            lines
+        });
+    }
+
+    /// Reverses the order of the selected lines, or all lines
+    pub fn reverse_lines(&mut self) {
+        self.transform_lines(|mut lines| {
+            lines.reverse();
+            lines
+        });
+    }
+
+    /// Converts the case of the selected text, or the whole buffer
+    pub fn convert_case(&mut self, case: CaseConversion) {
+        let (start, end) = match (self.text_selection_start, self.text_selection_end) {
+            (Some(a), Some(b)) => (a.min(b), a.max(b)),
+            _ => self.buffer_bounds(),
+        };
+        let text = self.editor_content.text_in_range(start, end);
+        let converted = text.iter().map(|line| convert_case(line, case)).collect();
+        self.replace_with(start, end, text, converted);
+    }
+
+    /// Replaces the whole lines of the selection, or all lines, with what
+    /// transform makes of them
+    fn transform_lines(&mut self, transform: impl FnOnce(Vec<String>) -> Vec<String>) {
+        let (first, last) = if self.text_selection_start.is_some() {
+            self.selected_lines()
+        } else {
+            (0, self.editor_content.len().saturating_sub(1))
+        };
+        let start = CursorPosition { x: 0, y: first };
+        let end = CursorPosition {
+            x: self.editor_content.line_char_count(last),
+            y: last,
+        };
+        let lines = self.editor_content.text_in_range(start, end);
+        let transformed = transform(lines.clone());
+        self.replace_with(start, end, lines, transformed);
+    }
+
+    /// Replaces start to end with new as one undo step, unless nothing
+    /// changes. A selection is kept around the new text
+    fn replace_with(
+        &mut self,
+        start: CursorPosition,
+        end: CursorPosition,
+        old: Vec<String>,
+        new: Vec<String>,
+    ) {
+        if old == new {
+            return;
+        }
+        let new_end = CursorPosition {
+            x: match new.len() {
+                1 => start.x + new[0].chars().count(),
+                _ => new.last().map_or(0, |line| line.chars().count()),
+            },
+            y: start.y + new.len() - 1,
+        };
+        let action = self.replace_range(start, end, new);
+        self.undo_redo_manager.record_undo(action);
+        if self.text_selection_start.is_some() {
+            self.text_selection_start = Some(start);
+            self.text_selection_end = Some(new_end);
+            self.set_cursor_position(&new_end);
+        } else {
+            let cursor = self.cursor_position();
+            self.set_cursor_position(&cursor);
+        }
+    }
+
+    /// Start and end of the whole buffer
+    fn buffer_bounds(&self) -> (CursorPosition, CursorPosition) {
+        let last = self.editor_content.len().saturating_sub(1);
+        let end = CursorPosition {
+            x: self.editor_content.line_char_count(last),
+            y: last,
+        };
+        (CursorPosition { x: 0, y: 0 }, end)
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_transform_tests {
+    use super::*;
+    use crate::config::Config;
+    use std::sync::Arc;
+
+    fn create_editor_with_editor_content(vec: Vec<&str>) -> Editor {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = vec.into_iter().map(String::from).collect();
+        editor
+    }
+
+    fn lines(vec: &[&str]) -> Vec<String> {
+        vec.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_sort_options() {
+        let input = lines(&["b10", "a2", "B1", "x", "a2"]);
+        assert_eq!(
+            sort_lines(input.clone(), SortOptions::default()),
+            lines(&["B1", "a2", "a2", "b10", "x"])
+        );
+        let numeric = SortOptions {
+            numeric: true,
+            ..SortOptions::default()
+        };
+        assert_eq!(
+            sort_lines(input.clone(), numeric),
+            lines(&["x", "B1", "a2", "a2", "b10"])
+        );
+        let options = SortOptions {
+            ignore_case: true,
+            reverse: true,
+            unique: true,
+            ..SortOptions::default()
+        };
+        assert_eq!(sort_lines(input, options), lines(&["x", "b10", "B1", "a2"]));
+        assert_eq!(leading_number("v -3.5 and 7"), Some(-3.5));
+        assert_eq!(leading_number("1.2.3"), Some(1.2));
+    }
+
+    #[test]
+    fn test_convert_case() {
+        let line = "let parseHTTPRequest = my_value_2;";
+        assert_eq!(
+            convert_case(line, CaseConversion::Snake),
+            "let parse_http_request = my_value_2;"
+        );
+        assert_eq!(
+            convert_case(line, CaseConversion::Camel),
+            "let parseHttpRequest = myValue2;"
+        );
+        assert_eq!(
+            convert_case("_Private_Field", CaseConversion::Camel),
+            "_privateField"
+        );
+        assert_eq!(
+            convert_case("don't STOP me-now", CaseConversion::Title),
+            "Don't Stop Me-Now"
+        );
+        assert_eq!(convert_case("straße", CaseConversion::Upper), "STRASSE");
+    }
+
+    #[test]
+    fn test_line_transforms_work_on_buffer_or_selected_lines() {
+        let mut editor = create_editor_with_editor_content(vec!["c", "a", "a", "b"]);
+        editor.sort_lines(SortOptions::default());
+        assert_eq!(editor.editor_content.to_lines(), vec!["a", "a", "b", "c"]);
+        editor.unique_lines();
+        assert_eq!(editor.editor_content.to_lines(), vec!["a", "b", "c"]);
+
+        editor.text_selection_start = Some(CursorPosition { x: 0, y: 1 });
+        editor.text_selection_end = Some(CursorPosition { x: 1, y: 2 });
+        editor.reverse_lines();
+        assert_eq!(editor.editor_content.to_lines(), vec!["a", "c", "b"]);
+        assert_eq!(
+            editor.text_selection_end,
+            Some(CursorPosition { x: 1, y: 2 })
+        );
+
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content.to_lines(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_convert_case_of_selection_is_one_undo_step() {
+        let mut editor = create_editor_with_editor_content(vec!["one two", "three"]);
+        editor.text_selection_start = Some(CursorPosition { x: 4, y: 0 });
+        editor.text_selection_end = Some(CursorPosition { x: 2, y: 1 });
+        editor.convert_case(CaseConversion::Upper);
+        assert_eq!(editor.editor_content.to_lines(), vec!["one TWO", "THree"]);
+
+        editor.reset_text_selection_cursor();
+        editor.convert_case(CaseConversion::Lower);
+        assert_eq!(editor.editor_content.to_lines(), vec!["one two", "three"]);
+        // nothing changes, nothing is recorded
+        editor.convert_case(CaseConversion::Lower);
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content.to_lines(), vec!["one TWO", "THree"]);
+    }
+}
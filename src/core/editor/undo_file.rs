@@ -0,0 +1,298 @@
+//! Undo history kept between sessions.
+//!
+//! Saving a file writes its undo tree to an undo file under the data dir,
+//! named after the file's path, together with a hash of the text saved,
+//! line endings included.
+//! Opening the file again reads the tree back, but only if the text still
+//! hashes the same, since the actions would otherwise apply to other text.
+
+use super::undo_redo::{UndoHistory, UndoRedoManager};
+use super::Editor;
+use crate::errors::editor_errors::UndoError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Thread local storage for pointing undo files at a temporary dir during
+// tests, read by undo_dir() below
+thread_local! {
+    static TEST_UNDO_DIR: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Contents of an undo file
+#[derive(Debug, Serialize, Deserialize)]
+struct UndoFile {
+    /// File the history belongs to, in case two paths hash the same
+    path: PathBuf,
+    /// Hash of the text as it was saved
+    content_hash: String,
+    history: UndoHistory,
+}
+
+/// Dir undo files are kept in
+pub fn undo_dir() -> Option<PathBuf> {
+    // If a test dir is set, return it. ONLY USED FOR TESTING
+    if let Some(dir) = TEST_UNDO_DIR.with(|cell| cell.borrow().clone()) {
+        return Some(dir);
+    }
+    dirs::data_dir().map(|dir| dir.join("calliglyph").join("undo"))
+}
+
+/// Sets the dir undo files are kept in for the current thread. ONLY USED
+/// FOR TESTING
+pub fn set_test_undo_dir(dir: Option<PathBuf>) {
+    TEST_UNDO_DIR.with(|cell| *cell.borrow_mut() = dir);
+}
+
+/// Undo file of the file at path
+pub fn undo_file_path(path: &Path) -> Option<PathBuf> {
+    let path = absolute(path);
+    let name = format!("{:016x}.toml", fnv1a(path.to_string_lossy().as_bytes()));
+    undo_dir().map(|dir| dir.join(name))
+}
+
+/// Hash of text, compared against the one in the undo file when opening
+pub fn content_hash(text: &str) -> String {
+    format!("{:016x}", fnv1a(text.as_bytes()))
+}
+
+impl Editor {
+    /// Writes the undo history to the undo file of path, just saved with the
+    /// editor's text. Does nothing unless persistent_undo is on. Trees too
+    /// big for persistent_undo_max_kb lose their oldest states until they
+    /// fit
+    pub fn store_undo_history(&self, path: &Path) -> Result<(), UndoError> {
+        if !self.editor_config.persistent_undo {
+            return Ok(());
+        }
+        let failed = |e: &dyn std::fmt::Display| UndoError::UndoFile(e.to_string());
+        let file_path = undo_file_path(path).ok_or_else(|| failed(&"no data dir"))?;
+        let max_bytes = self.editor_config.persistent_undo_max_kb as usize * 1024;
+
+        let mut manager = self.undo_redo_manager.clone();
+        let serialized = loop {
+            let file = UndoFile {
+                path: absolute(path),
+                content_hash: content_hash(&self.file_text()),
+                history: manager.to_history(),
+            };
+            let serialized = toml::to_string(&file).map_err(|e| failed(&e))?;
+            let states = file.history.nodes.len();
+            if serialized.len() <= max_bytes {
+                break serialized;
+            }
+            if states > 1 {
+                manager.update_limit(states / 2 - 1);
+            }
+            if manager.nodes().count() >= states {
+                // not even the current state fits, so no history is kept
+                return match fs::remove_file(&file_path) {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(failed(&e)),
+                    _ => Ok(()),
+                };
+            }
+        };
+
+        if let Some(dir) = file_path.parent() {
+            fs::create_dir_all(dir).map_err(|e| failed(&e))?;
+        }
+        fs::write(&file_path, serialized).map_err(|e| failed(&e))
+    }
+
+    /// Replaces the undo history with the one in the undo file of path, when
+    /// there is one and it was written for the text now in the editor.
+    /// Returns whether a history was restored
+    pub fn restore_undo_history(&mut self, path: &Path) -> bool {
+        if !self.editor_config.persistent_undo {
+            return false;
+        }
+        let Some(contents) = undo_file_path(path).and_then(|file| fs::read_to_string(file).ok())
+        else {
+            return false;
+        };
+        let file: UndoFile = match toml::from_str(&contents) {
+            Ok(file) => file,
+            Err(e) => {
+                log_warn!("[UNDO] Unreadable undo file for {}: {}", path.display(), e);
+                return false;
+            }
+        };
+        if file.path != absolute(path) || file.content_hash != content_hash(&self.file_text()) {
+            log_info!("[UNDO] Undo file of {} is out of date", path.display());
+            return false;
+        }
+
+        match UndoRedoManager::from_history(file.history, self.editor_config.undo_history_limit) {
+            Ok(manager) => {
+                let idle = Duration::from_millis(self.editor_config.undo_group_idle_ms);
+                self.undo_redo_manager = manager.with_idle_threshold(idle);
+                true
+            }
+            Err(e) => {
+                log_warn!("[UNDO] {}", e);
+                false
+            }
+        }
+    }
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// 64 bit FNV-1a, stable across runs and builds unlike the std hasher
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_undo_file_tests {
+    use super::*;
+    use crate::config::EditorConfig;
+    use crate::core::editor::text_buffer::TextBuffer;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// editor as if text was just read from a file
+    fn editor(config: EditorConfig, text: &str) -> Editor {
+        let mut editor = Editor::new(Arc::new(config));
+        editor.editor_content = TextBuffer::from_text(text);
+        editor.set_file_format_from(text);
+        editor
+    }
+
+    fn persistent() -> EditorConfig {
+        EditorConfig {
+            persistent_undo: true,
+            ..EditorConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_history_round_trips_through_undo_file() {
+        let dir = TempDir::new().unwrap();
+        set_test_undo_dir(Some(dir.path().join("undo")));
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, "ab").unwrap();
+
+        let mut first = editor(persistent(), "ab");
+        first.cursor.x = 2;
+        first.write_char('c');
+        first.undo().unwrap();
+        first.write_char('d');
+        fs::write(&path, "abd").unwrap();
+        first.undo_redo_manager.mark_saved();
+        first.store_undo_history(&path).unwrap();
+
+        let mut second = editor(persistent(), "abd");
+        assert!(second.restore_undo_history(&path));
+        assert!(!second.undo_redo_manager.is_dirty());
+        assert_eq!(second.undo_redo_manager.nodes().count(), 3);
+        second.older_state().unwrap();
+        assert_eq!(second.editor_content.join("\n"), "abc");
+        second.undo().unwrap();
+        assert_eq!(second.editor_content.join("\n"), "ab");
+
+        // changed outside the editor, the history no longer applies
+        let mut changed = editor(persistent(), "abx");
+        assert!(!changed.restore_undo_history(&path));
+        set_test_undo_dir(None);
+    }
+
+    #[test]
+    fn test_undo_file_respects_switch_and_size_cap() {
+        let dir = TempDir::new().unwrap();
+        set_test_undo_dir(Some(dir.path().to_path_buf()));
+        let path = dir.path().join("big.txt");
+        let file = undo_file_path(&path).unwrap();
+
+        let mut off = editor(EditorConfig::default(), "");
+        off.write_char('a');
+        off.store_undo_history(&path).unwrap();
+        assert!(!file.exists());
+
+        let mut capped = editor(
+            EditorConfig {
+                persistent_undo_max_kb: 1,
+                undo_group_idle_ms: 0,
+                ..persistent()
+            },
+            "",
+        );
+        for _ in 0..200 {
+            capped.write_char('a');
+        }
+        capped.store_undo_history(&path).unwrap();
+        let written = fs::read_to_string(&file).unwrap();
+        assert!(written.len() <= 1024);
+        let stored: UndoFile = toml::from_str(&written).unwrap();
+        assert!(stored.history.nodes.len() > 1 && stored.history.nodes.len() < 200);
+        set_test_undo_dir(None);
+    }
+
+    #[test]
+    fn test_size_cap_trims_history_undone_to_near_the_start() {
+        let dir = TempDir::new().unwrap();
+        set_test_undo_dir(Some(dir.path().to_path_buf()));
+        let path = dir.path().join("undone.txt");
+
+        let mut editor = editor(
+            EditorConfig {
+                persistent_undo_max_kb: 1,
+                undo_group_idle_ms: 0,
+                ..persistent()
+            },
+            "",
+        );
+        for _ in 0..200 {
+            editor.write_char('a');
+        }
+        for _ in 0..199 {
+            editor.undo().unwrap();
+        }
+        editor.undo_redo_manager.mark_saved();
+        let current = editor.undo_redo_manager.current();
+        editor.store_undo_history(&path).unwrap();
+
+        // the states undone are dropped to fit, the current one is kept
+        let written = fs::read_to_string(undo_file_path(&path).unwrap()).unwrap();
+        assert!(written.len() <= 1024);
+        let stored: UndoFile = toml::from_str(&written).unwrap();
+        assert!(stored.history.nodes.len() < 200);
+        assert_eq!(stored.history.current, current);
+        assert!(stored.history.nodes.iter().any(|node| node.id == current));
+        set_test_undo_dir(None);
+    }
+
+    #[test]
+    fn test_line_endings_changed_outside_the_editor_drop_history() {
+        let dir = TempDir::new().unwrap();
+        set_test_undo_dir(Some(dir.path().to_path_buf()));
+        let path = dir.path().join("dos.txt");
+
+        let mut first = editor(persistent(), "ab\r\ncd\r\n");
+        first.write_char('x');
+        fs::write(&path, first.file_text()).unwrap();
+        first.mark_saved();
+        first.store_undo_history(&path).unwrap();
+
+        // same lines, converted to unix by another program
+        let mut converted = editor(persistent(), "xab\ncd\n");
+        assert!(!converted.restore_undo_history(&path));
+
+        let mut unchanged = editor(persistent(), "xab\r\ncd\r\n");
+        assert!(unchanged.restore_undo_history(&path));
+        set_test_undo_dir(None);
+    }
+}
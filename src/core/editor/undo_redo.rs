@@ -1,47 +1,285 @@
+//! Undo history kept as a tree, so undoing and then editing starts a new
+//! branch instead of throwing away what was undone.
+//!
+//! Every node is a state of the text, reached from its parent by the node's
+//! action. Undo walks up to the parent, redo down to the child last left or
+//! created. Other branches are reached by going to a node, which undoes up to
+//! the branch point and redoes down from there.
+
 use super::editor::EditAction;
+use super::text_buffer::char_slice;
+use crate::core::cursor::CursorPosition;
 use crate::errors::editor_errors::{RedoError, UndoError};
-use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::time::{Duration, SystemTime};
+
+/// A state in the undo tree
+#[derive(Debug, Clone)]
+pub struct UndoNode {
+    /// Action leading here from the parent, None for the root
+    pub action: Option<EditAction>,
+    pub parent: Option<usize>,
+    /// Branches from this state, oldest first
+    pub children: Vec<usize>,
+    // child redo goes to, the one last undone from or created
+    redo_child: Option<usize>,
+    /// Cursor and selection before the action, restored by undo
+    pub before: Option<CursorState>,
+    /// Cursor and selection after the action, restored by redo
+    pub after: Option<CursorState>,
+    /// When the state was reached by editing, the last char for typing
+    pub time: SystemTime,
+}
+
+/// Where the cursor and selection were around an edit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CursorState {
+    pub cursor: CursorPosition,
+    /// Start and end of the text selection
+    pub selection: Option<(CursorPosition, CursorPosition)>,
+}
+
+/// Row of the undo tree as listed for picking a state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoTreeRow {
+    pub id: usize,
+    /// Branches the node is indented by
+    pub depth: usize,
+    pub summary: String,
+    pub current: bool,
+    pub saved: bool,
+}
+
+/// The undo tree as written to an undo file. Children are rebuilt from the
+/// parents, in id order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoHistory {
+    pub current: usize,
+    pub next_id: usize,
+    /// States saved to disk, oldest first
+    #[serde(default)]
+    pub saves: Vec<usize>,
+    pub nodes: Vec<StoredNode>,
+}
+
+/// A state of the undo tree as written to an undo file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredNode {
+    pub id: usize,
+    pub parent: Option<usize>,
+    pub redo_child: Option<usize>,
+    pub action: Option<EditAction>,
+    pub before: Option<CursorState>,
+    pub after: Option<CursorState>,
+    pub time: SystemTime,
+}
 
 #[derive(Debug, Clone)]
 pub struct UndoRedoManager {
-    pub undo_stack: VecDeque<EditAction>,
-    pub redo_stack: VecDeque<EditAction>,
+    // nodes by id, ids count up in the order states were created
+    nodes: BTreeMap<usize, UndoNode>,
+    root: usize,
+    current: usize,
+    next_id: usize,
     max_history: usize,
-    last_saved_index: usize,
+    // node of the saved state, None once it was dropped from the history
+    saved: Option<usize>,
+    // node current when a group began, see begin_group
+    group_start: Option<usize>,
+    // pause after which typing starts a new state, zero never coalesces
+    idle_threshold: Duration,
+    // last char typed or deleted into the current state, while more may join it
+    typing: Option<Typing>,
+    // counts the actions recorded, see revision
+    revision: usize,
+    // states saved to disk, oldest first
+    saves: Vec<usize>,
+}
+
+/// How far :earlier and :later move through the history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryDistance {
+    /// States in the order they were made, across branches
+    Steps(usize),
+    /// Time passed between edits
+    Time(Duration),
+    /// Saves of the file
+    Saves(usize),
+}
+
+/// A single char typed or deleted, which coalesces with the ones around it
+#[derive(Debug, Clone, Copy)]
+struct Typing {
+    pos: CursorPosition,
+    c: char,
+    insert: bool,
+    at: SystemTime,
+}
+
+impl Typing {
+    fn from_action(action: &EditAction, at: SystemTime) -> Option<Self> {
+        let (pos, c, insert) = match action {
+            EditAction::Insert { pos, c } => (*pos, *c, true),
+            EditAction::Delete { pos, deleted_char } => (*pos, *deleted_char, false),
+            _ => return None,
+        };
+        Some(Self { pos, c, insert, at })
+    }
+
+    /// Whether next goes on from this one: the same kind of edit, right
+    /// next to it, and not the start of a new word
+    fn continues_with(&self, next: &Typing) -> bool {
+        let adjacent = if self.insert {
+            next.pos.x == self.pos.x + 1
+        } else {
+            // backspace moves left, delete stays put
+            next.pos.x + 1 == self.pos.x || next.pos.x == self.pos.x
+        };
+        let new_word = self.c.is_whitespace() && !next.c.is_whitespace();
+        self.insert == next.insert && self.pos.y == next.pos.y && adjacent && !new_word
+    }
 }
 
 impl UndoRedoManager {
     pub fn new(max_history: usize) -> UndoRedoManager {
+        let root = UndoNode {
+            action: None,
+            parent: None,
+            children: Vec::new(),
+            redo_child: None,
+            before: None,
+            after: None,
+            time: SystemTime::now(),
+        };
         Self {
-            undo_stack: VecDeque::with_capacity(max_history),
-            redo_stack: VecDeque::new(),
+            nodes: BTreeMap::from([(0, root)]),
+            root: 0,
+            current: 0,
+            next_id: 1,
             max_history,
-            last_saved_index: 0,
+            saved: Some(0),
+            group_start: None,
+            idle_threshold: Duration::ZERO,
+            typing: None,
+            revision: 0,
+            saves: vec![0],
         }
     }
 
+    /// Coalesces chars typed or deleted one after another into one state,
+    /// until a pause longer than idle, a new word or a jump elsewhere
+    pub fn with_idle_threshold(mut self, idle: Duration) -> Self {
+        self.idle_threshold = idle;
+        self
+    }
+
     // saved index management (changes)
 
     /// Checks if change in undo tree (signifies changes in file)
     pub fn is_dirty(&self) -> bool {
-        self.undo_stack.len() != self.last_saved_index
+        self.saved != Some(self.current)
     }
 
-    /// marks new saved index to current length of stack
+    /// marks the current state as the saved one
     pub fn mark_saved(&mut self) {
-        self.last_saved_index = self.undo_stack.len();
+        self.saved = Some(self.current);
+        self.typing = None;
+        if self.saves.last() != Some(&self.current) {
+            self.saves.push(self.current);
+        }
     }
 
-    /// Records and action done to the undo stack, and clears redo stack.
+    /// Records an action done as a new state after the current one. The
+    /// states undone before stay in the tree as another branch
     pub fn record_undo(&mut self, action: EditAction) {
-        self.undo_stack.push_back(action);
+        self.record_undo_at(action, SystemTime::now());
+    }
+
+    /// Records action as done at the given time. A char typed or deleted
+    /// soon after the one before, next to it, joins its state instead
+    pub fn record_undo_at(&mut self, action: EditAction, at: SystemTime) {
+        self.revision += 1;
+        let typed = Typing::from_action(&action, at);
+        if let Some(typed) = typed {
+            if self.coalesce(&action, typed) {
+                return;
+            }
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.nodes.insert(
+            id,
+            UndoNode {
+                action: Some(action),
+                parent: Some(self.current),
+                children: Vec::new(),
+                redo_child: None,
+                before: None,
+                after: None,
+                time: at,
+            },
+        );
+        let parent = self.node_mut(self.current);
+        parent.children.push(id);
+        parent.redo_child = Some(id);
+        self.current = id;
+        self.typing = typed;
 
         // Limit history size
-        while self.undo_stack.len() > self.max_history {
-            self.undo_stack.pop_front();
+        self.trim_history();
+    }
+
+    /// Adds action to the current state if it goes on from the char last
+    /// typed there. Returns false when it starts a state of its own
+    fn coalesce(&mut self, action: &EditAction, typed: Typing) -> bool {
+        let Some(last) = self.typing else {
+            return false;
+        };
+        let idle = typed.at.duration_since(last.at).unwrap_or_default();
+        if self.idle_threshold.is_zero()
+            || idle > self.idle_threshold
+            || !last.continues_with(&typed)
+        {
+            return false;
         }
+        let node = self.node_mut(self.current);
+        node.action = match node.action.take() {
+            Some(EditAction::Bulk(mut actions)) => {
+                actions.push(action.clone());
+                Some(EditAction::Bulk(actions))
+            }
+            Some(first) => Some(EditAction::Bulk(vec![first, action.clone()])),
+            // the state became the root when the history was trimmed
+            None => return false,
+        };
+        node.time = typed.at;
+        self.typing = Some(typed);
+        true
+    }
 
-        self.redo_stack.clear();
+    /// Changes whenever an action is recorded, so callers can tell whether
+    /// something they ran edited the text
+    pub fn revision(&self) -> usize {
+        self.revision
+    }
+
+    /// Sets the cursor states around the edits just recorded on the current
+    /// state. A state typed into over several edits keeps the before of the
+    /// first
+    pub fn set_cursor_states(&mut self, before: CursorState, after: CursorState) {
+        let node = self.node_mut(self.current);
+        if node.action.is_some() {
+            node.before.get_or_insert(before);
+            node.after = Some(after);
+        }
+    }
+
+    /// Ends the state typing is coalesced into, so the next char starts a
+    /// new one
+    pub fn break_typing(&mut self) {
+        self.typing = None;
     }
 
     /// Function to change max limit of undo history
@@ -49,30 +287,485 @@ impl UndoRedoManager {
         self.max_history = new_limit;
 
         // Trim if necessary
-        while self.undo_stack.len() > new_limit {
-            self.undo_stack.pop_front();
+        self.trim_history();
+    }
+
+    /// Drops the oldest states past the limit, whatever branch they are on.
+    /// The edit of a dropped state is folded into the states after it, so
+    /// every newer state stays reachable. The current state is always kept
+    fn trim_history(&mut self) {
+        while self.nodes.len() > self.max_history + 1 {
+            // ids count up from parent to child, so the oldest is the root,
+            // or a child of it while the root is current
+            let Some(oldest) = self.nodes.keys().copied().find(|id| *id != self.current) else {
+                return;
+            };
+            if oldest == self.root {
+                self.drop_root();
+            } else {
+                self.fold_into_children(oldest);
+            }
+        }
+    }
+
+    /// Makes the oldest child of the root the new root. The root's other
+    /// branches move under it, their edits first undoing its own
+    fn drop_root(&mut self) {
+        let old_root = self.root;
+        let mut children = self.nodes[&old_root].children.clone();
+        children.sort_unstable();
+        let Some((&new_root, others)) = children.split_first() else {
+            return;
+        };
+        let new_root_node = &self.nodes[&new_root];
+        let after = new_root_node.after;
+        let undone = new_root_node
+            .action
+            .clone()
+            .map(|action| self.reverse_action(&action));
+        for child in others {
+            let node = self.node_mut(*child);
+            node.parent = Some(new_root);
+            node.action = match (undone.clone(), node.action.take()) {
+                (Some(undone), Some(action)) => Some(folded(undone, action)),
+                (_, action) => action,
+            };
+            node.before = after;
         }
+
+        let node = self.node_mut(new_root);
+        node.action = None;
+        node.parent = None;
+        node.children.extend_from_slice(others);
+        node.children.sort_unstable();
+        self.root = new_root;
+        self.remove_node(old_root);
+        if self.group_start == Some(old_root) {
+            self.group_start = Some(new_root);
+        }
+    }
+
+    /// Removes state id below the root, its children taking its place with
+    /// its edit done before their own
+    fn fold_into_children(&mut self, id: usize) {
+        let node = self.nodes[&id].clone();
+        let Some(parent) = node.parent else {
+            return;
+        };
+        for child in &node.children {
+            let child_node = self.node_mut(*child);
+            child_node.parent = Some(parent);
+            child_node.action = match (node.action.clone(), child_node.action.take()) {
+                (Some(first), Some(action)) => Some(folded(first, action)),
+                (_, action) => action,
+            };
+            child_node.before = node.before;
+        }
+
+        let parent_node = self.node_mut(parent);
+        parent_node.children.retain(|child| *child != id);
+        parent_node.children.extend_from_slice(&node.children);
+        parent_node.children.sort_unstable();
+        if parent_node.redo_child == Some(id) {
+            parent_node.redo_child = node.redo_child.or(node.children.last().copied());
+        }
+        self.remove_node(id);
+        if self.group_start == Some(id) {
+            self.group_start = Some(parent);
+        }
+    }
+
+    // GROUPING
+
+    /// Starts collecting the actions recorded from now on into one undo step,
+    /// closed by end_group. Used when replaying macros
+    pub fn begin_group(&mut self) {
+        self.group_start = Some(self.current);
+        self.typing = None;
+    }
+
+    /// Turns the states recorded since begin_group into a single one, whose
+    /// action is a Bulk of theirs. Branches made within the group are dropped
+    pub fn end_group(&mut self) {
+        let Some(start) = self.group_start.take() else {
+            return;
+        };
+        self.typing = None;
+        let path: Vec<usize> = self
+            .path_from_root(self.current)
+            .into_iter()
+            .skip_while(|id| *id != start)
+            .skip(1)
+            .collect();
+        if path.len() < 2 {
+            return;
+        }
+        let actions: Vec<EditAction> = path
+            .iter()
+            .filter_map(|id| self.nodes[id].action.clone())
+            .collect();
+
+        // the last state takes the place of the first, the ones between go
+        let last = self.current;
+        let before = self.nodes[&path[0]].before;
+        for pair in path.windows(2) {
+            let (id, next) = (pair[0], pair[1]);
+            for child in self.nodes[&id].children.clone() {
+                if child != next {
+                    self.remove_subtree(child);
+                }
+            }
+            self.remove_node(id);
+        }
+        let start_node = self.node_mut(start);
+        for child in start_node.children.iter_mut() {
+            if *child == path[0] {
+                *child = last;
+            }
+        }
+        if start_node.redo_child == Some(path[0]) {
+            start_node.redo_child = Some(last);
+        }
+        let node = self.node_mut(last);
+        node.parent = Some(start);
+        node.action = Some(EditAction::Bulk(actions));
+        node.before = before;
     }
 
     // UNDO AND REDO FUNCTIONALITY
     /// undo's last action of user
     pub fn undo(&mut self) -> Result<EditAction, UndoError> {
-        if let Some(last_action) = self.undo_stack.pop_back() {
-            let action_reversed = self.reverse_action(&last_action);
-            self.redo_stack.push_back(last_action);
-            Ok(action_reversed)
-        } else {
-            Err(UndoError::NoActionToUndo)
+        let node = &self.nodes[&self.current];
+        let (Some(parent), Some(action)) = (node.parent, node.action.clone()) else {
+            return Err(UndoError::NoActionToUndo);
+        };
+        self.typing = None;
+        // undoing past the start of a group shrinks it
+        if self.group_start == Some(self.current) {
+            self.group_start = Some(parent);
         }
+        self.node_mut(parent).redo_child = Some(self.current);
+        self.current = parent;
+        Ok(self.reverse_action(&action))
     }
 
     /// redo's last action of user
     pub fn redo(&mut self) -> Result<EditAction, RedoError> {
-        if let Some(last_action) = self.redo_stack.pop_back() {
-            self.undo_stack.push_back(last_action.clone());
-            Ok(last_action)
-        } else {
-            Err(RedoError::NoActionToRedo)
+        let node = &self.nodes[&self.current];
+        let child = node
+            .redo_child
+            .or(node.children.last().copied())
+            .ok_or(RedoError::NoActionToRedo)?;
+        self.typing = None;
+        self.current = child;
+        self.nodes[&child]
+            .action
+            .clone()
+            .ok_or(RedoError::NoActionToRedo)
+    }
+
+    // TREE NAVIGATION
+
+    /// Id of the current state
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn node(&self, id: usize) -> Option<&UndoNode> {
+        self.nodes.get(&id)
+    }
+
+    /// States by id, in the order they were created
+    pub fn nodes(&self) -> impl Iterator<Item = (usize, &UndoNode)> {
+        self.nodes.iter().map(|(id, node)| (*id, node))
+    }
+
+    /// Moves to state target, on any branch. Returns the actions taking the
+    /// text there, undos up to the branch point then redos down from it
+    pub fn goto_state(&mut self, target: usize) -> Result<Vec<EditAction>, UndoError> {
+        if !self.nodes.contains_key(&target) {
+            return Err(UndoError::NoSuchState(target));
+        }
+        let target_path = self.path_from_root(target);
+        let on_target_path: HashSet<usize> = target_path.iter().copied().collect();
+
+        let mut actions = Vec::new();
+        while !on_target_path.contains(&self.current) {
+            actions.push(self.undo()?);
+        }
+        let below: Vec<usize> = target_path
+            .into_iter()
+            .skip_while(|id| *id != self.current)
+            .skip(1)
+            .collect();
+        for id in below {
+            self.node_mut(self.current).redo_child = Some(id);
+            actions.push(self.redo().map_err(|_| UndoError::FailedToUndo)?);
+        }
+        Ok(actions)
+    }
+
+    /// State created before the current one, whatever branch it is on
+    pub fn earlier_state(&self) -> Option<usize> {
+        self.nodes
+            .range(..self.current)
+            .next_back()
+            .map(|(id, _)| *id)
+    }
+
+    /// State created after the current one, whatever branch it is on
+    pub fn later_state(&self) -> Option<usize> {
+        self.nodes
+            .range(self.current + 1..)
+            .next()
+            .map(|(id, _)| *id)
+    }
+
+    /// State distance before the current one, for :earlier. Going back
+    /// further than the history reaches gives the oldest state
+    pub fn earlier_target(&self, distance: HistoryDistance) -> usize {
+        match distance {
+            HistoryDistance::Steps(n) => self
+                .nodes
+                .range(..self.current)
+                .rev()
+                .nth(n.saturating_sub(1))
+                .map_or(self.root, |(id, _)| *id),
+            HistoryDistance::Time(time) => {
+                let current = self.nodes[&self.current].time;
+                current
+                    .checked_sub(time)
+                    .and_then(|at| self.state_at(at))
+                    .unwrap_or(self.root)
+            }
+            HistoryDistance::Saves(n) => {
+                let saves = self.live_saves();
+                // unsaved changes make the last save the first one back
+                let behind = match saves.iter().rposition(|id| *id == self.current) {
+                    Some(idx) => idx,
+                    None => saves.iter().filter(|id| **id < self.current).count(),
+                };
+                behind.checked_sub(n).map_or(self.root, |idx| saves[idx])
+            }
+        }
+    }
+
+    /// State distance after the current one, for :later. Going further than
+    /// the history reaches gives the newest state
+    pub fn later_target(&self, distance: HistoryDistance) -> usize {
+        let newest = *self.nodes.keys().next_back().expect("root is kept");
+        match distance {
+            HistoryDistance::Steps(n) => self
+                .nodes
+                .range(self.current + 1..)
+                .nth(n.saturating_sub(1))
+                .map_or(newest, |(id, _)| *id),
+            HistoryDistance::Time(time) => {
+                let current = self.nodes[&self.current].time;
+                current
+                    .checked_add(time)
+                    .and_then(|at| self.state_at(at))
+                    .map_or(newest, |id| id.max(self.current))
+            }
+            HistoryDistance::Saves(n) => {
+                let saves = self.live_saves();
+                let ahead = match saves.iter().rposition(|id| *id == self.current) {
+                    Some(idx) => idx + n,
+                    None => saves.iter().filter(|id| **id < self.current).count() + n - 1,
+                };
+                saves.get(ahead).copied().unwrap_or(newest)
+            }
+        }
+    }
+
+    /// The newest state made at or before time, None if all are newer
+    fn state_at(&self, time: SystemTime) -> Option<usize> {
+        self.nodes
+            .iter()
+            .rev()
+            .find(|(_, node)| node.time <= time)
+            .map(|(id, _)| *id)
+    }
+
+    /// Saves whose states are still in the tree
+    fn live_saves(&self) -> Vec<usize> {
+        self.saves
+            .iter()
+            .copied()
+            .filter(|id| self.nodes.contains_key(id))
+            .collect()
+    }
+
+    /// Actions from the oldest state up to the current one, what undo undoes
+    /// from last to first
+    pub fn undo_stack(&self) -> VecDeque<EditAction> {
+        self.path_from_root(self.current)
+            .into_iter()
+            .filter_map(|id| self.nodes[&id].action.clone())
+            .collect()
+    }
+
+    /// Actions redo redoes, the next one last
+    pub fn redo_stack(&self) -> VecDeque<EditAction> {
+        let mut stack = VecDeque::new();
+        let mut id = self.current;
+        while let Some(child) = self
+            .nodes
+            .get(&id)
+            .and_then(|node| node.redo_child.or(node.children.last().copied()))
+        {
+            if let Some(action) = self.nodes[&child].action.clone() {
+                stack.push_front(action);
+            }
+            id = child;
+        }
+        stack
+    }
+
+    /// The tree as rows, depth first. Older branches are indented under the
+    /// state they branch from and the newest continues the line
+    pub fn tree_rows(&self) -> Vec<UndoTreeRow> {
+        let mut rows = Vec::with_capacity(self.nodes.len());
+        let mut stack = vec![(self.root, 0)];
+        while let Some((id, depth)) = stack.pop() {
+            let node = &self.nodes[&id];
+            rows.push(UndoTreeRow {
+                id,
+                depth,
+                summary: node
+                    .action
+                    .as_ref()
+                    .map(summarize)
+                    .unwrap_or_else(|| "original".to_string()),
+                current: id == self.current,
+                saved: Some(id) == self.saved,
+            });
+            if let Some((newest, older)) = node.children.split_last() {
+                stack.push((*newest, depth));
+                stack.extend(older.iter().rev().map(|child| (*child, depth + 1)));
+            }
+        }
+        rows
+    }
+
+    // PERSISTENCE
+
+    /// The tree in the form written to an undo file
+    pub fn to_history(&self) -> UndoHistory {
+        UndoHistory {
+            current: self.current,
+            next_id: self.next_id,
+            saves: self.saves.clone(),
+            nodes: self
+                .nodes
+                .iter()
+                .map(|(id, node)| StoredNode {
+                    id: *id,
+                    parent: node.parent,
+                    redo_child: node.redo_child,
+                    action: node.action.clone(),
+                    before: node.before,
+                    after: node.after,
+                    time: node.time,
+                })
+                .collect(),
+        }
+    }
+
+    /// Rebuilds a tree read from an undo file, with its current state as the
+    /// saved one. Fails if the nodes don't make up a single tree
+    pub fn from_history(history: UndoHistory, max_history: usize) -> Result<Self, UndoError> {
+        let invalid = |reason: &str| UndoError::UndoFile(reason.to_string());
+        let mut nodes: BTreeMap<usize, UndoNode> = BTreeMap::new();
+        let mut root = None;
+        for stored in history.nodes {
+            match (stored.parent, &stored.action) {
+                (None, None) if root.is_none() => root = Some(stored.id),
+                (Some(parent), Some(_)) if parent < stored.id && nodes.contains_key(&parent) => {}
+                _ => return Err(invalid("nodes don't form a tree")),
+            }
+            if stored.id >= history.next_id || nodes.contains_key(&stored.id) {
+                return Err(invalid("node ids are out of order"));
+            }
+            if let Some(parent) = stored.parent.and_then(|parent| nodes.get_mut(&parent)) {
+                parent.children.push(stored.id);
+            }
+            nodes.insert(
+                stored.id,
+                UndoNode {
+                    action: stored.action,
+                    parent: stored.parent,
+                    children: Vec::new(),
+                    redo_child: stored.redo_child,
+                    before: stored.before,
+                    after: stored.after,
+                    time: stored.time,
+                },
+            );
+        }
+        let root = root.ok_or_else(|| invalid("no root state"))?;
+        for node in nodes.values_mut() {
+            // a redo child that isn't one leaves redo to the newest branch
+            if node
+                .redo_child
+                .is_some_and(|id| !node.children.contains(&id))
+            {
+                node.redo_child = None;
+            }
+        }
+        if !nodes.contains_key(&history.current) {
+            return Err(UndoError::NoSuchState(history.current));
+        }
+
+        let mut manager = Self {
+            nodes,
+            root,
+            current: history.current,
+            next_id: history.next_id,
+            max_history,
+            saved: Some(history.current),
+            group_start: None,
+            idle_threshold: Duration::ZERO,
+            typing: None,
+            revision: 0,
+            saves: history.saves,
+        };
+        manager.saves.retain(|id| manager.nodes.contains_key(id));
+        manager.mark_saved();
+        manager.trim_history();
+        Ok(manager)
+    }
+
+    /// Ids from the root down to id
+    fn path_from_root(&self, id: usize) -> Vec<usize> {
+        let mut path = vec![id];
+        let mut id = id;
+        while let Some(parent) = self.nodes.get(&id).and_then(|node| node.parent) {
+            path.push(parent);
+            id = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut UndoNode {
+        self.nodes.get_mut(&id).expect("undo tree node exists")
+    }
+
+    fn remove_node(&mut self, id: usize) {
+        self.nodes.remove(&id);
+        if self.saved == Some(id) {
+            self.saved = None;
+        }
+    }
+
+    fn remove_subtree(&mut self, id: usize) {
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.nodes.get(&id) {
+                stack.extend(node.children.iter().copied());
+            }
+            self.remove_node(id);
         }
     }
 
@@ -137,8 +830,9 @@ impl UndoRedoManager {
             },
             EditAction::JoinLine { pos, merged } => EditAction::SplitLine {
                 pos: *pos,
-                left: merged[..pos.x].to_string(),
-                right: merged[pos.x..].to_string(),
+                // pos.x is a char index, split on chars so multibyte lines don't panic
+                left: char_slice(merged, 0, pos.x).to_string(),
+                right: char_slice(merged, pos.x, usize::MAX).to_string(),
             },
             EditAction::Bulk(actions) => {
                 let reversed_actions = actions
@@ -151,3 +845,252 @@ impl UndoRedoManager {
         }
     }
 }
+
+/// Action doing first then then, as one Bulk
+fn folded(first: EditAction, then: EditAction) -> EditAction {
+    let mut actions = match first {
+        EditAction::Bulk(actions) => actions,
+        action => vec![action],
+    };
+    match then {
+        EditAction::Bulk(more) => actions.extend(more),
+        action => actions.push(action),
+    }
+    EditAction::Bulk(actions)
+}
+
+/// Short description of action for listing it in the undo tree
+fn summarize(action: &EditAction) -> String {
+    let lines = |n: usize| if n == 1 { "line" } else { "lines" };
+    match action {
+        EditAction::Insert { pos, c } => format!("insert '{}' at {}:{}", c, pos.y + 1, pos.x + 1),
+        EditAction::Delete { pos, deleted_char } => {
+            format!("delete '{}' at {}:{}", deleted_char, pos.y + 1, pos.x + 1)
+        }
+        EditAction::Replace {
+            start, old, new, ..
+        } => {
+            format!(
+                "replace '{}' with '{}' at {}:{}",
+                old,
+                new,
+                start.y + 1,
+                start.x + 1
+            )
+        }
+        EditAction::ReplaceRange { start, new, .. } => format!(
+            "replace with {} {} at {}:{}",
+            new.len(),
+            lines(new.len()),
+            start.y + 1,
+            start.x + 1
+        ),
+        EditAction::InsertLines { start, lines: new }
+        | EditAction::InsertRange {
+            start, lines: new, ..
+        } => format!(
+            "insert {} {} at {}:{}",
+            new.len(),
+            lines(new.len()),
+            start.y + 1,
+            start.x + 1
+        ),
+        EditAction::DeleteLines { start, deleted }
+        | EditAction::DeleteRange { start, deleted, .. } => format!(
+            "delete {} {} at {}:{}",
+            deleted.len(),
+            lines(deleted.len()),
+            start.y + 1,
+            start.x + 1
+        ),
+        EditAction::SplitLine { pos, .. } => format!("split line {}", pos.y + 1),
+        EditAction::JoinLine { pos, .. } => format!("join line {}", pos.y + 1),
+        EditAction::Bulk(actions) => match typed_text(actions) {
+            Some((true, text, pos)) => format!("insert '{}' at {}:{}", text, pos.y + 1, pos.x + 1),
+            Some((false, text, pos)) => format!("delete '{}' at {}:{}", text, pos.y + 1, pos.x + 1),
+            None => format!("{} edits", actions.len()),
+        },
+    }
+}
+
+/// Text of a run of chars typed, or deleted, on one line, with whether it
+/// was typed and where it starts. None for other bulks
+fn typed_text(actions: &[EditAction]) -> Option<(bool, String, CursorPosition)> {
+    let mut chars = actions
+        .iter()
+        .map(|action| match action {
+            EditAction::Insert { pos, c } => Some((true, *pos, *c)),
+            EditAction::Delete { pos, deleted_char } => Some((false, *pos, *deleted_char)),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let insert = chars.first()?.0;
+    if chars
+        .iter()
+        .any(|(kind, pos, _)| *kind != insert || pos.y != chars[0].1.y)
+    {
+        return None;
+    }
+    // backspace deletes right to left, so the chars are put in text order
+    chars.sort_by_key(|(_, pos, _)| pos.x);
+    Some((
+        insert,
+        chars.iter().map(|(_, _, c)| c).collect(),
+        chars[0].1,
+    ))
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_undo_coalescing_tests {
+    use super::*;
+
+    const IDLE: Duration = Duration::from_millis(1000);
+
+    fn manager() -> UndoRedoManager {
+        UndoRedoManager::new(100).with_idle_threshold(IDLE)
+    }
+
+    fn insert(x: usize, c: char) -> EditAction {
+        EditAction::Insert {
+            pos: CursorPosition { x, y: 0 },
+            c,
+        }
+    }
+
+    /// types text from x, a char every 100ms starting at start
+    fn type_text(manager: &mut UndoRedoManager, x: usize, text: &str, start: SystemTime) {
+        for (i, c) in text.chars().enumerate() {
+            let at = start + Duration::from_millis(100 * i as u64);
+            manager.record_undo_at(insert(x + i, c), at);
+        }
+    }
+
+    fn summaries(manager: &UndoRedoManager) -> Vec<String> {
+        manager
+            .tree_rows()
+            .into_iter()
+            .skip(1)
+            .map(|row| row.summary)
+            .collect()
+    }
+
+    #[test]
+    fn test_typing_coalesces_by_word() {
+        let mut manager = manager();
+        type_text(&mut manager, 0, "hi there", SystemTime::now());
+        assert_eq!(
+            summaries(&manager),
+            vec!["insert 'hi ' at 1:1", "insert 'there' at 1:4"]
+        );
+    }
+
+    #[test]
+    fn test_pause_jump_newline_and_save_break_typing() {
+        let start = SystemTime::now();
+        let mut manager = manager();
+        type_text(&mut manager, 0, "ab", start);
+        // a pause longer than the threshold
+        manager.record_undo_at(insert(2, 'c'), start + IDLE * 2);
+        // typing somewhere else
+        manager.record_undo_at(insert(7, 'd'), start + IDLE * 2);
+        manager.record_undo(EditAction::SplitLine {
+            pos: CursorPosition { x: 8, y: 0 },
+            left: "abc    d".to_string(),
+            right: String::new(),
+        });
+        manager.record_undo_at(insert(0, 'e'), start + IDLE * 3);
+        manager.mark_saved();
+        manager.record_undo_at(insert(1, 'f'), start + IDLE * 3);
+
+        // the root, ab, c, d, the split, e and f
+        assert_eq!(manager.nodes().count(), 7);
+        assert!(manager.is_dirty());
+    }
+
+    #[test]
+    fn test_backspaces_coalesce_and_undo_together() {
+        let start = SystemTime::now();
+        let mut manager = manager();
+        for (i, (x, c)) in [(2, 'c'), (1, 'b'), (0, 'a')].into_iter().enumerate() {
+            let delete = EditAction::Delete {
+                pos: CursorPosition { x, y: 0 },
+                deleted_char: c,
+            };
+            manager.record_undo_at(delete, start + Duration::from_millis(i as u64));
+        }
+        assert_eq!(summaries(&manager), vec!["delete 'abc' at 1:1"]);
+
+        let EditAction::Bulk(reversed) = manager.undo().unwrap() else {
+            panic!("coalesced deletes undo as one bulk");
+        };
+        assert_eq!(reversed.len(), 3);
+        assert!(manager.undo().is_err());
+    }
+
+    #[test]
+    fn test_zero_threshold_keeps_every_char() {
+        let mut manager = UndoRedoManager::new(100);
+        type_text(&mut manager, 0, "abc", SystemTime::now());
+        assert_eq!(manager.nodes().count(), 4);
+    }
+}
+
+#[cfg(test)]
+mod unit_undo_time_travel_tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    fn insert(x: usize, c: char) -> EditAction {
+        EditAction::Insert {
+            pos: CursorPosition { x, y: 0 },
+            c,
+        }
+    }
+
+    /// states 1 to 4 made 1, 2, 3 and 10 minutes from now, saved at 2
+    fn history() -> UndoRedoManager {
+        let start = SystemTime::now();
+        let mut manager = UndoRedoManager::new(100);
+        manager.record_undo_at(insert(0, 'a'), start + MINUTE);
+        manager.record_undo_at(insert(1, 'b'), start + MINUTE * 2);
+        manager.mark_saved();
+        manager.record_undo_at(insert(2, 'c'), start + MINUTE * 3);
+        manager.record_undo_at(insert(3, 'd'), start + MINUTE * 10);
+        manager
+    }
+
+    #[test]
+    fn test_earlier_by_time_steps_and_saves() {
+        let manager = history();
+        assert_eq!(manager.earlier_target(HistoryDistance::Time(MINUTE * 5)), 3);
+        assert_eq!(
+            manager.earlier_target(HistoryDistance::Time(MINUTE * 60)),
+            0
+        );
+        assert_eq!(manager.earlier_target(HistoryDistance::Steps(1)), 3);
+        // unsaved changes go back to the last save first
+        assert_eq!(manager.earlier_target(HistoryDistance::Saves(1)), 2);
+        assert_eq!(manager.earlier_target(HistoryDistance::Saves(2)), 0);
+    }
+
+    #[test]
+    fn test_later_by_time_steps_and_saves() {
+        let mut manager = history();
+        manager.goto_state(0).unwrap();
+        assert_eq!(
+            manager.later_target(HistoryDistance::Time(MINUTE * 3 / 2)),
+            1
+        );
+        assert_eq!(manager.later_target(HistoryDistance::Time(MINUTE * 60)), 4);
+        assert_eq!(manager.later_target(HistoryDistance::Steps(2)), 2);
+        assert_eq!(manager.later_target(HistoryDistance::Saves(1)), 2);
+        assert_eq!(manager.later_target(HistoryDistance::Saves(2)), 4);
+    }
+}
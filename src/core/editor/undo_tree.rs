@@ -0,0 +1,250 @@
+//! Moving between states of the undo tree, across branches.
+
+use super::editor::Editor;
+use super::text_buffer::TextBuffer;
+use super::undo_redo::CursorState;
+use crate::errors::editor_errors::{EditorError, UndoError};
+
+impl Editor {
+    /// Takes the text to state id of the undo tree, on whatever branch
+    pub fn goto_undo_state(&mut self, id: usize) -> Result<(), EditorError> {
+        let from = self.undo_redo_manager.current();
+        let actions = self.undo_redo_manager.goto_state(id)?;
+        //secondary cursors and blocks do not follow the text back, so they are dropped
+        self.clear_secondary_cursors();
+        self.clear_block_selection();
+        self.reset_text_selection_cursor();
+        for action in &actions {
+            self.apply_action(action);
+        }
+        let state = self.cursor_state_at(id, from);
+        self.restore_cursor_state(state);
+        Ok(())
+    }
+
+    /// Cursor state on arriving at state id from state from. Only undoing
+    /// leaves the cursor where the last undone edit started, otherwise it
+    /// goes where the edit leading to id ended
+    fn cursor_state_at(&self, id: usize, from: usize) -> Option<CursorState> {
+        let manager = &self.undo_redo_manager;
+        let mut below = from;
+        while let Some(node) = manager.node(below) {
+            match node.parent {
+                Some(parent) if parent == id => return node.before,
+                Some(parent) => below = parent,
+                None => break,
+            }
+        }
+        manager.node(id).and_then(|node| node.after)
+    }
+
+    /// Text as it is in state id, leaving the editor as it is
+    pub fn text_at_undo_state(&self, id: usize) -> Result<TextBuffer, EditorError> {
+        let mut scratch = Editor::new(self.editor_config.clone());
+        scratch.editor_content = self.editor_content.clone();
+        scratch.undo_redo_manager = self.undo_redo_manager.clone();
+        scratch.goto_undo_state(id)?;
+        Ok(scratch.editor_content)
+    }
+
+    /// Goes to the state created before the current one, stepping through
+    /// every branch in the order they were made
+    pub fn older_state(&mut self) -> Result<(), EditorError> {
+        let id = self
+            .undo_redo_manager
+            .earlier_state()
+            .ok_or(UndoError::NoActionToUndo)?;
+        self.goto_undo_state(id)
+    }
+
+    /// Goes to the state created after the current one
+    pub fn newer_state(&mut self) -> Result<(), EditorError> {
+        let id = self
+            .undo_redo_manager
+            .later_state()
+            .ok_or(UndoError::NoActionToUndo)?;
+        self.goto_undo_state(id)
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_undo_tree_tests {
+    use super::*;
+    use crate::config::{Config, EditorConfig};
+    use crate::core::cursor::CursorPosition;
+    use crate::core::editor::editor::EditAction;
+    use crate::core::editor::undo_redo::UndoRedoManager;
+    use crate::input::actions::{EditorAction, InputAction};
+    use std::sync::Arc;
+
+    fn create_editor_with_editor_content(vec: Vec<&str>) -> Editor {
+        // chars typed are states of their own, to branch between them
+        let config = EditorConfig {
+            undo_group_idle_ms: 0,
+            ..Config::default().editor
+        };
+        let mut editor = Editor::new(Arc::new(config));
+        editor.editor_content = vec.into_iter().map(String::from).collect();
+        editor
+    }
+
+    fn insert(x: usize, c: char) -> EditAction {
+        EditAction::Insert {
+            pos: CursorPosition { x, y: 0 },
+            c,
+        }
+    }
+
+    #[test]
+    fn test_editing_after_undo_keeps_the_undone_branch() {
+        let mut editor = create_editor_with_editor_content(vec![""]);
+        editor.write_char('a');
+        editor.write_char('b');
+        editor.undo().unwrap();
+        editor.write_char('c');
+        assert_eq!(editor.editor_content[0], "ac");
+
+        // states 0 "", 1 "a", 2 "ab" and 3 "ac"
+        editor.goto_undo_state(2).unwrap();
+        assert_eq!(editor.editor_content[0], "ab");
+        editor.goto_undo_state(0).unwrap();
+        assert_eq!(editor.editor_content[0], "");
+        // redo follows the branch left last
+        editor.redo().unwrap();
+        editor.redo().unwrap();
+        assert_eq!(editor.editor_content[0], "ab");
+        assert!(editor.goto_undo_state(9).is_err());
+    }
+
+    #[test]
+    fn test_older_and_newer_states_walk_every_branch() {
+        let mut editor = create_editor_with_editor_content(vec![""]);
+        editor.write_char('a');
+        editor.write_char('b');
+        editor.undo().unwrap();
+        editor.write_char('c');
+
+        editor.older_state().unwrap();
+        assert_eq!(editor.editor_content[0], "ab");
+        editor.older_state().unwrap();
+        assert_eq!(editor.editor_content[0], "a");
+        editor.newer_state().unwrap();
+        editor.newer_state().unwrap();
+        assert_eq!(editor.editor_content[0], "ac");
+        assert!(editor.newer_state().is_err());
+    }
+
+    #[test]
+    fn test_going_to_a_state_puts_the_cursor_at_its_edit() {
+        let mut editor = create_editor_with_editor_content(vec!["one", "two", "three"]);
+        let type_at = |editor: &mut Editor, y: i32, c: char| {
+            editor.cursor.y = y;
+            editor.cursor.x = 0;
+            let action = InputAction::Editor(EditorAction::WriteChar(c));
+            editor.handle_input_action(action).unwrap();
+        };
+        type_at(&mut editor, 0, 'a');
+        editor.undo().unwrap();
+        type_at(&mut editor, 2, 'b');
+
+        // redoing down the other branch ends where its edit did
+        editor.goto_undo_state(1).unwrap();
+        assert_eq!(editor.editor_content[0], "aone");
+        assert_eq!((editor.cursor.x, editor.cursor.y), (1, 0));
+        // undoing only goes back to where the edit started
+        editor.cursor.y = 2;
+        editor.goto_undo_state(0).unwrap();
+        assert_eq!((editor.cursor.x, editor.cursor.y), (0, 0));
+    }
+
+    #[test]
+    fn test_tree_rows_indent_older_branches() {
+        let mut manager = UndoRedoManager::new(100);
+        manager.record_undo(insert(0, 'a'));
+        manager.record_undo(insert(1, 'b'));
+        manager.undo().unwrap();
+        manager.record_undo(insert(1, 'c'));
+
+        let rows: Vec<(usize, usize, bool)> = manager
+            .tree_rows()
+            .iter()
+            .map(|row| (row.id, row.depth, row.current))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![(0, 0, false), (1, 0, false), (2, 1, false), (3, 0, true)]
+        );
+        assert_eq!(manager.tree_rows()[3].summary, "insert 'c' at 1:2");
+        assert!(manager.tree_rows()[0].saved);
+    }
+
+    #[test]
+    fn test_trimming_drops_oldest_states() {
+        let mut manager = UndoRedoManager::new(2);
+        manager.record_undo(insert(0, 'a'));
+        manager.undo().unwrap();
+        manager.record_undo(insert(0, 'b'));
+        manager.record_undo(insert(1, 'c'));
+        manager.record_undo(insert(2, 'd'));
+
+        // "a" branched off the dropped root, "b" became the new root
+        let ids: Vec<usize> = manager.nodes().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![2, 3, 4]);
+        assert_eq!(manager.undo_stack().len(), 2);
+        assert!(manager.is_dirty());
+        manager.undo().unwrap();
+        manager.undo().unwrap();
+        assert!(manager.undo().is_err());
+    }
+
+    #[test]
+    fn test_trimming_keeps_newer_branches() {
+        let mut manager = UndoRedoManager::new(3);
+        for x in 0..3 {
+            manager.record_undo(insert(x, 'a'));
+        }
+        manager.goto_state(0).unwrap();
+        manager.record_undo(insert(0, 'b'));
+
+        // the original text went, the undone branch is still there
+        let ids: Vec<usize> = manager.nodes().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+        let actions = manager.goto_state(3).unwrap();
+        assert_eq!(actions.len(), 3);
+
+        // with the oldest state current, the tree still shrinks to the limit
+        manager.goto_state(1).unwrap();
+        manager.update_limit(1);
+        let ids: Vec<usize> = manager.nodes().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![1, 4]);
+    }
+
+    #[test]
+    fn test_trimmed_branches_still_apply_to_the_text() {
+        let mut editor = Editor::new(Arc::new(EditorConfig {
+            undo_history_limit: 2,
+            undo_group_idle_ms: 0,
+            ..EditorConfig::default()
+        }));
+        editor.editor_content = TextBuffer::from_text("");
+        editor.write_char('a');
+        editor.write_char('b');
+        editor.undo().unwrap();
+        editor.undo().unwrap();
+        editor.write_char('x');
+        editor.write_char('y');
+
+        // "ab" became the root, with the "xy" branch moved under it
+        let newest = editor.undo_redo_manager.current();
+        editor.goto_undo_state(2).unwrap();
+        assert_eq!(editor.editor_content[0], "ab");
+        editor.goto_undo_state(newest).unwrap();
+        assert_eq!(editor.editor_content[0], "xy");
+    }
+}
@@ -0,0 +1,247 @@
+//! Visual layout of logical lines: tab expansion and soft wrapping.
+//!
+//! Shared by editor cursor movement and UI rendering so both agree on
+//! where a character ends up on screen. Lines are laid out per grapheme
+//! with display widths, a wide grapheme that does not fit the rest of a
+//! row moves to the next one.
+
+use super::graphemes;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Places graphemes one after another in rows of `wrap_width` cells
+struct RowLayout {
+    wrap_width: Option<usize>,
+    tab_width: usize,
+    row: usize,
+    col: usize,
+    line_col: usize, // column in the unwrapped line, tab stops are based on it
+}
+
+impl RowLayout {
+    fn new(wrap_width: Option<usize>, tab_width: usize) -> Self {
+        Self {
+            wrap_width: wrap_width.map(|w| w.max(1)),
+            tab_width: tab_width.max(1),
+            row: 0,
+            col: 0,
+            line_col: 0,
+        }
+    }
+
+    fn width_of(&self, grapheme: &str) -> usize {
+        if grapheme == "\t" {
+            self.tab_width - (self.line_col % self.tab_width)
+        } else {
+            graphemes::display_width(grapheme)
+        }
+    }
+
+    /// Moves to the next row if grapheme does not fit the rest of this one.
+    /// Tabs are drawn as spaces, so they are split over rows instead
+    fn fit(&mut self, grapheme: &str, width: usize) {
+        if let Some(wrap_width) = self.wrap_width {
+            if grapheme != "\t" && self.col > 0 && self.col + width > wrap_width {
+                self.row += 1;
+                self.col = 0;
+            }
+        }
+    }
+
+    fn advance(&mut self, width: usize) {
+        self.col += width;
+        self.line_col += width;
+        if let Some(wrap_width) = self.wrap_width {
+            if self.col >= wrap_width {
+                self.row += self.col / wrap_width;
+                self.col %= wrap_width;
+            }
+        }
+    }
+}
+
+/// Row and column where the grapheme starting at `char_idx` is drawn, rows only
+/// when wrapping. Indexes past the end give the position after the last grapheme,
+/// which is on a row of its own if the last row is full
+pub fn visual_position(
+    line: &str,
+    char_idx: usize,
+    wrap_width: Option<usize>,
+    tab_width: usize,
+) -> (usize, usize) {
+    let mut layout = RowLayout::new(wrap_width, tab_width);
+    let mut idx = 0;
+    for grapheme in line.graphemes(true) {
+        let width = layout.width_of(grapheme);
+        layout.fit(grapheme, width);
+        if idx >= char_idx {
+            return (layout.row, layout.col);
+        }
+        layout.advance(width);
+        idx += grapheme.chars().count();
+    }
+    (layout.row, layout.col)
+}
+
+/// Char index of the grapheme drawn at `row` and `col`. A column past the end of
+/// the row gives its last grapheme, or the line length on the last row
+pub fn char_idx_at_position(
+    line: &str,
+    row: usize,
+    col: usize,
+    wrap_width: Option<usize>,
+    tab_width: usize,
+) -> usize {
+    let mut layout = RowLayout::new(wrap_width, tab_width);
+    let mut idx = 0;
+    let mut last_in_row = None;
+    for grapheme in line.graphemes(true) {
+        let width = layout.width_of(grapheme);
+        layout.fit(grapheme, width);
+        if layout.row > row {
+            return last_in_row.unwrap_or(idx);
+        }
+        if layout.row == row {
+            if col < layout.col + width {
+                return idx;
+            }
+            last_in_row = Some(idx);
+        }
+        layout.advance(width);
+        idx += grapheme.chars().count();
+    }
+    idx
+}
+
+/// Visual column where the char at `char_idx` starts, tabs expanded to the next
+/// tab stop. Indexes past the end give the column after the last char
+pub fn visual_col(line: &str, char_idx: usize, tab_width: usize) -> usize {
+    visual_position(line, char_idx, None, tab_width).1
+}
+
+/// Char index of the grapheme covering visual column `col`, the line length when
+/// `col` is past the end
+pub fn char_idx_at_visual_col(line: &str, col: usize, tab_width: usize) -> usize {
+    char_idx_at_position(line, 0, col, None, tab_width)
+}
+
+/// Rows a line takes when wrapped at `wrap_width`.
+/// A row is kept for the cursor past the end, so a line filling a row exactly takes two
+pub fn wrapped_rows(line: &str, wrap_width: usize, tab_width: usize) -> usize {
+    visual_position(line, usize::MAX, Some(wrap_width), tab_width).0 + 1
+}
+
+/// Line with tabs expanded to spaces up to the next tab stop, as it is drawn
+pub fn expand_tabs(line: &str, tab_width: usize) -> String {
+    let mut layout = RowLayout::new(None, tab_width);
+    let mut expanded = String::with_capacity(line.len());
+    for grapheme in line.graphemes(true) {
+        let width = layout.width_of(grapheme);
+        if grapheme == "\t" {
+            expanded.extend(std::iter::repeat_n(' ', width));
+        } else {
+            expanded.push_str(grapheme);
+        }
+        layout.advance(width);
+    }
+    expanded
+}
+
+/// Splits a tab free line into the char ranges of its wrapped rows, always at least one
+pub fn row_ranges(line: &str, wrap_width: usize) -> Vec<std::ops::Range<usize>> {
+    let mut layout = RowLayout::new(Some(wrap_width), 1);
+    let mut ranges = Vec::new();
+    let mut row_start = 0;
+    let mut idx = 0;
+    for grapheme in line.graphemes(true) {
+        let width = layout.width_of(grapheme);
+        let row = layout.row;
+        layout.fit(grapheme, width);
+        if layout.row != row {
+            ranges.push(row_start..idx);
+            row_start = idx;
+        }
+        let row = layout.row;
+        layout.advance(width);
+        idx += grapheme.chars().count();
+        // a row filled up exactly, the next grapheme or the cursor starts a new one
+        for _ in row..layout.row {
+            ranges.push(row_start..idx);
+            row_start = idx;
+        }
+    }
+    ranges.push(row_start..idx);
+    ranges
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_wrap_tests {
+    use super::*;
+
+    #[test]
+    fn test_visual_col_expands_tabs_to_tab_stops() {
+        assert_eq!(visual_col("ab\tc", 2, 4), 2);
+        assert_eq!(visual_col("ab\tc", 3, 4), 4);
+        assert_eq!(visual_col("\t\tx", 2, 4), 8);
+        assert_eq!(visual_col("abc", 10, 4), 3);
+    }
+
+    #[test]
+    fn test_visual_col_uses_display_width() {
+        assert_eq!(visual_col("漢字x", 1, 4), 2);
+        assert_eq!(visual_col("漢字x", 2, 4), 4);
+        // combining mark adds no width
+        assert_eq!(visual_col("e\u{301}x", 2, 4), 1);
+    }
+
+    #[test]
+    fn test_char_idx_at_visual_col_inside_tab() {
+        assert_eq!(char_idx_at_visual_col("a\tb", 0, 4), 0);
+        assert_eq!(char_idx_at_visual_col("a\tb", 2, 4), 1);
+        assert_eq!(char_idx_at_visual_col("a\tb", 4, 4), 2);
+        assert_eq!(char_idx_at_visual_col("a\tb", 9, 4), 3);
+    }
+
+    #[test]
+    fn test_char_idx_at_visual_col_inside_wide_char() {
+        assert_eq!(char_idx_at_visual_col("漢字", 1, 4), 0);
+        assert_eq!(char_idx_at_visual_col("漢字", 3, 4), 1);
+    }
+
+    #[test]
+    fn test_wrapped_rows_keeps_room_for_cursor() {
+        assert_eq!(wrapped_rows("", 4, 4), 1);
+        assert_eq!(wrapped_rows("abc", 4, 4), 1);
+        assert_eq!(wrapped_rows("abcd", 4, 4), 2);
+        assert_eq!(wrapped_rows("abcdefghi", 4, 4), 3);
+        assert_eq!(wrapped_rows("\tab", 4, 4), 2);
+    }
+
+    #[test]
+    fn test_wide_char_moves_to_next_row_when_it_does_not_fit() {
+        // 'a' and '漢' fill 3 of 4 cells, '字' needs 2 so it starts the next row
+        let line = "a漢字";
+        assert_eq!(visual_position(line, 2, Some(4), 4), (1, 0));
+        assert_eq!(wrapped_rows(line, 4, 4), 2);
+        assert_eq!(char_idx_at_position(line, 0, 3, Some(4), 4), 1);
+        assert_eq!(row_ranges(line, 4), vec![0..2, 2..3]);
+    }
+
+    #[test]
+    fn test_row_ranges_match_wrapped_rows() {
+        assert_eq!(row_ranges("", 4), vec![0..0]);
+        assert_eq!(row_ranges("abcd", 4), vec![0..4, 4..4]);
+        assert_eq!(row_ranges("abcdefghi", 4), vec![0..4, 4..8, 8..9]);
+    }
+
+    #[test]
+    fn test_expand_tabs_to_next_stop() {
+        assert_eq!(expand_tabs("a\tb", 4), "a   b");
+        assert_eq!(expand_tabs("漢\tb", 4), "漢  b");
+    }
+}
@@ -0,0 +1,304 @@
+//! Keyboard macros, editor input actions recorded into named registers and
+//! replayed on demand.
+//!
+//! Macros are kept as the input actions themselves. To be stored in the
+//! config file they are written as steps, the keymap name of an action or
+//! `text:` followed by the characters typed in a row.
+
+use crate::config::KeymapConfig;
+use crate::errors::config_errors::ConfigError;
+use crate::input::actions::{EditorAction, InputAction};
+use std::collections::{BTreeMap, HashMap};
+
+/// Register recorded into when recording is toggled by key
+pub const DEFAULT_MACRO_REGISTER: char = 'q';
+
+/// Prefix of a step holding typed text
+const TEXT_STEP_PREFIX: &str = "text:";
+
+/// Actions a macro can hold besides typed chars, by keymap name. Actions that
+/// leave the editor, like opening the command line, a prompt or another
+/// split, are left out since a replay could not follow them
+const MACRO_ACTIONS: &[&str] = &[
+    "save",
+    "copy",
+    "paste",
+    "cut",
+    "undo",
+    "redo",
+    "older_state",
+    "newer_state",
+    "backspace",
+    "delete",
+    "enter",
+    "tab",
+    "outdent",
+    "move_up",
+    "move_down",
+    "move_left",
+    "move_right",
+    "move_word_left",
+    "move_word_right",
+    "move_line_start",
+    "move_line_end",
+    "move_document_start",
+    "move_document_end",
+    "move_page_up",
+    "move_page_down",
+    "select_up",
+    "select_down",
+    "select_left",
+    "select_right",
+    "select_word_left",
+    "select_word_right",
+    "select_line_start",
+    "select_line_end",
+    "select_document_start",
+    "select_document_end",
+    "select_page_up",
+    "select_page_down",
+    "block_select_up",
+    "block_select_down",
+    "block_select_left",
+    "block_select_right",
+    "add_cursor_above",
+    "add_cursor_below",
+    "add_cursor_next_match",
+    "jump_to_matching_bracket",
+    "duplicate_line",
+    "move_line_up",
+    "move_line_down",
+    "delete_line",
+    "join_lines",
+    "toggle_comment",
+    "jump_back",
+    "jump_forward",
+];
+
+/// Recorded macros by register, and the recording in progress
+#[derive(Debug, Default, Clone)]
+pub struct KeyboardMacros {
+    registers: BTreeMap<char, Vec<InputAction>>,
+    recording: Option<(char, Vec<InputAction>)>,
+    last_used: Option<char>,
+    replaying: bool,
+}
+
+impl KeyboardMacros {
+    /// Loads the macros stored in the config, skipping those that fail to parse
+    pub fn from_config(stored: &HashMap<String, Vec<String>>) -> Self {
+        let mut macros = Self::default();
+        for (name, steps) in stored {
+            let Some(register) = register_name(name) else {
+                log_warn!("[MACROS] Skipping macro with invalid name '{}'", name);
+                continue;
+            };
+            match actions_from_steps(steps) {
+                Ok(actions) => {
+                    macros.registers.insert(register, actions);
+                }
+                Err(e) => log_warn!("[MACROS] Skipping macro '{}': {}", name, e),
+            }
+        }
+        macros
+    }
+
+    /// Starts recording into register, replacing what it held once stopped
+    pub fn start_recording(&mut self, register: char) {
+        self.recording = Some((register, Vec::new()));
+    }
+
+    /// Stops recording and stores the macro, returns the register recorded into
+    pub fn stop_recording(&mut self) -> Option<char> {
+        let (register, actions) = self.recording.take()?;
+        self.registers.insert(register, actions);
+        self.last_used = Some(register);
+        Some(register)
+    }
+
+    /// Register being recorded into, if recording
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|(register, _)| *register)
+    }
+
+    /// Adds action to the recording, actions a macro can't hold and those
+    /// coming from a replay are ignored
+    pub fn record(&mut self, action: &InputAction) {
+        if self.replaying || !is_recordable(action) {
+            return;
+        }
+        if let Some((_, actions)) = self.recording.as_mut() {
+            actions.push(action.clone());
+        }
+    }
+
+    pub fn get(&self, register: char) -> Option<&[InputAction]> {
+        self.registers.get(&register).map(Vec::as_slice)
+    }
+
+    /// Macros sorted by register
+    pub fn iter(&self) -> impl Iterator<Item = (char, &[InputAction])> {
+        self.registers
+            .iter()
+            .map(|(register, actions)| (*register, actions.as_slice()))
+    }
+
+    pub fn remove(&mut self, register: char) -> Option<Vec<InputAction>> {
+        self.registers.remove(&register)
+    }
+
+    /// Register recorded or played last, replayed by the play_macro key
+    pub fn last_used(&self) -> Option<char> {
+        self.last_used
+    }
+
+    /// Marks a replay of register as running or done, actions are not
+    /// recorded while it runs
+    pub(crate) fn set_replaying(&mut self, register: char, replaying: bool) {
+        self.replaying = replaying;
+        self.last_used = Some(register);
+    }
+}
+
+/// Registers are named by a single letter or digit
+pub fn register_name(name: &str) -> Option<char> {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphanumeric() => Some(c),
+        _ => None,
+    }
+}
+
+/// Whether a macro can hold action
+pub fn is_recordable(action: &InputAction) -> bool {
+    matches!(action, InputAction::Editor(EditorAction::WriteChar(_)))
+        || action_name(action).is_some()
+}
+
+fn action_name(action: &InputAction) -> Option<&'static str> {
+    MACRO_ACTIONS
+        .iter()
+        .copied()
+        .find(|name| KeymapConfig::parse_editor_action(name).is_ok_and(|parsed| &parsed == action))
+}
+
+/// Writes actions as steps for the config file, chars typed in a row become
+/// one text step
+pub fn actions_to_steps(actions: &[InputAction]) -> Vec<String> {
+    let mut steps: Vec<String> = Vec::new();
+    let mut text = String::new();
+    for action in actions {
+        if let InputAction::Editor(EditorAction::WriteChar(c)) = action {
+            text.push(*c);
+            continue;
+        }
+        if !text.is_empty() {
+            steps.push(format!("{TEXT_STEP_PREFIX}{}", std::mem::take(&mut text)));
+        }
+        if let Some(name) = action_name(action) {
+            steps.push(name.to_string());
+        }
+    }
+    if !text.is_empty() {
+        steps.push(format!("{TEXT_STEP_PREFIX}{text}"));
+    }
+    steps
+}
+
+/// Reads steps written by actions_to_steps back into actions
+pub fn actions_from_steps(steps: &[String]) -> Result<Vec<InputAction>, ConfigError> {
+    let mut actions = Vec::new();
+    for step in steps {
+        if let Some(text) = step.strip_prefix(TEXT_STEP_PREFIX) {
+            actions.extend(
+                text.chars()
+                    .map(|c| InputAction::Editor(EditorAction::WriteChar(c))),
+            );
+        } else if MACRO_ACTIONS.contains(&step.to_lowercase().as_str()) {
+            actions.push(KeymapConfig::parse_editor_action(step)?);
+        } else {
+            return Err(ConfigError::InvalidValue {
+                field: "macros".to_string(),
+                reason: format!("unknown step '{}'", step),
+            });
+        }
+    }
+    Ok(actions)
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_keyboard_macros_tests {
+    use super::*;
+    use crate::input::actions::Motion;
+
+    fn write(c: char) -> InputAction {
+        InputAction::Editor(EditorAction::WriteChar(c))
+    }
+
+    #[test]
+    fn test_recording_skips_actions_leaving_the_editor() {
+        let mut macros = KeyboardMacros::default();
+        macros.record(&write('x'));
+        macros.start_recording('a');
+        macros.record(&write('x'));
+        macros.record(&InputAction::ToggleActiveArea);
+        macros.record(&InputAction::Editor(EditorAction::GotoLine));
+        macros.record(&InputAction::Editor(EditorAction::MoveCursor(Motion::Down)));
+        assert_eq!(macros.stop_recording(), Some('a'));
+
+        assert_eq!(
+            macros.get('a'),
+            Some(
+                &[
+                    write('x'),
+                    InputAction::Editor(EditorAction::MoveCursor(Motion::Down))
+                ][..]
+            )
+        );
+        assert_eq!(macros.last_used(), Some('a'));
+        assert_eq!(macros.recording(), None);
+    }
+
+    #[test]
+    fn test_steps_round_trip() {
+        let actions = vec![
+            InputAction::Editor(EditorAction::MoveCursor(Motion::LineStart)),
+            write('/'),
+            write('/'),
+            write(' '),
+            InputAction::Editor(EditorAction::MoveCursor(Motion::Down)),
+            InputAction::ENTER,
+        ];
+        let steps = actions_to_steps(&actions);
+        assert_eq!(
+            steps,
+            vec!["move_line_start", "text:// ", "move_down", "enter"]
+        );
+        assert_eq!(actions_from_steps(&steps).unwrap(), actions);
+
+        assert!(actions_from_steps(&["goto_line".to_string()]).is_err());
+        assert!(actions_from_steps(&["nope".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_stored_macros_are_skipped() {
+        let mut stored = HashMap::new();
+        stored.insert("a".to_string(), vec!["text:hi".to_string()]);
+        stored.insert("bad".to_string(), vec!["text:hi".to_string()]);
+        stored.insert("b".to_string(), vec!["fly".to_string()]);
+        let macros = KeyboardMacros::from_config(&stored);
+        assert_eq!(
+            macros
+                .iter()
+                .map(|(register, _)| register)
+                .collect::<Vec<_>>(),
+            vec!['a']
+        );
+    }
+}
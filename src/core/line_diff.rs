@@ -0,0 +1,159 @@
+//! Line by line differences between two versions of a text, as previewed
+//! before :earlier and :later change it.
+
+/// A line of a diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+impl DiffLine {
+    pub fn is_change(&self) -> bool {
+        !matches!(self, DiffLine::Same(_))
+    }
+}
+
+/// Lines compared against each other, past which a changed region is shown
+/// removed and added whole instead of aligned line by line
+const MAX_COMPARED: usize = 4_000_000;
+
+/// Lines turning old into new, unchanged ones included, in text order
+pub fn diff_lines(old: &[String], new: &[String]) -> Vec<DiffLine> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let mut diff: Vec<DiffLine> = old[..prefix].iter().cloned().map(DiffLine::Same).collect();
+    if a.len().saturating_mul(b.len()) > MAX_COMPARED {
+        diff.extend(a.iter().cloned().map(DiffLine::Removed));
+        diff.extend(b.iter().cloned().map(DiffLine::Added));
+    } else {
+        diff.extend(aligned(a, b));
+    }
+    diff.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .cloned()
+            .map(DiffLine::Same),
+    );
+    diff
+}
+
+/// Diff of a and b keeping their longest common subsequence of lines
+fn aligned(a: &[String], b: &[String]) -> Vec<DiffLine> {
+    // common[i][j] is the longest common subsequence of a[i..] and b[j..]
+    let width = b.len() + 1;
+    let mut common = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            common[i * width + j] = if a[i] == b[j] {
+                common[(i + 1) * width + j + 1] + 1
+            } else {
+                common[(i + 1) * width + j].max(common[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            diff.push(DiffLine::Same(a[i].clone()));
+            i += 1;
+            j += 1;
+        } else if common[(i + 1) * width + j] >= common[i * width + j + 1] {
+            diff.push(DiffLine::Removed(a[i].clone()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(b[j].clone()));
+            j += 1;
+        }
+    }
+    diff.extend(a[i..].iter().cloned().map(DiffLine::Removed));
+    diff.extend(b[j..].iter().cloned().map(DiffLine::Added));
+    diff
+}
+
+/// The changes of diff with up to context unchanged lines around each.
+/// None stands for unchanged lines left out between them
+pub fn with_context(diff: &[DiffLine], context: usize) -> Vec<Option<&DiffLine>> {
+    let near_change = |idx: usize| {
+        let from = idx.saturating_sub(context);
+        let to = (idx + context + 1).min(diff.len());
+        diff[from..to].iter().any(DiffLine::is_change)
+    };
+
+    let mut rows = Vec::new();
+    for (idx, line) in diff.iter().enumerate() {
+        if near_change(idx) {
+            rows.push(Some(line));
+        } else if rows.last().is_some_and(Option::is_some) {
+            rows.push(None);
+        }
+    }
+    if rows.last() == Some(&None) {
+        rows.pop();
+    }
+    rows
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_line_diff_tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.split(' ').map(String::from).collect()
+    }
+
+    #[test]
+    fn test_diff_keeps_common_lines() {
+        let diff = diff_lines(&lines("a b c d"), &lines("a x c d e"));
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("a".into()),
+                DiffLine::Removed("b".into()),
+                DiffLine::Added("x".into()),
+                DiffLine::Same("c".into()),
+                DiffLine::Same("d".into()),
+                DiffLine::Added("e".into()),
+            ]
+        );
+        assert!(diff_lines(&lines("a b"), &lines("a b"))
+            .iter()
+            .all(|line| !line.is_change()));
+    }
+
+    #[test]
+    fn test_context_leaves_out_far_unchanged_lines() {
+        let diff = diff_lines(&lines("1 2 3 4 5 6 7 8"), &lines("1 2 3 4 5 6 7 X"));
+        let rows = with_context(&diff, 1);
+        assert_eq!(
+            rows,
+            vec![
+                Some(&DiffLine::Same("7".into())),
+                Some(&DiffLine::Removed("8".into())),
+                Some(&DiffLine::Added("X".into())),
+            ]
+        );
+
+        let diff = diff_lines(&lines("X 2 3 4 5 6 7 8"), &lines("1 2 3 4 5 6 7 8"));
+        assert_eq!(with_context(&diff, 1).len(), 3);
+    }
+}
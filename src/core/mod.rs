@@ -1,7 +1,12 @@
 pub mod app;
+pub mod buffers;
 pub mod clipboard;
+pub mod clipboard_provider;
 pub mod command_line;
 pub mod cursor;
 pub mod debug;
 pub mod editor;
 pub mod help_registry;
+pub mod keyboard_macros;
+pub mod line_diff;
+pub mod splits;
@@ -0,0 +1,135 @@
+use super::split_layout::{SplitDirection, View, ViewId};
+use crate::core::app::App;
+use crate::core::cursor::CursorPosition;
+use crate::core::editor::Editor;
+use crate::errors::error::AppError;
+use crate::input::actions::{Direction, SplitAction};
+use std::path::PathBuf;
+
+/// Percent a view grows or shrinks by per resize action
+const RESIZE_STEP: i16 = 5;
+
+impl App {
+    /// Dispatches split related input actions
+    pub(crate) fn handle_split_action(&mut self, action: SplitAction) {
+        match action {
+            SplitAction::SplitHorizontal => self.split_view(SplitDirection::Horizontal),
+            SplitAction::SplitVertical => self.split_view(SplitDirection::Vertical),
+            SplitAction::Focus(direction) => self.focus_view_in_direction(direction),
+            SplitAction::FocusNext => self.focus_view(self.splits.next_view()),
+            SplitAction::Grow => self.resize_view(RESIZE_STEP),
+            SplitAction::Shrink => self.resize_view(-RESIZE_STEP),
+            SplitAction::Close => {
+                if !self.close_view() {
+                    log_info!("Cannot close the last view");
+                }
+            }
+        }
+    }
+
+    /// Splits the focused view, the new view shows the same buffer and gets focus
+    pub fn split_view(&mut self, direction: SplitDirection) {
+        self.store_focused_view();
+        self.splits.split(direction);
+        // new view is a copy of the focused one, so the editor already shows it
+        self.editor.reset_text_selection_cursor();
+    }
+
+    /// Splits the focused view and opens file in the new view
+    pub fn split_view_with_file(
+        &mut self,
+        direction: SplitDirection,
+        path: PathBuf,
+    ) -> Result<(), AppError> {
+        self.split_view(direction);
+        self.open_buffer(path)
+    }
+
+    /// Closes the focused view, false if it is the last one
+    pub fn close_view(&mut self) -> bool {
+        self.store_focused_view();
+        if self.splits.close().is_none() {
+            return false;
+        }
+        self.load_focused_view();
+        true
+    }
+
+    /// Closes all views except the focused one
+    pub fn close_other_views(&mut self) {
+        self.splits.close_others();
+    }
+
+    /// Moves focus to view, showing its buffer, cursor and scroll in the editor
+    pub fn focus_view(&mut self, id: ViewId) {
+        if id == self.splits.focused() || self.splits.view(id).is_none() {
+            return;
+        }
+        self.store_focused_view();
+        self.splits.focus(id);
+        self.load_focused_view();
+    }
+
+    /// Moves focus to the view next to the focused one, if there is one
+    pub fn focus_view_in_direction(&mut self, direction: Direction) {
+        if let Some(id) = self.splits.neighbour(&direction) {
+            self.focus_view(id);
+        }
+    }
+
+    /// Grows or shrinks the focused view by delta percent
+    pub fn resize_view(&mut self, delta: i16) {
+        self.splits.resize(delta);
+    }
+
+    /// Editor holding the content a view shows, for views on the active
+    /// buffer this is the app editor
+    pub fn view_editor(&self, id: ViewId) -> Option<&Editor> {
+        let view = self.splits.view(id)?;
+        if id == self.splits.focused() || view.buffer == self.buffers.active_index() {
+            Some(&self.editor)
+        } else {
+            self.buffers.get(view.buffer).map(|buffer| &buffer.editor)
+        }
+    }
+
+    /// Buffer index shown by a view
+    pub fn view_buffer_index(&self, id: ViewId) -> Option<usize> {
+        if id == self.splits.focused() {
+            return Some(self.buffers.active_index());
+        }
+        self.splits.view(id).map(|view| view.buffer)
+    }
+
+    /// Saves editor cursor, scroll and active buffer into the focused view
+    fn store_focused_view(&mut self) {
+        let buffer = self.buffers.active_index();
+        let cursor = CursorPosition {
+            x: self.editor.cursor.x.max(0) as usize,
+            y: self.editor.cursor.y.max(0) as usize,
+        };
+        let scroll_offset = self.editor.scroll_offset;
+        if let Some(view) = self.splits.view_mut(self.splits.focused()) {
+            *view = View {
+                buffer,
+                cursor,
+                scroll_offset,
+            };
+        }
+    }
+
+    /// Shows the focused view's buffer in the editor, with its cursor and scroll
+    fn load_focused_view(&mut self) {
+        let Some(view) = self.splits.view(self.splits.focused()).copied() else {
+            return;
+        };
+        if view.buffer != self.buffers.active_index() {
+            self.switch_to_buffer(view.buffer);
+        }
+        self.editor.reset_text_selection_cursor();
+        self.editor.clear_secondary_cursors();
+        // other views may have changed the buffer, so cursor gets clamped to content
+        self.editor.set_cursor_position(&view.cursor);
+        self.editor.set_scroll_offset(view.scroll_offset);
+    }
+}
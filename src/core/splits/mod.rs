@@ -0,0 +1,4 @@
+mod app_extensions;
+mod split_layout;
+
+pub use split_layout::{SplitDirection, SplitLayout, View, ViewId};
@@ -0,0 +1,445 @@
+use crate::core::cursor::CursorPosition;
+use crate::input::actions::Direction;
+use ratatui::layout::{Constraint, Direction as LayoutDirection, Layout, Rect};
+use std::collections::BTreeMap;
+
+pub type ViewId = usize;
+
+/// Smallest and largest share in percent a view can be resized to
+const MIN_RATIO: u16 = 10;
+const MAX_RATIO: u16 = 90;
+
+/// How a split divides its area, named like in vim:
+/// horizontal stacks views on top of each other, vertical puts them side by side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitDirection {
+    Horizontal,
+    Vertical,
+}
+
+/// A window into a buffer with its own cursor and scroll position.
+///
+/// The focused view is live in the app editor, so its entry is only
+/// up to date after focus moves away from it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct View {
+    pub buffer: usize,
+    pub cursor: CursorPosition,
+    pub scroll_offset: i32,
+}
+
+#[derive(Debug, Clone)]
+enum SplitNode {
+    View(ViewId),
+    Split {
+        direction: SplitDirection,
+        ratio: u16, // percent of the area given to first
+        first: Box<SplitNode>,
+        second: Box<SplitNode>,
+    },
+}
+
+impl SplitNode {
+    /// Path of child choices (true = first) from this node to the view
+    fn path_to(&self, id: ViewId, path: &mut Vec<bool>) -> bool {
+        match self {
+            SplitNode::View(view_id) => *view_id == id,
+            SplitNode::Split { first, second, .. } => {
+                for (is_first, child) in [(true, first), (false, second)] {
+                    path.push(is_first);
+                    if child.path_to(id, path) {
+                        return true;
+                    }
+                    path.pop();
+                }
+                false
+            }
+        }
+    }
+
+    fn at(&self, path: &[bool]) -> &SplitNode {
+        match (self, path.split_first()) {
+            (SplitNode::Split { first, second, .. }, Some((is_first, rest))) => {
+                if *is_first {
+                    first.at(rest)
+                } else {
+                    second.at(rest)
+                }
+            }
+            _ => self,
+        }
+    }
+
+    fn at_mut(&mut self, path: &[bool]) -> &mut SplitNode {
+        match (self, path.split_first()) {
+            (SplitNode::Split { first, second, .. }, Some((is_first, rest))) => {
+                if *is_first {
+                    first.at_mut(rest)
+                } else {
+                    second.at_mut(rest)
+                }
+            }
+            (node, _) => node,
+        }
+    }
+
+    fn first_view(&self) -> ViewId {
+        match self {
+            SplitNode::View(id) => *id,
+            SplitNode::Split { first, .. } => first.first_view(),
+        }
+    }
+
+    fn last_view(&self) -> ViewId {
+        match self {
+            SplitNode::View(id) => *id,
+            SplitNode::Split { second, .. } => second.last_view(),
+        }
+    }
+
+    fn collect_views(&self, ids: &mut Vec<ViewId>) {
+        match self {
+            SplitNode::View(id) => ids.push(*id),
+            SplitNode::Split { first, second, .. } => {
+                first.collect_views(ids);
+                second.collect_views(ids);
+            }
+        }
+    }
+
+    fn collect_areas(&self, area: Rect, areas: &mut Vec<(ViewId, Rect)>) {
+        match self {
+            SplitNode::View(id) => areas.push((*id, area)),
+            SplitNode::Split {
+                direction,
+                ratio,
+                first,
+                second,
+            } => {
+                let layout_direction = match direction {
+                    SplitDirection::Horizontal => LayoutDirection::Vertical,
+                    SplitDirection::Vertical => LayoutDirection::Horizontal,
+                };
+                let parts = Layout::default()
+                    .direction(layout_direction)
+                    .constraints([
+                        Constraint::Percentage(*ratio),
+                        Constraint::Percentage(100 - *ratio),
+                    ])
+                    .split(area);
+                first.collect_areas(parts[0], areas);
+                second.collect_areas(parts[1], areas);
+            }
+        }
+    }
+}
+
+/// Tree of split views filling the editor area, one of which has focus
+#[derive(Debug)]
+pub struct SplitLayout {
+    root: SplitNode,
+    views: BTreeMap<ViewId, View>,
+    focused: ViewId,
+    next_id: ViewId,
+}
+
+impl SplitLayout {
+    /// Layout with a single view on the first buffer
+    pub fn new() -> Self {
+        Self {
+            root: SplitNode::View(0),
+            views: BTreeMap::from([(0, View::default())]),
+            focused: 0,
+            next_id: 1,
+        }
+    }
+
+    /// Number of views, never 0
+    pub fn count(&self) -> usize {
+        self.views.len()
+    }
+
+    pub fn focused(&self) -> ViewId {
+        self.focused
+    }
+
+    pub fn view(&self, id: ViewId) -> Option<&View> {
+        self.views.get(&id)
+    }
+
+    pub fn view_mut(&mut self, id: ViewId) -> Option<&mut View> {
+        self.views.get_mut(&id)
+    }
+
+    /// View ids in layout order, left to right and top to bottom
+    pub fn view_ids(&self) -> Vec<ViewId> {
+        let mut ids = Vec::new();
+        self.root.collect_views(&mut ids);
+        ids
+    }
+
+    /// Gives focus to view, false if there is no such view
+    pub fn focus(&mut self, id: ViewId) -> bool {
+        if self.views.contains_key(&id) {
+            self.focused = id;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Splits the focused view in two, the new view starts as a copy of it
+    /// and takes focus. Returns the id of the new view
+    pub fn split(&mut self, direction: SplitDirection) -> ViewId {
+        let id = self.next_id;
+        self.next_id += 1;
+        let view = self.views.get(&self.focused).copied().unwrap_or_default();
+        self.views.insert(id, view);
+
+        let mut path = Vec::new();
+        self.root.path_to(self.focused, &mut path);
+        let node = self.root.at_mut(&path);
+        *node = SplitNode::Split {
+            direction,
+            ratio: 50,
+            first: Box::new(SplitNode::View(self.focused)),
+            second: Box::new(SplitNode::View(id)),
+        };
+
+        self.focused = id;
+        id
+    }
+
+    /// Closes the focused view, its sibling takes over the space and focus.
+    /// Returns the newly focused view, None if it was the last view
+    pub fn close(&mut self) -> Option<ViewId> {
+        let mut path = Vec::new();
+        self.root.path_to(self.focused, &mut path);
+        let was_first = path.pop()?;
+
+        let parent = self.root.at_mut(&path);
+        let sibling = match std::mem::replace(parent, SplitNode::View(self.focused)) {
+            SplitNode::Split { first, second, .. } => {
+                if was_first {
+                    *second
+                } else {
+                    *first
+                }
+            }
+            node => node,
+        };
+        // focus the view that was closest to the closed one
+        let next = if was_first {
+            sibling.first_view()
+        } else {
+            sibling.last_view()
+        };
+        *parent = sibling;
+
+        self.views.remove(&self.focused);
+        self.focused = next;
+        Some(next)
+    }
+
+    /// Closes every view except the focused one
+    pub fn close_others(&mut self) {
+        self.root = SplitNode::View(self.focused);
+        self.views.retain(|id, _| *id == self.focused);
+    }
+
+    /// Next view in layout order, wrapping around
+    pub fn next_view(&self) -> ViewId {
+        let ids = self.view_ids();
+        let pos = ids.iter().position(|id| *id == self.focused).unwrap_or(0);
+        ids[(pos + 1) % ids.len()]
+    }
+
+    /// View next to the focused one in given direction, if any
+    pub fn neighbour(&self, direction: &Direction) -> Option<ViewId> {
+        let (axis, forward) = match direction {
+            Direction::Left => (SplitDirection::Vertical, false),
+            Direction::Right => (SplitDirection::Vertical, true),
+            Direction::Up => (SplitDirection::Horizontal, false),
+            Direction::Down => (SplitDirection::Horizontal, true),
+        };
+
+        let mut path = Vec::new();
+        self.root.path_to(self.focused, &mut path);
+        // walk up to the nearest split along the axis that has room in that direction
+        while let Some(was_first) = path.pop() {
+            if let SplitNode::Split {
+                direction,
+                first,
+                second,
+                ..
+            } = self.root.at(&path)
+            {
+                if *direction == axis && was_first == forward {
+                    return Some(if forward {
+                        second.first_view()
+                    } else {
+                        first.last_view()
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Grows (or shrinks for negative delta) the focused view's share of the
+    /// split it is in, in percent. Returns false if there is nothing to resize
+    pub fn resize(&mut self, delta: i16) -> bool {
+        let mut path = Vec::new();
+        self.root.path_to(self.focused, &mut path);
+        let Some(was_first) = path.pop() else {
+            return false;
+        };
+
+        if let SplitNode::Split { ratio, .. } = self.root.at_mut(&path) {
+            let delta = if was_first { delta } else { -delta };
+            *ratio = (*ratio as i16 + delta).clamp(MIN_RATIO as i16, MAX_RATIO as i16) as u16;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Areas of all views within given editor area, in layout order
+    pub fn areas(&self, area: Rect) -> Vec<(ViewId, Rect)> {
+        let mut areas = Vec::new();
+        self.root.collect_areas(area, &mut areas);
+        areas
+    }
+
+    /// Keeps views pointing at the right buffers after one is removed from the
+    /// buffer list, views that showed it switch to `replacement`
+    pub fn buffer_removed(&mut self, removed: usize, replacement: usize) {
+        for view in self.views.values_mut() {
+            if view.buffer == removed {
+                view.buffer = replacement;
+                view.cursor = CursorPosition::default();
+                view.scroll_offset = 0;
+            } else if view.buffer > removed {
+                view.buffer -= 1;
+            }
+        }
+    }
+}
+
+impl Default for SplitLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_split_layout_tests {
+    use super::*;
+
+    #[test]
+    fn test_new_layout_has_single_view() {
+        let layout = SplitLayout::new();
+        assert_eq!(layout.count(), 1);
+        assert_eq!(layout.view_ids(), vec![0]);
+        assert!(layout.neighbour(&Direction::Right).is_none());
+    }
+
+    #[test]
+    fn test_split_focuses_new_view_with_copied_state() {
+        let mut layout = SplitLayout::new();
+        layout.view_mut(0).unwrap().scroll_offset = 7;
+
+        let id = layout.split(SplitDirection::Vertical);
+
+        assert_eq!(layout.focused(), id);
+        assert_eq!(layout.count(), 2);
+        assert_eq!(layout.view(id).unwrap().scroll_offset, 7);
+        assert_eq!(layout.view_ids(), vec![0, id]);
+    }
+
+    #[test]
+    fn test_areas_follow_split_direction() {
+        let mut layout = SplitLayout::new();
+        layout.split(SplitDirection::Vertical);
+        let area = Rect::new(0, 0, 80, 20);
+
+        let areas = layout.areas(area);
+        assert_eq!(areas[0].1, Rect::new(0, 0, 40, 20));
+        assert_eq!(areas[1].1, Rect::new(40, 0, 40, 20));
+
+        layout.split(SplitDirection::Horizontal);
+        let areas = layout.areas(area);
+        assert_eq!(areas.len(), 3);
+        assert_eq!(areas[1].1, Rect::new(40, 0, 40, 10));
+        assert_eq!(areas[2].1, Rect::new(40, 10, 40, 10));
+    }
+
+    #[test]
+    fn test_neighbour_moves_across_nested_splits() {
+        let mut layout = SplitLayout::new();
+        let right = layout.split(SplitDirection::Vertical);
+        let bottom_right = layout.split(SplitDirection::Horizontal);
+
+        assert_eq!(layout.neighbour(&Direction::Left), Some(0));
+        assert_eq!(layout.neighbour(&Direction::Up), Some(right));
+        assert_eq!(layout.neighbour(&Direction::Down), None);
+
+        layout.focus(0);
+        assert_eq!(layout.neighbour(&Direction::Right), Some(right));
+        assert_eq!(layout.neighbour(&Direction::Left), None);
+        layout.focus(right);
+        assert_eq!(layout.neighbour(&Direction::Down), Some(bottom_right));
+    }
+
+    #[test]
+    fn test_close_gives_space_and_focus_to_sibling() {
+        let mut layout = SplitLayout::new();
+        let right = layout.split(SplitDirection::Vertical);
+        layout.split(SplitDirection::Horizontal);
+
+        assert_eq!(layout.close(), Some(right));
+        assert_eq!(layout.view_ids(), vec![0, right]);
+        assert_eq!(layout.close(), Some(0));
+        assert_eq!(layout.count(), 1);
+        assert_eq!(layout.close(), None);
+    }
+
+    #[test]
+    fn test_resize_is_clamped_and_relative_to_focused_view() {
+        let mut layout = SplitLayout::new();
+        layout.split(SplitDirection::Vertical);
+        let area = Rect::new(0, 0, 100, 10);
+
+        // focused view is the second one, growing it shrinks the first
+        assert!(layout.resize(20));
+        assert_eq!(layout.areas(area)[1].1.width, 70);
+
+        assert!(layout.resize(100));
+        assert_eq!(layout.areas(area)[1].1.width, 90);
+
+        layout.close_others();
+        assert!(!layout.resize(5));
+    }
+
+    #[test]
+    fn test_buffer_removed_shifts_view_buffers() {
+        let mut layout = SplitLayout::new();
+        let a = layout.split(SplitDirection::Vertical);
+        let b = layout.split(SplitDirection::Vertical);
+        layout.view_mut(0).unwrap().buffer = 0;
+        layout.view_mut(a).unwrap().buffer = 1;
+        layout.view_mut(b).unwrap().buffer = 2;
+
+        layout.buffer_removed(1, 0);
+
+        assert_eq!(layout.view(0).unwrap().buffer, 0);
+        assert_eq!(layout.view(a).unwrap().buffer, 0);
+        assert_eq!(layout.view(b).unwrap().buffer, 1);
+    }
+}
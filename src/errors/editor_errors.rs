@@ -32,6 +32,8 @@ pub enum TextSelectionError {
 pub enum ClipboardError {
     #[error("No text currently copied")]
     NoCopiedText,
+    #[error("Clipboard provider failed: {0}")]
+    ProviderFailed(String),
 }
 
 #[derive(Debug, Error)]
@@ -40,6 +42,10 @@ pub enum UndoError {
     NoActionToUndo,
     #[error("failed to undo action")]
     FailedToUndo,
+    #[error("No state {0} in the undo history")]
+    NoSuchState(usize),
+    #[error("Undo file: {0}")]
+    UndoFile(String),
 }
 
 #[derive(Debug, Error)]
@@ -13,30 +13,76 @@ pub enum InputAction {
     CommandLine(CommandLineAction),
     Popup(PopupAction),
     Debug(DebugAction),
+    Split(SplitAction),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EditorAction {
     // Movement
-    MoveCursor(Direction),
+    MoveCursor(Motion),
 
     // Selection
-    MoveSelectionCursor(Direction),
+    MoveSelectionCursor(Motion),
+    MoveBlockSelection(Direction),
+
+    // Multiple cursors
+    AddCursorAbove,
+    AddCursorBelow,
+    AddCursorAtNextMatch,
+
+    // Brackets
+    JumpToMatchingBracket,
+
+    // Navigation
+    GotoLine, // handled by the app, which prompts for the line
+    JumpBack,
+    JumpForward,
+
+    // Macros, handled by the app which replays them
+    ToggleMacroRecording,
+    PlayMacro,
+
+    // Whole lines
+    DuplicateLines,
+    MoveLinesUp,
+    MoveLinesDown,
+    DeleteLines,
+    JoinLines,
+    ToggleComment, // handled by the app, which knows the file type
 
     // Editing
     COPY,
     CUT,
     PASTE,
+    CopyToRegister(char),
+    PasteFromRegister(char),
+    ClipboardHistory, // handled by the app, which opens the history picker
     UNDO,
     REDO,
+    OlderState, // undo tree states in the order they were made, across branches
+    NewerState,
+    UndoTree, // handled by the app, which opens the undo tree
     WriteChar(char),
     BACKSPACE,
     DELETE,
+    Outdent,
 
     // Misc
     SAVE,
 }
 
+/// Actions on split views, handled by the app since they span buffers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SplitAction {
+    SplitHorizontal,
+    SplitVertical,
+    Focus(Direction),
+    FocusNext,
+    Grow,
+    Shrink,
+    Close,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CommandLineAction {
     WriteChar(char),
@@ -80,9 +126,9 @@ pub enum Direction {
     Right,
 }
 
-///convert direction to (x,y) vector with i16 values
+///convert direction to (x,y) vector with i32 values
 impl Direction {
-    pub fn to_vector(self) -> (i16, i16) {
+    pub fn to_vector(self) -> (i32, i32) {
         match self {
             Direction::Up => (0, -1),
             Direction::Down => (0, 1),
@@ -91,3 +137,44 @@ impl Direction {
         }
     }
 }
+
+/// Where a cursor movement in the editor goes, single steps or larger jumps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    Up,
+    Down,
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    LineStart,
+    LineEnd,
+    DocumentStart,
+    DocumentEnd,
+    PageUp,
+    PageDown,
+}
+
+impl Motion {
+    /// (x,y) vector of a single step, (0,0) for the larger motions
+    pub fn to_vector(self) -> (i32, i32) {
+        match self {
+            Motion::Up => (0, -1),
+            Motion::Down => (0, 1),
+            Motion::Left => (-1, 0),
+            Motion::Right => (1, 0),
+            _ => (0, 0),
+        }
+    }
+}
+
+impl From<Direction> for Motion {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => Motion::Up,
+            Direction::Down => Motion::Down,
+            Direction::Left => Motion::Left,
+            Direction::Right => Motion::Right,
+        }
+    }
+}
@@ -119,10 +119,16 @@ fn on_key_event(app: &mut App, key: KeyEvent) {
                 KeyCode::Char(c) => {
                     // Ignore if only CONTROL is pressed (e.g., Ctrl+S),
                     // but ALLOW if AltGr (Ctrl+Alt) is pressed.
-                    if key.modifiers.intersects(KeyModifiers::CONTROL)
-                        && !key.modifiers.intersects(KeyModifiers::ALT)
-                    {
+                    let ctrl = key.modifiers.intersects(KeyModifiers::CONTROL);
+                    let alt = key.modifiers.intersects(KeyModifiers::ALT);
+                    if ctrl && !alt {
                         keymaps.get_editor_action(key.modifiers, key.code).cloned()
+                    } else if alt && !ctrl {
+                        // Alt+char runs its binding (e.g., Alt+u), unbound ones are still typed
+                        keymaps
+                            .get_editor_action(key.modifiers, key.code)
+                            .cloned()
+                            .or(Some(InputAction::Editor(EditorAction::WriteChar(c))))
                     } else {
                         // write char
                         Some(InputAction::Editor(EditorAction::WriteChar(c)))
@@ -165,7 +171,7 @@ fn on_key_event(app: &mut App, key: KeyEvent) {
             (KeyModifiers::SHIFT, KeyCode::Char('/')) => {
                 Some(InputAction::Popup(PopupAction::ToggleSearch))
             }
-            (KeyModifiers::NONE, KeyCode::Char(c)) => {
+            (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c)) => {
                 Some(InputAction::Popup(PopupAction::WriteChar(c)))
             }
             (KeyModifiers::NONE, KeyCode::Backspace) => {
@@ -198,3 +204,65 @@ fn on_scroll_events(app: &mut App, mouse_event: MouseEvent) {
         _ => {}
     }
 }
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_input_tests {
+    use super::*;
+    use crate::app_config::AppLaunchConfig;
+    use crate::config::Config;
+    use crate::core::cursor::CursorPosition;
+
+    fn create_app_with_lines(lines: Vec<&str>) -> App {
+        let mut app = App::new(Config::default(), AppLaunchConfig::default());
+        app.editor.editor_content = lines.into_iter().map(String::from).collect();
+        app
+    }
+
+    fn press(app: &mut App, modifiers: KeyModifiers, c: char) {
+        on_key_event(app, KeyEvent::new(KeyCode::Char(c), modifiers));
+    }
+
+    #[test]
+    fn test_alt_v_opens_clipboard_history() {
+        let mut app = create_app_with_lines(vec!["one"]);
+        app.editor.text_selection_start = Some(CursorPosition { x: 0, y: 0 });
+        app.editor.text_selection_end = Some(CursorPosition { x: 3, y: 0 });
+        app.process_input_action(InputAction::Editor(EditorAction::COPY));
+
+        press(&mut app, KeyModifiers::ALT, 'v');
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        assert_eq!(app.editor.editor_content, vec!["one"]);
+    }
+
+    #[test]
+    fn test_alt_u_opens_undo_tree_and_alt_z_y_walk_states() {
+        let mut app = create_app_with_lines(vec![""]);
+        press(&mut app, KeyModifiers::NONE, 'a');
+        press(&mut app, KeyModifiers::NONE, 'b');
+
+        // typing ab is a single undo state
+        press(&mut app, KeyModifiers::ALT, 'z');
+        assert_eq!(app.editor.editor_content, vec![""]);
+        press(&mut app, KeyModifiers::ALT, 'y');
+        assert_eq!(app.editor.editor_content, vec!["ab"]);
+
+        press(&mut app, KeyModifiers::ALT, 'u');
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        assert_eq!(app.editor.editor_content, vec!["ab"]);
+    }
+
+    #[test]
+    fn test_unbound_alt_and_altgr_chars_are_typed() {
+        let mut app = create_app_with_lines(vec![""]);
+        press(&mut app, KeyModifiers::ALT, 'q');
+        press(&mut app, KeyModifiers::CONTROL | KeyModifiers::ALT, 'v');
+        assert_eq!(app.active_area, ActiveArea::Editor);
+        assert_eq!(app.editor.editor_content, vec!["qv"]);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::core::app::App;
-use crate::core::cursor::CursorPosition;
+use crate::core::cursor::{to_coord, CursorPosition};
 use crate::core::editor::editor::EditAction;
+use crate::core::editor::TextStorage;
 use crate::errors::plugin_error::PluginError;
 use crate::plugins::plugin_registry::{
     KeyContext, Plugin, PluginCommand, PluginKeybinding, PluginMetadata,
@@ -42,13 +43,14 @@ impl SearchReplacePlugin {
     }
 
     /// Find matches in buffer provided, according to search query text
-    fn find_matches(&mut self, buffer: &[String]) {
+    fn find_matches<S: TextStorage>(&mut self, buffer: &S) {
         self.matches.clear();
         if self.search_query.is_empty() {
             return;
         }
         // iterate on query array then matches fo und on
-        for (line_idx, line) in buffer.iter().enumerate() {
+        for line_idx in 0..buffer.line_count() {
+            let line = buffer.line(line_idx).unwrap_or("");
             let mut start_idx = 0;
 
             while start_idx < line.len() {
@@ -107,21 +109,18 @@ impl SearchReplacePlugin {
         //current match
         let (line_idx, byte_col) = self.matches[self.current_match_idx];
 
-        let mut buffer = app.editor.editor_content.clone();
-
-        if line_idx > buffer.len() {
+        let query_len = self.search_query.len();
+        let Some(line) = app.editor.editor_content.line_mut(line_idx) else {
             return Err(PluginError::Internal(
                 "Line index is larger than buffer length".to_string(),
             ));
-        }
-
-        let line = &mut buffer[line_idx];
+        };
 
         // Ensure byte position is at a char boundary
-        let actual_byte_col = self.find_char_boundary(line, byte_col);
+        let actual_byte_col = Self::find_char_boundary(line, byte_col);
 
         // Calculate byte range to replace
-        let byte_end = (actual_byte_col + self.search_query.len()).min(line.len());
+        let byte_end = (actual_byte_col + query_len).min(line.len());
 
         // Verify can slice safely
         if !line.is_char_boundary(actual_byte_col) || !line.is_char_boundary(byte_end) {
@@ -132,12 +131,9 @@ impl SearchReplacePlugin {
         }
 
         let old_text = line[actual_byte_col..byte_end].to_string();
-        // Replace
+        // Replace in place, only the matched line is touched
         line.replace_range(actual_byte_col..byte_end, &self.replace_text);
 
-        // Replace editor content with new changes
-        app.editor.editor_content = buffer.clone();
-
         // Find new matches and scroll to
         self.find_matches(&app.editor.editor_content);
         self.scroll_to_match(app);
@@ -171,20 +167,14 @@ impl SearchReplacePlugin {
         // Collect all sub actions taken
         let mut bulk_actions: Vec<EditAction> = Vec::new();
 
-        let mut buffer = app.editor.editor_content.clone();
-
         // Work backwards to avoid index shifting
-        let matches_copy: Vec<(usize, usize)> = self.matches.clone();
-
-        for &(line_idx, byte_col) in matches_copy.iter().rev() {
-            if line_idx >= buffer.len() {
+        for &(line_idx, byte_col) in self.matches.iter().rev() {
+            let Some(line) = app.editor.editor_content.line_mut(line_idx) else {
                 continue;
-            }
-
-            let line = &mut buffer[line_idx];
+            };
 
             // Ensure byte position is at a char boundary
-            let actual_byte_col = self.find_char_boundary(line, byte_col);
+            let actual_byte_col = Self::find_char_boundary(line, byte_col);
             let byte_end = (actual_byte_col + self.search_query.len()).min(line.len());
 
             // Verify we can slice safely
@@ -209,9 +199,6 @@ impl SearchReplacePlugin {
             });
         }
 
-        // Put modified buffer back once
-        app.editor.editor_content = buffer.clone();
-
         // Record undo if not empty
         if !bulk_actions.is_empty() {
             app.editor
@@ -220,7 +207,7 @@ impl SearchReplacePlugin {
         }
 
         // Re-find matches in updated buffer
-        self.find_matches(&buffer);
+        self.find_matches(&app.editor.editor_content);
 
         // Scroll to first match if available
         if !self.matches.is_empty() {
@@ -232,7 +219,7 @@ impl SearchReplacePlugin {
     }
 
     /// Find the char boundary at or before the given byte position
-    fn find_char_boundary(&self, line: &str, byte_pos: usize) -> usize {
+    fn find_char_boundary(line: &str, byte_pos: usize) -> usize {
         if byte_pos > line.len() {
             return line.len();
         }
@@ -252,7 +239,8 @@ impl SearchReplacePlugin {
     /// Scroll editor to current match
     fn scroll_to_match(&self, app: &mut App) {
         if let Some((line, col)) = self.matches.get(self.current_match_idx) {
-            // Move cursor to match position
+            // Move cursor to match position, the jump list remembers where it was
+            app.editor.record_jump();
             app.editor
                 .set_cursor_position(&CursorPosition { x: *col, y: *line });
 
@@ -263,7 +251,7 @@ impl SearchReplacePlugin {
             } else {
                 line - viewport_height / 2
             };
-            app.editor.set_scroll_offset(target_scroll as i16);
+            app.editor.set_scroll_offset(to_coord(target_scroll));
         }
     }
 
@@ -353,11 +341,9 @@ impl SearchReplacePlugin {
             }
 
             // Get the actual line content
-            if line >= app.editor.editor_content.len() {
+            let Some(line_content) = app.editor.editor_content.line(line) else {
                 continue;
-            }
-
-            let line_content = &app.editor.editor_content[line];
+            };
 
             // Convert byte position to visual column position
             let visual_col = self.byte_pos_to_visual_col(line_content, byte_col, tab_width);
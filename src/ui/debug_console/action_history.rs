@@ -20,7 +20,7 @@ pub fn render_history(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_undo_stack(frame: &mut Frame, app: &App, area: Rect) {
-    let undo_stack = &app.editor.undo_redo_manager.undo_stack;
+    let undo_stack = app.editor.undo_redo_manager.undo_stack();
 
     if undo_stack.is_empty() {
         let text = vec![Line::from(""), Line::from("  No undo history")];
@@ -52,7 +52,7 @@ fn render_undo_stack(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_redo_stack(frame: &mut Frame, app: &App, area: Rect) {
-    let redo_stack = &app.editor.undo_redo_manager.redo_stack;
+    let redo_stack = app.editor.undo_redo_manager.redo_stack();
 
     if redo_stack.is_empty() {
         let text = vec![Line::from(""), Line::from("  No redo history")];
@@ -1,4 +1,5 @@
 use crate::core::app::App;
+use crate::core::clipboard::ClipboardEntry;
 use ratatui::style::Modifier;
 use ratatui::widgets::List;
 use ratatui::{
@@ -11,8 +12,12 @@ use ratatui::{
 
 pub fn render_clipboard(frame: &mut Frame, app: &App, area: Rect) {
     let clipboard = &app.editor.clipboard;
+    let current = clipboard.current();
 
-    if clipboard.copied_text.is_empty() {
+    if current.text.is_empty()
+        && clipboard.history().next().is_none()
+        && clipboard.registers().next().is_none()
+    {
         let text = vec![
             Line::from(""),
             Line::from("  Clipboard is empty"),
@@ -21,7 +26,7 @@ pub fn render_clipboard(frame: &mut Frame, app: &App, area: Rect) {
         ];
 
         let block = Block::default()
-            .title("Clipboard")
+            .title(format!("Clipboard ({})", clipboard.provider_name()))
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::Magenta));
 
@@ -30,54 +35,76 @@ pub fn render_clipboard(frame: &mut Frame, app: &App, area: Rect) {
         return;
     }
 
-    // Create scrollable list of clipboard entries
-    let items: Vec<ListItem> = clipboard
-        .copied_text
-        .iter()
-        .enumerate()
-        .map(|(i, entry)| {
-            // Show preview of entry (first 100 chars)
-            let preview = if entry.len() > 100 {
-                format!("{}...", &entry[..100])
-            } else {
-                entry.clone()
-            };
+    // Width left for previews inside the borders and the entry header
+    let width = area.width.saturating_sub(8) as usize;
 
-            // Replace newlines with visual indicator
-            let preview = preview.replace('\n', "↵ ");
-
-            // Count lines in entry
-            let line_count = entry.lines().count();
-            let char_count = entry.len();
+    let mut items: Vec<ListItem> = vec![section("Pasted")];
+    if !current.text.is_empty() {
+        items.push(entry_item("\"", &current, width));
+    }
 
-            let content = vec![
-                Line::from(vec![
-                    Span::styled(
-                        format!("{:3}: ", i),
-                        Style::default()
-                            .fg(Color::DarkGray)
-                            .add_modifier(Modifier::BOLD),
-                    ),
-                    Span::styled(
-                        format!("[{} lines, {} chars] ", line_count, char_count),
-                        Style::default().fg(Color::Blue),
-                    ),
-                ]),
-                Line::from(vec![Span::raw("     "), Span::raw(preview)]),
-            ];
+    items.push(section(&format!(
+        "History ({} entries)",
+        clipboard.history().count()
+    )));
+    items.extend(
+        clipboard
+            .history()
+            .enumerate()
+            .map(|(i, entry)| entry_item(&i.to_string(), entry, width)),
+    );
 
-            ListItem::new(content)
-        })
-        .collect();
+    items.push(section(&format!(
+        "Registers ({} named)",
+        clipboard.registers().count()
+    )));
+    items.extend(
+        clipboard
+            .registers()
+            .map(|(name, entry)| entry_item(&name.to_string(), entry, width)),
+    );
 
     let block = Block::default()
-        .title(format!(
-            "Clipboard ({} entries)",
-            clipboard.copied_text.len()
-        ))
+        .title(format!("Clipboard ({})", clipboard.provider_name()))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Magenta));
 
     let list = List::new(items).block(block);
     frame.render_widget(list, area);
 }
+
+fn section(title: &str) -> ListItem<'static> {
+    ListItem::new(Line::from(Span::styled(
+        title.to_string(),
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    )))
+}
+
+fn entry_item(name: &str, entry: &ClipboardEntry, width: usize) -> ListItem<'static> {
+    let char_count: usize = entry.text.iter().map(|line| line.chars().count()).sum();
+
+    let content = vec![
+        Line::from(vec![
+            Span::styled(
+                format!("{:>3}: ", name),
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    "[{:?}, {} lines, {} chars] ",
+                    entry.kind,
+                    entry.text.len(),
+                    char_count
+                ),
+                Style::default().fg(Color::Blue),
+            ),
+        ]),
+        Line::from(vec![Span::raw("     "), Span::raw(entry.preview(width))]),
+    ];
+
+    ListItem::new(content)
+}
@@ -1,5 +1,6 @@
 use crate::core::app::App;
 use crate::core::debug::AppSnapshot;
+use crate::core::editor::TextStorage;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -86,11 +87,10 @@ fn render_snapshot_header(frame: &mut Frame, snapshot: &AppSnapshot, idx: usize,
 }
 
 fn render_snapshot_buffer(frame: &mut Frame, snapshot: &AppSnapshot, area: Rect) {
-    let lines: Vec<Line> = snapshot
-        .buffer_content
-        .iter()
-        .enumerate()
-        .map(|(i, line)| {
+    let content = &snapshot.buffer_content;
+    let lines: Vec<Line> = (0..content.line_count())
+        .map(|i| {
+            let line = content.line(i).unwrap_or("");
             let line_num = format!("{:4} ", i + 1);
             Line::from(vec![
                 Span::styled(line_num, Style::default().fg(Color::DarkGray)),
@@ -4,9 +4,10 @@ use ratatui::layout::Rect;
 #[derive(Debug, Clone, Copy)]
 pub struct UILayout {
     pub status_bar_area: Option<Rect>,
-    pub editor_area: Rect, // Full editor area (includes line numbers)
+    pub tab_bar_area: Option<Rect>, // Optional tab bar of open buffers
+    pub editor_area: Rect,          // Focused view area (includes line numbers)
     pub line_number_area: Option<Rect>, // Optional line numbers on left
-    pub content_area: Rect, // Actual text content area
+    pub content_area: Rect,         // Actual text content area
     pub command_line_area: Rect,
 }
 
@@ -19,6 +20,7 @@ impl UILayout {
                 width: area.width,
                 height: 1,
             }),
+            tab_bar_area: None,
             editor_area: Rect {
                 x: 0,
                 y: 1,
@@ -48,6 +50,7 @@ impl UILayout {
             "content" => Some(self.content_area),
             "line_numbers" => self.line_number_area,
             "statusbar" => self.status_bar_area,
+            "tabbar" => self.tab_bar_area,
             "commandline" => Some(self.command_line_area),
             _ => None,
         }
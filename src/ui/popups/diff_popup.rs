@@ -0,0 +1,92 @@
+use crate::core::line_diff::{with_context, DiffLine};
+use crate::input::actions::{Direction, InputAction, PopupAction};
+use crate::ui::popups::popup::{Popup, PopupResult, PopupType};
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+/// Unchanged lines shown around each change
+const CONTEXT_LINES: usize = 2;
+
+/// Changes to the text shown before making them. Enter answers yes and
+/// Esc no
+#[derive(Debug)]
+pub struct DiffPopup {
+    title: String,
+    lines: Vec<Line<'static>>,
+    scroll_offset: usize,
+}
+
+impl DiffPopup {
+    pub fn new(title: &str, diff: &[DiffLine]) -> Self {
+        let mut lines: Vec<Line<'static>> = with_context(diff, CONTEXT_LINES)
+            .into_iter()
+            .map(|row| match row {
+                Some(DiffLine::Same(text)) => Line::raw(format!("  {}", text)),
+                Some(DiffLine::Removed(text)) => {
+                    Line::styled(format!("- {}", text), Style::default().fg(Color::Red))
+                }
+                Some(DiffLine::Added(text)) => {
+                    Line::styled(format!("+ {}", text), Style::default().fg(Color::Green))
+                }
+                None => Line::styled("  …", Style::default().fg(Color::DarkGray)),
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push(Line::styled(
+                "  No changes to the text",
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        Self {
+            title: title.to_string(),
+            lines,
+            scroll_offset: 0,
+        }
+    }
+}
+
+impl Popup for DiffPopup {
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(format!(" {} ", self.title))
+            .title_bottom(" ↑↓: Scroll | Enter: Apply | Esc: Cancel ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(Color::Blue));
+
+        let paragraph = Paragraph::new(self.lines.clone())
+            .block(block)
+            .scroll((self.scroll_offset as u16, 0));
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
+    }
+
+    fn get_popup_type(&self) -> PopupType {
+        PopupType::Confirmation
+    }
+
+    fn handle_input_action(&mut self, action: InputAction) -> PopupResult {
+        match action {
+            InputAction::ENTER => PopupResult::Bool(true),
+            InputAction::Popup(PopupAction::Close) | InputAction::ToggleActiveArea => {
+                PopupResult::Bool(false)
+            }
+            InputAction::Popup(PopupAction::MoveCursor(Direction::Up)) => {
+                self.scroll_offset = self.scroll_offset.saturating_sub(1);
+                PopupResult::None
+            }
+            InputAction::Popup(PopupAction::MoveCursor(Direction::Down)) => {
+                self.scroll_offset = (self.scroll_offset + 1).min(self.lines.len() - 1);
+                PopupResult::None
+            }
+            _ => PopupResult::None,
+        }
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (70, 60)
+    }
+}
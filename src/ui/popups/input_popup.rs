@@ -0,0 +1,80 @@
+use crate::input::actions::{InputAction, PopupAction};
+use crate::ui::popups::popup::{Popup, PopupResult, PopupType};
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+/// One line text prompt, Enter answers with the typed text and Esc cancels
+#[derive(Debug)]
+pub struct InputPopup {
+    title: String,
+    hint: String,
+    pub input: String,
+}
+
+impl InputPopup {
+    pub fn new(title: &str, hint: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            hint: hint.to_string(),
+            input: String::new(),
+        }
+    }
+}
+
+impl Popup for InputPopup {
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        // the prompt only needs a line for input and one for the hint
+        let height = area.height.min(4);
+        let area = Rect {
+            y: area.y + (area.height - height) / 2,
+            height,
+            ..area
+        };
+
+        let block = Block::default()
+            .title(format!(" {} ", self.title))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(Color::Blue));
+
+        let text = vec![
+            Line::from(vec![
+                Span::raw(self.input.clone()),
+                Span::styled("█", Style::default().fg(Color::Gray)),
+            ]),
+            Line::styled(self.hint.clone(), Style::default().fg(Color::DarkGray)),
+        ];
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn get_popup_type(&self) -> PopupType {
+        PopupType::Input
+    }
+
+    fn handle_input_action(&mut self, action: InputAction) -> PopupResult {
+        match action {
+            InputAction::ENTER => PopupResult::String(self.input.clone()),
+            InputAction::Popup(PopupAction::Close) | InputAction::ToggleActiveArea => {
+                PopupResult::Affirmed
+            }
+            InputAction::Popup(PopupAction::WriteChar(c)) => {
+                self.input.push(c);
+                PopupResult::None
+            }
+            InputAction::Popup(PopupAction::Backspace) => {
+                self.input.pop();
+                PopupResult::None
+            }
+            _ => PopupResult::None,
+        }
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (40, 50)
+    }
+}
@@ -1,6 +1,9 @@
 pub mod config_validation_result_popup;
 pub mod confirmation_popup;
+pub mod diff_popup;
 pub mod error_popup;
 pub mod help_popup;
+pub mod input_popup;
 pub mod popup;
 pub mod scrollable_text_popup;
+pub mod select_popup;
@@ -28,6 +28,7 @@ pub enum PopupResult {
     None,
     Bool(bool),
     String(String),
+    Selected(usize),
     Affirmed,
 }
 
@@ -37,4 +38,6 @@ pub enum PopupType {
     Warning,
     Error,
     Info,
+    Input,
+    Select,
 }
@@ -0,0 +1,104 @@
+use crate::input::actions::{Direction, InputAction, PopupAction};
+use crate::ui::popups::popup::{Popup, PopupResult, PopupType};
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState};
+use ratatui::Frame;
+
+/// List to pick an item from. Enter, or the number in front of an item,
+/// answers with its index and Esc cancels
+#[derive(Debug)]
+pub struct SelectPopup {
+    title: String,
+    items: Vec<String>,
+    pub selected: usize,
+    numbered: bool,
+}
+
+impl SelectPopup {
+    pub fn new(title: &str, items: Vec<String>) -> Self {
+        Self {
+            title: title.to_string(),
+            items,
+            selected: 0,
+            numbered: true,
+        }
+    }
+
+    /// Starts with item idx selected
+    pub fn with_selected(mut self, idx: usize) -> Self {
+        self.selected = idx.min(self.items.len().saturating_sub(1));
+        self
+    }
+
+    /// Leaves out the numbers, for items numbered themselves
+    pub fn without_numbers(mut self) -> Self {
+        self.numbered = false;
+        self
+    }
+}
+
+impl Popup for SelectPopup {
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(format!(" {} ", self.title))
+            .title_bottom(" ↑↓: Select | Enter: Pick | Esc: Cancel ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(Color::Blue));
+
+        let items: Vec<ListItem> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| match self.numbered {
+                true => ListItem::new(format!("{:>2}  {}", i + 1, item)),
+                false => ListItem::new(item.as_str()),
+            })
+            .collect();
+        let list = List::new(items).block(block).highlight_style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+        );
+
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn get_popup_type(&self) -> PopupType {
+        PopupType::Select
+    }
+
+    fn handle_input_action(&mut self, action: InputAction) -> PopupResult {
+        match action {
+            InputAction::ENTER if !self.items.is_empty() => PopupResult::Selected(self.selected),
+            InputAction::Popup(PopupAction::Close) | InputAction::ToggleActiveArea => {
+                PopupResult::Affirmed
+            }
+            InputAction::Popup(PopupAction::MoveCursor(Direction::Up)) => {
+                self.selected = self.selected.saturating_sub(1);
+                PopupResult::None
+            }
+            InputAction::Popup(PopupAction::MoveCursor(Direction::Down)) => {
+                self.selected = (self.selected + 1).min(self.items.len().saturating_sub(1));
+                PopupResult::None
+            }
+            InputAction::Popup(PopupAction::WriteChar(c)) if self.numbered => {
+                match c.to_digit(10) {
+                    Some(n) if n >= 1 && (n as usize) <= self.items.len() => {
+                        PopupResult::Selected(n as usize - 1)
+                    }
+                    _ => PopupResult::None,
+                }
+            }
+            _ => PopupResult::None,
+        }
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (60, 50)
+    }
+}
@@ -1,11 +1,17 @@
 use crate::config::EditorConfig;
 use crate::core::app::{ActiveArea, App};
+use crate::core::buffers::BufferSummary;
 use crate::core::cursor::CursorPosition;
+use crate::core::editor::file_format::FileFormat;
+use crate::core::editor::modal::ModalState;
+use crate::core::editor::multi_cursor::SecondaryCursor;
+use crate::core::editor::text_buffer::char_slice;
+use crate::core::editor::{graphemes, wrap, TextBuffer};
 use crate::ui::debug;
 use ratatui::layout::{Alignment, Position, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span, Text};
-use ratatui::widgets::Paragraph;
+use ratatui::widgets::{Paragraph, Tabs};
 use ratatui::{
     layout::{Constraint, Direction, Layout},
     widgets::Block,
@@ -31,7 +37,7 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
 }
 
 fn render_editor_ui(frame: &mut Frame, app: &mut App) {
-    app.terminal_height = frame.area().height as i16;
+    app.terminal_height = frame.area().height as i32;
 
     // just array of constraints to use in layout,
     // mutable, so can step by step add to constraints
@@ -42,6 +48,11 @@ fn render_editor_ui(frame: &mut Frame, app: &mut App) {
         constraints.push(Constraint::Length(1));
     }
 
+    // check for tab bar enabled
+    if app.config.ui.show_tab_bar {
+        constraints.push(Constraint::Length(1));
+    }
+
     // Editor area
     constraints.push(Constraint::Min(1));
 
@@ -64,46 +75,117 @@ fn render_editor_ui(frame: &mut Frame, app: &mut App) {
         None
     };
 
+    // Find tab bar area if enabled
+    let tab_bar_area = if app.config.ui.show_tab_bar {
+        let area = layout[layout_idx];
+        layout_idx += 1;
+        Some(area)
+    } else {
+        None
+    };
+
     let editor_area = layout[layout_idx];
     layout_idx += 1;
     let command_area = layout[layout_idx];
 
-    app.editor.editor_height = editor_area.height;
-
-    // Editor layout with optional line numbers
-    let (line_number_area, content_area) = if app.config.editor.line_numbers {
-        let line_count = app.editor.editor_content.len();
-        let line_num_width = (line_count.to_string().len() as u16).max(2) + 1;
-
-        let editor_layout = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(vec![Constraint::Length(line_num_width), Constraint::Min(1)])
-            .split(editor_area);
-
-        (Some(editor_layout[0]), editor_layout[1])
-    } else {
-        (None, editor_area)
-    };
+    // Split views fill the editor area, each with a view bar if there are several
+    let show_view_bars = app.splits.count() > 1;
+    let focused_view = app.splits.focused();
+    let view_areas = app.splits.areas(editor_area);
+
+    // The focused view drives editor viewport size and layout areas
+    let focused_view_area = view_areas
+        .iter()
+        .find(|(view_id, _)| *view_id == focused_view)
+        .map(|(_, area)| *area)
+        .unwrap_or(editor_area);
+    let (text_area, _) = split_view_bar(focused_view_area, show_view_bars);
+    let (line_number_area, content_area) = split_line_numbers(
+        text_area,
+        app.editor.editor_content.len(),
+        &app.config.editor,
+    );
+    app.editor.editor_height = content_area.height;
+    app.editor.editor_width = content_area.width as i32;
     //----------------------------------------------------------
 
     // Update app layout areas with new found areas
     app.update_layout(
         status_bar_area,
-        editor_area,
+        tab_bar_area,
+        focused_view_area,
         line_number_area,
         content_area,
         command_area,
     );
 
-    app.editor.editor_width = content_area.width as i16;
+    let buffer_summaries = app.buffer_summaries();
+    for (view_id, view_area) in view_areas {
+        let is_focused = view_id == focused_view;
+        let (text_area, view_bar_area) = split_view_bar(view_area, show_view_bars);
+
+        let (content, view_cursor) = if is_focused {
+            (
+                &app.editor.editor_content,
+                ViewCursor {
+                    cursor_y: app.editor.cursor.y,
+                    visual_x: app.editor.visual_cursor_x,
+                    scroll_offset: app.editor.scroll_offset,
+                    selection_start: app.editor.text_selection_start,
+                    selection_end: app.editor.text_selection_end,
+                    secondary_cursors: app.editor.secondary_cursors.clone(),
+                    block_ranges: app.editor.block_ranges(),
+                    matching_bracket: app
+                        .config
+                        .editor
+                        .match_brackets
+                        .then(|| app.editor.matching_bracket())
+                        .flatten(),
+                },
+            )
+        } else {
+            let (Some(view), Some(editor)) = (app.splits.view(view_id), app.view_editor(view_id))
+            else {
+                continue;
+            };
+            (
+                &editor.editor_content,
+                ViewCursor {
+                    cursor_y: view.cursor.y as i32,
+                    visual_x: 0,
+                    scroll_offset: view.scroll_offset,
+                    selection_start: None,
+                    selection_end: None,
+                    secondary_cursors: Vec::new(),
+                    block_ranges: Vec::new(),
+                    matching_bracket: None,
+                },
+            )
+        };
 
-    let editor_content: Text = handle_editor_content(
-        app.editor.editor_content.clone(),
-        app.editor.text_selection_start,
-        app.editor.text_selection_end,
-        content_area.width as usize,
-        app,
-    );
+        let (view_line_number_area, view_content_area) =
+            split_line_numbers(text_area, content.len(), &app.config.editor);
+        render_view(
+            frame,
+            content,
+            view_cursor,
+            &app.config.editor,
+            view_line_number_area,
+            view_content_area,
+        );
+
+        if let Some(bar_area) = view_bar_area {
+            let summary = app
+                .view_buffer_index(view_id)
+                .and_then(|idx| buffer_summaries.get(idx));
+            if let Some(summary) = summary {
+                frame.render_widget(
+                    view_bar(summary.name.clone(), summary.modified, is_focused),
+                    bar_area,
+                );
+            }
+        }
+    }
 
     let command_input: String = app.command_line.input.to_string();
     let file_name_optional: Option<PathBuf> = app.file_path.clone();
@@ -124,40 +206,23 @@ fn render_editor_ui(frame: &mut Frame, app: &mut App) {
                 app.editor.cursor.y,
                 app.editor.visual_cursor_x,
                 app.editor.scroll_offset,
-                app.editor.text_selection_start,
-                app.editor.text_selection_end,
+                app.editor
+                    .text_selection_start
+                    .zip(app.editor.text_selection_end),
                 app.content_modified,
+                app.editor.modal.as_ref(),
+                app.macros.recording(),
+                app.editor.file_format,
             ),
             status_area,
         );
     }
 
-    // Render line number side line if enabled
-    if let Some(ln_area) = line_number_area {
-        frame.render_widget(
-            editor_side_line(
-                editor_content.clone(),
-                app.editor.scroll_offset as u16,
-                content_area.width as usize,
-                app.editor.cursor.y,
-                &app.config.editor,
-            ),
-            ln_area,
-        );
+    // Render tab bar of open buffers if enabled
+    if let Some(tab_area) = tab_bar_area {
+        frame.render_widget(tab_bar(buffer_summaries), tab_area);
     }
 
-    // Render editor content
-    frame.render_widget(
-        editor(
-            editor_content,
-            app.editor.scroll_offset as u16,
-            app.editor.cursor.y,
-            &app.config.editor,
-            content_area.height,
-            app.editor.editor_content.len(),
-        ),
-        content_area,
-    );
     // Render command line
     frame.render_widget(command_line(command_input), command_area);
 
@@ -180,9 +245,11 @@ fn render_editor_ui(frame: &mut Frame, app: &mut App) {
     if should_show_cursor {
         match app.active_area {
             ActiveArea::Editor => {
-                let x = content_area.x + app.editor.visual_cursor_x as u16; //using visual x
+                // visual position, row and column within the row when lines are wrapped
+                let x = content_area.x
+                    + app.editor.cursor_viewport_col().clamp(0, u16::MAX as i32) as u16;
                 let y = content_area.y
-                    + (app.editor.cursor.y - app.editor.scroll_offset).clamp(0, i16::MAX) as u16;
+                    + app.editor.cursor_viewport_row().clamp(0, u16::MAX as i32) as u16;
                 let pos: Position = Position { x, y };
 
                 frame.set_cursor_position(pos);
@@ -213,30 +280,161 @@ fn centered_rect(percent_width: u16, percent_height: u16, area: Rect) -> Rect {
     }
 }
 
+/// Cursor, scroll and selection of a view, what is needed besides content to draw it
+struct ViewCursor {
+    cursor_y: i32,
+    visual_x: i32,
+    scroll_offset: i32,
+    selection_start: Option<CursorPosition>,
+    selection_end: Option<CursorPosition>,
+    secondary_cursors: Vec<SecondaryCursor>,
+    block_ranges: Vec<(CursorPosition, CursorPosition)>,
+    matching_bracket: Option<(CursorPosition, CursorPosition)>,
+}
+
+///splits off a one line view bar at the bottom of a view area, if enabled
+fn split_view_bar(area: Rect, show_view_bar: bool) -> (Rect, Option<Rect>) {
+    if !show_view_bar || area.height < 2 {
+        return (area, None);
+    }
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Min(1), Constraint::Length(1)])
+        .split(area);
+    (parts[0], Some(parts[1]))
+}
+
+///splits area into optional line number area and content area, line number width
+/// depends on line count of content shown
+fn split_line_numbers(
+    area: Rect,
+    line_count: usize,
+    config: &EditorConfig,
+) -> (Option<Rect>, Rect) {
+    if !config.line_numbers {
+        return (None, area);
+    }
+    let line_num_width = (line_count.to_string().len() as u16).max(2) + 1;
+    let parts = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Length(line_num_width), Constraint::Min(1)])
+        .split(area);
+    (Some(parts[0]), parts[1])
+}
+
+///renders line numbers and content of one view
+fn render_view(
+    frame: &mut Frame,
+    content: &TextBuffer,
+    view: ViewCursor,
+    config: &EditorConfig,
+    line_number_area: Option<Rect>,
+    content_area: Rect,
+) {
+    let wrap_width = Some(content_area.width as usize).filter(|w| config.wrap_lines && *w > 0);
+
+    // only lines inside the viewport are copied out of the buffer and styled
+    let first_visible_line = view.scroll_offset.max(0) as usize;
+    let visible_lines: Vec<String> = content
+        .iter_from(first_visible_line)
+        .take(content_area.height as usize)
+        .cloned()
+        .collect();
+
+    let editor_content: Text = handle_editor_content(
+        visible_lines,
+        first_visible_line,
+        &view,
+        content_area.width as usize,
+        config,
+    );
+
+    // Render line number side line if enabled
+    if let Some(ln_area) = line_number_area {
+        frame.render_widget(
+            editor_side_line(
+                editor_content.clone(),
+                first_visible_line,
+                content_area.width as usize,
+                view.cursor_y,
+                config,
+                wrap_width,
+            ),
+            ln_area,
+        );
+    }
+
+    // Render editor content
+    frame.render_widget(
+        editor(
+            editor_content,
+            first_visible_line,
+            view.cursor_y,
+            config,
+            content_area.height,
+            content.len(),
+            wrap_width,
+        ),
+        content_area,
+    );
+}
+
 //COMPONENTS
 fn info_bar<'a>(
     file_name: String,
-    cursor_x: i16,
-    cursor_y: i16,
-    visual_x: i16,
-    scroll_offset: i16,
-    selection_start: Option<CursorPosition>,
-    selection_end: Option<CursorPosition>,
+    cursor_x: i32,
+    cursor_y: i32,
+    visual_x: i32,
+    scroll_offset: i32,
+    selection: Option<(CursorPosition, CursorPosition)>,
     is_content_modified: bool,
+    modal: Option<&ModalState>,
+    recording_macro: Option<char>,
+    file_format: FileFormat,
 ) -> Paragraph<'a> {
     let modified_indicator = if is_content_modified { "[+]" } else { "" };
 
-    let selection_cursor_info = if selection_start.is_some() && selection_end.is_some() {
-        let start = selection_start.unwrap();
-        let end = selection_end.unwrap();
-        format!(" | Sel: ({},{}) → ({},{})", start.x, start.y, end.x, end.y)
-    } else {
-        String::new()
+    let selection_cursor_info = match selection {
+        Some((start, end)) => format!(" | Sel: ({},{}) → ({},{})", start.x, start.y, end.x, end.y),
+        None => String::new(),
     };
 
+    //mode of modal editing, with the keys of a command being typed
+    let mode_indicator = match modal {
+        Some(state) => format!("-- {} -- {} ", state.mode.label(), state.pending_keys()),
+        None => String::new(),
+    };
+
+    let recording_indicator = match recording_macro {
+        Some(register) => format!("recording @{} ", register),
+        None => String::new(),
+    };
+
+    //line endings written on save, and a last line left unended
+    let format_indicator = format!(
+        " [{}]{}",
+        file_format.line_ending.name(),
+        if file_format.final_newline {
+            ""
+        } else {
+            "[noeol]"
+        }
+    );
+
     let line = Line::from(vec![
+        Span::styled(
+            recording_indicator,
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            mode_indicator,
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
         Span::styled(modified_indicator, Style::default().fg(Color::White)),
         Span::styled(file_name, Style::default().fg(Color::LightCyan)),
+        Span::styled(format_indicator, Style::default().fg(Color::Gray)),
         Span::raw(" - "), // Separator
         Span::styled(
             format!(
@@ -256,13 +454,52 @@ fn info_bar<'a>(
     )
 }
 
+///generates a tab bar with one tab per open buffer, marking modified ones
+fn tab_bar<'a>(buffers: Vec<BufferSummary>) -> Tabs<'a> {
+    let active = buffers.iter().position(|b| b.active).unwrap_or(0);
+    let titles: Vec<String> = buffers
+        .into_iter()
+        .map(|b| {
+            let modified_indicator = if b.modified { "[+]" } else { "" };
+            format!("{} {}{}", b.index + 1, b.name, modified_indicator)
+        })
+        .collect();
+
+    Tabs::new(titles)
+        .select(active)
+        .style(Style::default().fg(Color::Gray).bg(Color::Black))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::LightCyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .divider("|")
+}
+
+///generates the bar under a split view, naming its buffer and marking the focused view
+fn view_bar<'a>(name: String, modified: bool, focused: bool) -> Paragraph<'a> {
+    let modified_indicator = if modified { "[+]" } else { "" };
+    let style = if focused {
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::LightCyan)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Gray).bg(Color::DarkGray)
+    };
+
+    Paragraph::new(format!(" {}{}", name, modified_indicator)).style(style)
+}
+
 ///generates a side bar for line nr display as well as displaying line overflow if existing
 fn editor_side_line<'a>(
     editor_content: Text,
-    scroll_offset: u16,
+    first_line: usize,
     editor_width: usize,
-    cursor_y: i16,
+    cursor_y: i32,
     config: &EditorConfig,
+    wrap_width: Option<usize>,
 ) -> Paragraph<'a> {
     let mut line_nrs: Text = Text::from(vec![]);
 
@@ -273,19 +510,20 @@ fn editor_side_line<'a>(
         .add_modifier(Modifier::BOLD);
     let normal_line_style = Style::default().fg(Color::Gray);
 
-    for (nr, s) in editor_content.iter().enumerate() {
+    for (i, s) in editor_content.iter().enumerate() {
+        let nr = first_line + i;
         let line_index = nr;
         let is_current_line = cursor_y as usize == line_index;
 
         // Calculate line number to display
         let line_num_display = if config.relative_line_numbers && !is_current_line {
-            cursor_y.abs_diff(nr as i16).to_string()
+            cursor_y.abs_diff(nr as i32).to_string()
         } else {
             (line_index + 1).to_string()
         };
 
-        // If content of line is longer than editor
-        let has_overflow = s.width() >= editor_width;
+        // If content of line is longer than editor, wrapped lines never overflow
+        let has_overflow = wrap_width.is_none() && s.width() >= editor_width;
 
         let line = if has_overflow {
             Line::from(vec![
@@ -311,27 +549,36 @@ fn editor_side_line<'a>(
         };
 
         line_nrs.push_line(line);
+
+        // Continuation rows of a wrapped line get an empty gutter
+        if let Some(width) = wrap_width {
+            for _ in 1..wrapped_row_count(s, width) {
+                line_nrs.push_line(Line::default());
+            }
+        }
     }
 
     Paragraph::new(line_nrs)
         .style(Style::default().bg(Color::DarkGray).fg(Color::White))
         .block(Block::default())
-        .scroll((scroll_offset, 0))
 }
 
 fn editor<'a>(
     editor_content: Text<'a>,
-    scroll_offset: u16,
-    cursor_y: i16,
+    first_line: usize,
+    cursor_y: i32,
     config: &EditorConfig,
     viewport_height: u16,
     content_length: usize,
+    wrap_width: Option<usize>,
 ) -> Paragraph<'a> {
+    let lines_shown = editor_content.lines.len();
+
     // Apply current line highlighting if enabled
     let mut lines_vec = if config.highlight_current_line {
         let mut lines = Vec::new();
         for (i, line) in editor_content.lines.iter().enumerate() {
-            if i == cursor_y as usize {
+            if first_line + i == cursor_y as usize {
                 // Highlight current line
                 let highlighted_spans: Vec<Span> = line
                     .spans
@@ -350,22 +597,18 @@ fn editor<'a>(
         editor_content.lines
     };
 
-    // Add empty lines at the end for bottom margin effect
-    let visible_lines_start = scroll_offset as usize;
-    let visible_lines_end = (scroll_offset + viewport_height) as usize;
+    // Soft wrap, each logical line becomes one or more visual rows
+    if let Some(width) = wrap_width {
+        lines_vec = lines_vec
+            .into_iter()
+            .flat_map(|line| wrap_styled_line(line, width))
+            .collect();
+    }
 
-    // If we're scrolled past the actual content, add empty placeholder lines
-    if visible_lines_start < content_length && visible_lines_end > content_length {
-        let empty_lines_needed = visible_lines_end - content_length;
-        for _ in 0..empty_lines_needed {
-            lines_vec.push(Line::from(Span::styled(
-                "~",
-                Style::default().fg(Color::Blue),
-            )));
-        }
-    } else if visible_lines_start >= content_length {
-        // Entirely in the margin area
-        for _ in 0..viewport_height {
+    // If the end of content is in view, fill the rows below it with placeholder lines
+    // for bottom margin effect
+    if first_line + lines_shown >= content_length {
+        for _ in lines_vec.len()..viewport_height as usize {
             lines_vec.push(Line::from(Span::styled(
                 "~",
                 Style::default().fg(Color::Blue),
@@ -378,7 +621,6 @@ fn editor<'a>(
     Paragraph::new(styled_content)
         .style(Style::default().fg(Color::White))
         .block(Block::default())
-        .scroll((scroll_offset, 0))
 }
 
 fn command_line<'a>(command_input: String) -> Paragraph<'a> {
@@ -397,17 +639,17 @@ fn command_line<'a>(command_input: String) -> Paragraph<'a> {
 /// content is interpreted visually
 fn handle_editor_content<'a>(
     vec: Vec<String>,
-    selection_start: Option<CursorPosition>,
-    selection_end: Option<CursorPosition>,
+    first_line: usize,
+    view: &ViewCursor,
     editor_width: usize,
-    app: &mut App,
+    config: &EditorConfig,
 ) -> Text<'a> {
     let editor_vec: Vec<String> = vec
-        .into_iter()
+        .iter()
         .map(|s| {
             // If show whitespaces render white space " " as "·"
-            let with_tabs = handle_tab_rendering(s, app.config.editor.tab_width);
-            if app.config.editor.show_whitespace {
+            let with_tabs = handle_tab_rendering(s, config.tab_width);
+            if config.show_whitespace {
                 with_tabs.replace(" ", "·")
             } else {
                 with_tabs
@@ -417,19 +659,20 @@ fn handle_editor_content<'a>(
 
     let mut editor_text: Text = Text::default();
 
-    if selection_start.is_some() {
-        editor_text = highlight_text(editor_vec.clone(), selection_start, selection_end);
+    let highlights = view_highlights(view);
+    if !highlights.is_empty() {
+        editor_text = highlight_text(&vec, editor_vec, first_line, config.tab_width, &highlights);
     } else {
         for (i, s) in editor_vec.into_iter().enumerate() {
-            let visual_x = app.editor.visual_cursor_x;
+            let visual_x = view.visual_x;
 
             // Line wrapping and horizontal scroll
-            let line: Line = if app.config.editor.wrap_lines {
-                // Simple wrap TODO make actual wrapping solution that is intelligent
+            let line: Line = if config.wrap_lines {
+                // Wrapped into rows when drawn, so no horizontal scroll
                 Line::from(s)
-            } else if i == app.editor.cursor.y as usize && visual_x > editor_width as i16 {
+            } else if first_line + i == view.cursor_y as usize && visual_x > editor_width as i32 {
                 // Horizontal scroll for current line
-                let start_idx = (visual_x - editor_width as i16).max(0) as usize;
+                let start_idx = (visual_x - editor_width as i32).max(0) as usize;
                 Line::from(
                     get_copy_of_editor_content_at_line_between_cursor_editor_width(s, start_idx),
                 )
@@ -444,90 +687,195 @@ fn handle_editor_content<'a>(
     editor_text
 }
 
-///gets a copy of the text content at specific line and range of editor content
+///number of visual rows a styled line is wrapped into, matches wrap_styled_line
+fn wrapped_row_count(line: &Line, width: usize) -> usize {
+    let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+    wrap::row_ranges(&text, width).len()
+}
+
+///splits styled line into rows of at most width cells, keeping span styles.
+/// A line filling its last row exactly gets an extra empty row for the cursor
+fn wrap_styled_line<'a>(line: Line<'a>, width: usize) -> Vec<Line<'a>> {
+    let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+    let styled: Vec<(char, Style)> = line
+        .spans
+        .iter()
+        .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+        .collect();
+
+    wrap::row_ranges(&text, width)
+        .into_iter()
+        .map(|range| {
+            let mut row: Vec<Span> = Vec::new();
+            for &(c, style) in &styled[range] {
+                match row.last_mut() {
+                    Some(span) if span.style == style => span.content.to_mut().push(c),
+                    _ => row.push(Span::styled(c.to_string(), style)),
+                }
+            }
+            Line::from(row).style(line.style)
+        })
+        .collect()
+}
+
+///gets a copy of the text content at specific line from visual column start
 fn get_copy_of_editor_content_at_line_between_cursor_editor_width(
     s: String,
     start: usize,
 ) -> String {
-    let mut line_chars_vec: Vec<char> = s.chars().collect();
-    line_chars_vec.drain(start..).collect()
+    let start_idx = wrap::char_idx_at_visual_col(&s, start, 1);
+    s.chars().skip(start_idx).collect()
 }
 
 ///manipulates how the editor content \t character is rendered visually
-fn handle_tab_rendering(s: String, tab_width: u16) -> String {
-    let mut temp_string: Vec<char> = s.chars().collect();
-
-    let mut i = 0;
+fn handle_tab_rendering(s: &str, tab_width: u16) -> String {
+    wrap::expand_tabs(s, tab_width as usize)
+}
 
-    while i < temp_string.len() {
-        if temp_string[i] == '\t' {
-            let spaces_needed = tab_width as usize - ((i as i16) as usize % tab_width as usize);
+//TEXT HIGHLIGTHING
 
-            temp_string.remove(i);
-            temp_string.splice(i..i, std::iter::repeat(' ').take(spaces_needed));
+///selections of a view, its secondary cursors and block selection, as highlights to draw
+fn view_highlights(view: &ViewCursor) -> Vec<Highlight> {
+    let selected_style = Style::default()
+        .bg(Color::White)
+        .fg(Color::Black)
+        .add_modifier(Modifier::BOLD);
+    let cursor_style = Style::default().add_modifier(Modifier::REVERSED);
+    let bracket_style = Style::default()
+        .bg(Color::DarkGray)
+        .add_modifier(Modifier::BOLD);
 
-            i += spaces_needed - 1; // Adjust index for added spaces
+    let mut highlights = Vec::new();
+    if let (Some(start), Some(end)) = (view.selection_start, view.selection_end) {
+        highlights.push(Highlight {
+            start,
+            end,
+            style: selected_style,
+            cursor: false,
+        });
+    }
+    for secondary in &view.secondary_cursors {
+        if let Some((start, end)) = secondary.selection() {
+            highlights.push(Highlight {
+                start,
+                end,
+                style: selected_style,
+                cursor: false,
+            });
         }
-        i += 1;
+        highlights.push(Highlight {
+            start: secondary.pos,
+            end: secondary.pos,
+            style: cursor_style,
+            cursor: true,
+        });
     }
-
-    temp_string.into_iter().collect()
+    for &(start, end) in &view.block_ranges {
+        if start != end {
+            highlights.push(Highlight {
+                start,
+                end,
+                style: selected_style,
+                cursor: false,
+            });
+        } else if start.y as i32 != view.cursor_y {
+            //a block without width is drawn as a cursor on every line but the real one
+            highlights.push(Highlight {
+                start,
+                end,
+                style: cursor_style,
+                cursor: true,
+            });
+        }
+    }
+    if let Some((bracket, other)) = view.matching_bracket {
+        for pos in [bracket, other] {
+            highlights.push(Highlight {
+                start: pos,
+                end: CursorPosition {
+                    x: pos.x + 1,
+                    y: pos.y,
+                },
+                style: bracket_style,
+                cursor: false,
+            });
+        }
+    }
+    highlights
 }
 
-//TEXT HIGHLIGTHING
+/// Range of text drawn in its own style, positions are in chars of the raw lines.
+/// A cursor highlight covers the grapheme at start, or a placeholder cell at line end
+struct Highlight {
+    start: CursorPosition,
+    end: CursorPosition,
+    style: Style,
+    cursor: bool,
+}
 
-//TODO error when shift selecting up into å æ ø multi byte chars
-/// Function to highlight selected text by processing whole content, and returning styled
+/// Function to highlight selected text by processing visible content, and returning styled
+/// text, first_line is the buffer line index of the first line in text.
+/// Highlights are in chars of the raw lines, so they are mapped onto the rendered ones
 fn highlight_text<'a>(
+    raw: &[String],
     text: Vec<String>,
-    start: Option<CursorPosition>,
-    end: Option<CursorPosition>,
+    first_line: usize,
+    tab_width: u16,
+    highlights: &[Highlight],
 ) -> Text<'a> {
     let mut highlighted_lines = Vec::new();
 
-    // Safety check for None options, and to safely unwrap beneath
-    if start.is_none() || end.is_none() {
-        return Text::from(
-            text.iter()
-                .map(|s| Line::from(s.clone()))
-                .collect::<Vec<_>>(),
-        );
-    }
-
-    let start = start.unwrap();
-    let end = end.unwrap();
+    for (idx, line) in text.iter().enumerate() {
+        let i = first_line + idx;
+        let raw_line = raw.get(idx).map(String::as_str).unwrap_or("");
+        let line_chars = line.chars().count();
 
-    for (i, line) in text.iter().enumerate() {
-        let mut spans = Vec::new();
-
-        if i < start.y || i > end.y {
-            spans.push(Span::raw(line.clone())); // No selection on this line
-        } else {
-            let start_col = if i == start.y { start.x } else { 0 };
-            let end_col = if i == end.y { end.x } else { line.len() };
-
-            // Ensure selection is within valid bounds
-            let start_col = start_col.min(line.len());
-            let end_col = end_col.min(line.len());
-
-            spans.push(Span::raw(line[..start_col].to_string())); // Before selectiona
+        // map raw chars onto rendered chars, tabs expand to several
+        let to_rendered = |x: usize| {
+            let col = wrap::visual_col(raw_line, x, tab_width as usize);
+            wrap::char_idx_at_visual_col(line, col, 1).min(line_chars)
+        };
 
-            let selected_style = Style::default()
-                .bg(Color::White)
-                .fg(Color::Black)
-                .add_modifier(Modifier::BOLD);
+        // rendered ranges highlighted on this line, in line order
+        let mut ranges: Vec<(usize, usize, Style, bool)> = highlights
+            .iter()
+            .filter(|h| h.start.y <= i && i <= h.end.y)
+            .map(|h| {
+                let start = if i == h.start.y { h.start.x } else { 0 };
+                let end = if h.cursor {
+                    graphemes::next_boundary(raw_line, start)
+                } else if i == h.end.y {
+                    h.end.x
+                } else {
+                    raw_line.chars().count()
+                };
+                // empty lines inside a selection get a visual placeholder " "
+                let placeholder = h.cursor || (line.is_empty() && i > h.start.y);
+                (to_rendered(start), to_rendered(end), h.style, placeholder)
+            })
+            .collect();
+        ranges.sort_by_key(|range| range.0);
 
-            if line.is_empty() && i > start.y && i <= end.y {
-                // add highlights for empty lines with added visual placeholder " "
-                spans.push(Span::styled(" ", selected_style));
-            } else {
+        let mut spans = Vec::new();
+        let mut pos = 0;
+        for (start, end, style, placeholder) in ranges {
+            let start = start.max(pos);
+            if start > pos {
+                spans.push(Span::raw(char_slice(line, pos, start).to_string()));
+                pos = start;
+            }
+            if end > start {
                 spans.push(Span::styled(
-                    line[start_col..end_col].to_string(), // Highlighted text
-                    selected_style,
+                    char_slice(line, start, end).to_string(),
+                    style,
                 ));
+                pos = end;
+            } else if placeholder && start >= line_chars {
+                spans.push(Span::styled(" ", style));
             }
-
-            spans.push(Span::raw(line[end_col..].to_string())); // After selection
+        }
+        if pos < line_chars {
+            spans.push(Span::raw(char_slice(line, pos, line_chars).to_string()));
         }
 
         highlighted_lines.push(Line::from(spans));
@@ -31,7 +31,7 @@ mod integration_app_tests {
     fn test_save_confirmation_saves_file_and_removes_state() {
         let mut app = create_app();
         let save_path = test_save_path("file1.txt");
-        app.editor.editor_content = vec![String::from("test")];
+        app.editor.editor_content = vec![String::from("test")].into();
 
         app.pending_states
             .push_back(PendingState::Saving(save_path.clone()));
@@ -52,7 +52,7 @@ mod integration_app_tests {
     fn test_save_rejection_closes_popup_but_does_not_save() {
         let mut app = create_app();
         let save_path = test_save_path("file2.txt");
-        app.editor.editor_content = vec![String::from("test")];
+        app.editor.editor_content = vec![String::from("test")].into();
 
         app.pending_states
             .push_back(PendingState::Saving(save_path.clone()));
@@ -78,7 +78,7 @@ mod integration_app_tests {
     fn test_save_then_quit_calls_save_then_quit() {
         let mut app = create_app();
         let save_path = test_save_path("file3.txt");
-        app.editor.editor_content = vec![String::from("test")];
+        app.editor.editor_content = vec![String::from("test")].into();
         app.pending_states
             .push_back(PendingState::Saving(save_path.clone()));
         app.pending_states.push_back(PendingState::QuittingAbsolute);
@@ -96,7 +96,7 @@ mod integration_app_tests {
 
     fn create_app_with_editor_content(vec: Vec<String>) -> App {
         let mut app = App::new(Config::default(), AppLaunchConfig::default());
-        app.editor.editor_content = vec;
+        app.editor.editor_content = vec.into();
         app
     }
     #[test]
@@ -115,7 +115,7 @@ mod integration_app_tests {
         app.process_input_action(InputAction::ENTER);
 
         let saved_content = fs::read_to_string(file_path).unwrap();
-        assert_eq!(saved_content, "Test content");
+        assert_eq!(saved_content, "Test content\n");
     }
 
     #[test]
@@ -131,7 +131,7 @@ mod integration_app_tests {
         app.process_input_action(InputAction::ENTER);
 
         let saved_content = fs::read_to_string(file_path).unwrap();
-        assert_eq!(saved_content, "New content");
+        assert_eq!(saved_content, "New content\n");
     }
 
     #[test]
@@ -144,7 +144,7 @@ mod integration_app_tests {
         app.process_input_action(InputAction::ENTER);
 
         let saved_content = fs::read_to_string("untitled").unwrap();
-        assert_eq!(saved_content, "Default content");
+        assert_eq!(saved_content, "Default content\n");
 
         fs::remove_file("untitled").unwrap(); // Clean up after test
     }
@@ -153,7 +153,7 @@ mod integration_app_tests {
     fn test_does_not_save_if_no_changes() {
         let temp_file = NamedTempFile::new().unwrap();
         let file_path = temp_file.path().to_path_buf();
-        fs::write(&file_path, "Unchanged content").unwrap();
+        fs::write(&file_path, "Unchanged content\n").unwrap();
         let mut app = create_app_with_editor_content(vec!["Unchanged content".to_string()]);
         app.file_path = Some(file_path.clone());
         app.active_area = ActiveArea::CommandLine;
@@ -165,7 +165,7 @@ mod integration_app_tests {
         app.process_input_action(InputAction::ENTER);
 
         let saved_content = fs::read_to_string(file_path).unwrap();
-        assert_eq!(saved_content, "Unchanged content"); // No overwrite happened
+        assert_eq!(saved_content, "Unchanged content\n"); // No overwrite happened
     }
 
     #[test]
@@ -182,8 +182,1062 @@ mod integration_app_tests {
         app.process_input_action(InputAction::ENTER);
 
         let saved_content = fs::read_to_string(&temp_file_path).unwrap();
-        assert_eq!(saved_content, "Hello World!");
+        assert_eq!(saved_content, "Hello World!\n");
 
         fs::remove_file(temp_file_path).unwrap(); // Clean up
     }
 }
+
+#[cfg(test)]
+mod integration_buffer_tests {
+    use calliglyph::app_config::AppLaunchConfig;
+    use calliglyph::config::Config;
+    use calliglyph::core::app::*;
+    use calliglyph::core::editor::file_format::LineEnding;
+    use calliglyph::core::editor::undo_file;
+    use calliglyph::input::actions::{EditorAction, InputAction};
+    use std::fs;
+    use tempfile::TempDir;
+
+    //init functions
+    fn create_app_with_editor_content(vec: Vec<String>) -> App {
+        let mut app = App::new(Config::default(), AppLaunchConfig::default());
+        app.editor.editor_content = vec.into();
+        app
+    }
+
+    fn run_command(app: &mut App, input: &str) {
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = input.to_string();
+        app.process_input_action(InputAction::ENTER);
+    }
+
+    fn write_file(dir: &TempDir, name: &str, content: &str) -> String {
+        let path = dir.path().join(name);
+        fs::write(&path, content).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_edit_opens_file_in_new_buffer() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(&dir, "other.txt", "first\nsecond");
+        let mut app = create_app_with_editor_content(vec!["scratch".to_string()]);
+
+        run_command(&mut app, &format!(":e {}", path));
+
+        assert!(app.popup.is_none());
+        assert_eq!(app.editor.editor_content, vec!["first", "second"]);
+        assert_eq!(app.file_path.as_ref().unwrap().to_str().unwrap(), path);
+        let buffers = app.buffer_summaries();
+        assert_eq!(buffers.len(), 2);
+        assert!(buffers[1].active);
+        assert_eq!(buffers[1].name, "other.txt");
+    }
+
+    #[test]
+    fn test_edit_already_open_file_switches_to_it() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(&dir, "a.txt", "a");
+        let mut app = create_app_with_editor_content(vec!["scratch".to_string()]);
+
+        run_command(&mut app, &format!(":e {}", path));
+        run_command(&mut app, ":bp");
+        assert_eq!(app.editor.editor_content, vec!["scratch"]);
+
+        run_command(&mut app, &format!(":e {}", path));
+        assert_eq!(app.buffer_summaries().len(), 2);
+        assert_eq!(app.editor.editor_content, vec!["a"]);
+    }
+
+    #[test]
+    fn test_edit_missing_file_opens_empty_buffer() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("new.txt");
+        let mut app = create_app_with_editor_content(vec!["scratch".to_string()]);
+
+        run_command(&mut app, &format!(":e {}", path.display()));
+
+        assert!(app.popup.is_none());
+        assert_eq!(app.editor.editor_content, vec![""]);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_edit_without_path_fails() {
+        let mut app = create_app_with_editor_content(vec!["scratch".to_string()]);
+        run_command(&mut app, ":e");
+        assert!(app.popup.is_some());
+        assert_eq!(app.buffer_summaries().len(), 1);
+    }
+
+    #[test]
+    fn test_buffer_next_and_prev_wrap_around() {
+        let dir = TempDir::new().unwrap();
+        let a = write_file(&dir, "a.txt", "a");
+        let b = write_file(&dir, "b.txt", "b");
+        let mut app = create_app_with_editor_content(vec!["scratch".to_string()]);
+        run_command(&mut app, &format!(":e {}", a));
+        run_command(&mut app, &format!(":e {}", b));
+
+        run_command(&mut app, ":bn");
+        assert_eq!(app.editor.editor_content, vec!["scratch"]);
+        run_command(&mut app, ":bp");
+        assert_eq!(app.editor.editor_content, vec!["b"]);
+        run_command(&mut app, ":bp");
+        assert_eq!(app.editor.editor_content, vec!["a"]);
+    }
+
+    #[test]
+    fn test_buffers_keep_own_undo_history_and_dirty_state() {
+        let dir = TempDir::new().unwrap();
+        let a = write_file(&dir, "a.txt", "a");
+        let mut app = create_app_with_editor_content(vec!["scratch".to_string()]);
+
+        run_command(&mut app, &format!(":e {}", a));
+        app.active_area = ActiveArea::Editor;
+        app.process_input_action(InputAction::Editor(EditorAction::WriteChar('x')));
+        assert!(app.content_modified);
+
+        run_command(&mut app, ":bp");
+        assert!(!app.content_modified);
+        assert!(app.editor.undo_redo_manager.undo_stack().is_empty());
+        assert!(app.buffer_summaries()[1].modified);
+
+        run_command(&mut app, ":bn");
+        assert!(app.content_modified);
+        app.active_area = ActiveArea::Editor;
+        app.process_input_action(InputAction::Editor(EditorAction::UNDO));
+        assert_eq!(app.editor.editor_content, vec!["a"]);
+        assert!(!app.content_modified);
+    }
+
+    #[test]
+    fn test_buffer_delete_refuses_unsaved_changes() {
+        let dir = TempDir::new().unwrap();
+        let a = write_file(&dir, "a.txt", "a");
+        let mut app = create_app_with_editor_content(vec!["scratch".to_string()]);
+        run_command(&mut app, &format!(":e {}", a));
+        app.active_area = ActiveArea::Editor;
+        app.process_input_action(InputAction::Editor(EditorAction::WriteChar('x')));
+
+        run_command(&mut app, ":bd");
+        assert!(app.popup.is_some());
+        assert_eq!(app.buffer_summaries().len(), 2);
+
+        app.close_popup();
+        run_command(&mut app, ":bd!");
+        assert_eq!(app.buffer_summaries().len(), 1);
+        assert_eq!(app.editor.editor_content, vec!["scratch"]);
+    }
+
+    #[test]
+    fn test_buffer_delete_last_buffer_leaves_empty_buffer() {
+        let mut app = create_app_with_editor_content(vec!["scratch".to_string()]);
+        run_command(&mut app, ":bd");
+
+        let buffers = app.buffer_summaries();
+        assert_eq!(buffers.len(), 1);
+        assert_eq!(buffers[0].name, "untitled");
+        assert_eq!(app.editor.editor_content, vec![""]);
+        assert!(app.file_path.is_none());
+    }
+
+    #[test]
+    fn test_buffer_list_opens_popup() {
+        let mut app = create_app_with_editor_content(vec!["scratch".to_string()]);
+        run_command(&mut app, ":ls");
+        assert!(app.popup.is_some());
+        assert_eq!(app.active_area, ActiveArea::Popup);
+    }
+
+    #[test]
+    fn test_quit_asks_confirmation_for_unsaved_parked_buffer() {
+        let dir = TempDir::new().unwrap();
+        let a = write_file(&dir, "a.txt", "a");
+        let mut app = create_app_with_editor_content(vec!["scratch".to_string()]);
+        run_command(&mut app, &format!(":e {}", a));
+        app.active_area = ActiveArea::Editor;
+        app.process_input_action(InputAction::Editor(EditorAction::WriteChar('x')));
+        run_command(&mut app, ":bp");
+
+        run_command(&mut app, ":q");
+        assert!(app.popup.is_some());
+        assert!(matches!(
+            app.pending_states.front(),
+            Some(PendingState::Quitting)
+        ));
+    }
+
+    #[test]
+    fn test_undo_history_survives_reopening_saved_file() {
+        let dir = TempDir::new().unwrap();
+        undo_file::set_test_undo_dir(Some(dir.path().join("undo")));
+        let path = write_file(&dir, "kept.txt", "a");
+        let mut config = Config::default();
+        config.editor.persistent_undo = true;
+
+        let mut app = App::new(config.clone(), AppLaunchConfig::default());
+        run_command(&mut app, &format!(":e {}", path));
+        app.active_area = ActiveArea::Editor;
+        app.editor.cursor.x = 1;
+        app.process_input_action(InputAction::Editor(EditorAction::WriteChar('b')));
+        run_command(&mut app, ":w!");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "ab");
+
+        let mut reopened = App::new(config, AppLaunchConfig::default());
+        run_command(&mut reopened, &format!(":e {}", path));
+        assert!(!reopened.content_modified);
+        reopened.active_area = ActiveArea::Editor;
+        reopened.process_input_action(InputAction::Editor(EditorAction::UNDO));
+        assert_eq!(reopened.editor.editor_content, vec!["a"]);
+        undo_file::set_test_undo_dir(None);
+    }
+
+    #[test]
+    fn test_line_endings_and_final_newline_are_kept_on_save() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(&dir, "dos.txt", "one\r\ntwo\r\n");
+        let mut app = App::new(Config::default(), AppLaunchConfig::default());
+        run_command(&mut app, &format!(":e {}", path));
+        assert_eq!(app.editor.editor_content, vec!["one", "two"]);
+        assert_eq!(app.editor.file_format.line_ending, LineEnding::Dos);
+
+        // unchanged text matches the file, so there is nothing to confirm
+        run_command(&mut app, ":w");
+        assert!(app.popup.is_none());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one\r\ntwo\r\n");
+
+        app.active_area = ActiveArea::Editor;
+        app.editor.cursor.y = 1;
+        app.editor.cursor.x = 3;
+        app.process_input_action(InputAction::Editor(EditorAction::WriteChar('!')));
+        run_command(&mut app, ":w!");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one\r\ntwo!\r\n");
+
+        let unended = write_file(&dir, "unended.txt", "a\nb");
+        run_command(&mut app, &format!(":e {}", unended));
+        run_command(&mut app, ":w!");
+        assert_eq!(fs::read_to_string(&unended).unwrap(), "a\nb");
+    }
+
+    #[test]
+    fn test_set_fileformat_converts_on_save() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(&dir, "unix.txt", "one\ntwo\n");
+        let mut app = App::new(Config::default(), AppLaunchConfig::default());
+        run_command(&mut app, &format!(":e {}", path));
+
+        run_command(&mut app, ":set ff=dos noeol");
+        assert!(app.content_modified);
+        run_command(&mut app, ":w!");
+        assert!(!app.content_modified);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one\r\ntwo");
+
+        // converting back to the saved format leaves nothing to save
+        run_command(&mut app, ":set fileformat=unix eol");
+        assert!(app.content_modified);
+        run_command(&mut app, ":set ff=dos noeol");
+        assert!(!app.content_modified);
+
+        // a bad option sets none of them
+        app.popup = None;
+        run_command(&mut app, ":set eol ff=mac");
+        assert!(app.popup.is_some());
+        assert!(!app.editor.file_format.final_newline);
+    }
+}
+
+#[cfg(test)]
+mod integration_split_tests {
+    use calliglyph::app_config::AppLaunchConfig;
+    use calliglyph::config::Config;
+    use calliglyph::core::app::*;
+    use calliglyph::input::actions::{Direction, EditorAction, InputAction, Motion, SplitAction};
+    use std::fs;
+    use tempfile::TempDir;
+
+    //init functions
+    fn create_app_with_lines(count: usize) -> App {
+        let mut app = App::new(Config::default(), AppLaunchConfig::default());
+        app.editor.editor_content = (0..count).map(|i| format!("line {i}")).collect();
+        app
+    }
+
+    fn run_command(app: &mut App, input: &str) {
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = input.to_string();
+        app.process_input_action(InputAction::ENTER);
+        app.active_area = ActiveArea::Editor;
+    }
+
+    fn move_down(app: &mut App, times: usize) {
+        for _ in 0..times {
+            app.process_input_action(InputAction::Editor(EditorAction::MoveCursor(Motion::Down)));
+        }
+    }
+
+    #[test]
+    fn test_vsplit_views_keep_own_cursor_on_shared_buffer() {
+        let mut app = create_app_with_lines(10);
+        move_down(&mut app, 5);
+
+        run_command(&mut app, ":vsplit");
+        assert_eq!(app.splits.count(), 2);
+        assert_eq!(app.editor.cursor.y, 5);
+
+        move_down(&mut app, 2);
+        app.process_input_action(InputAction::Split(SplitAction::Focus(Direction::Left)));
+        assert_eq!(app.editor.cursor.y, 5);
+
+        app.process_input_action(InputAction::Split(SplitAction::Focus(Direction::Right)));
+        assert_eq!(app.editor.cursor.y, 7);
+    }
+
+    #[test]
+    fn test_edits_show_in_all_views_of_buffer() {
+        let mut app = create_app_with_lines(3);
+        run_command(&mut app, ":split");
+        app.process_input_action(InputAction::Editor(EditorAction::WriteChar('x')));
+
+        app.process_input_action(InputAction::Split(SplitAction::Focus(Direction::Up)));
+        assert_eq!(app.editor.editor_content[0], "xline 0");
+        let other = app.splits.view_ids()[1];
+        assert_eq!(app.view_editor(other).unwrap().editor_content[0], "xline 0");
+    }
+
+    #[test]
+    fn test_split_with_file_shows_other_buffer() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("other.txt");
+        fs::write(&path, "other").unwrap();
+        let mut app = create_app_with_lines(2);
+
+        run_command(&mut app, &format!(":vs {}", path.display()));
+        assert_eq!(app.editor.editor_content, vec!["other"]);
+        assert_eq!(app.buffers.count(), 2);
+
+        app.process_input_action(InputAction::Split(SplitAction::FocusNext));
+        assert_eq!(app.editor.editor_content, vec!["line 0", "line 1"]);
+        let other = app.splits.view_ids()[1];
+        assert_eq!(
+            app.view_editor(other).unwrap().editor_content,
+            vec!["other"]
+        );
+    }
+
+    #[test]
+    fn test_close_split_returns_focus_and_refuses_last_view() {
+        let mut app = create_app_with_lines(10);
+        run_command(&mut app, ":sp");
+        move_down(&mut app, 4);
+
+        app.process_input_action(InputAction::Split(SplitAction::Close));
+        assert_eq!(app.splits.count(), 1);
+        assert_eq!(app.editor.cursor.y, 0);
+
+        run_command(&mut app, ":close");
+        assert_eq!(app.splits.count(), 1);
+        assert!(app.popup.is_some());
+    }
+
+    #[test]
+    fn test_only_closes_other_views() {
+        let mut app = create_app_with_lines(2);
+        run_command(&mut app, ":sp");
+        run_command(&mut app, ":vs");
+        assert_eq!(app.splits.count(), 3);
+
+        run_command(&mut app, ":only");
+        assert_eq!(app.splits.count(), 1);
+        assert!(app.popup.is_none());
+    }
+
+    #[test]
+    fn test_deleting_buffer_moves_views_showing_it() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("other.txt");
+        fs::write(&path, "other").unwrap();
+        let mut app = create_app_with_lines(2);
+        run_command(&mut app, ":vs");
+        run_command(&mut app, &format!(":e {}", path.display()));
+        run_command(&mut app, ":sp");
+
+        // both right side views show other.txt, deleting it leaves the first buffer
+        run_command(&mut app, ":bd");
+        assert_eq!(app.buffers.count(), 1);
+        for id in app.splits.view_ids() {
+            assert_eq!(app.view_buffer_index(id), Some(0));
+            assert_eq!(
+                app.view_editor(id).unwrap().editor_content,
+                vec!["line 0", "line 1"]
+            );
+        }
+    }
+}
+
+mod integration_modal_tests {
+    use calliglyph::app_config::AppLaunchConfig;
+    use calliglyph::config::Config;
+    use calliglyph::core::app::*;
+    use calliglyph::core::editor::modal::Mode;
+    use calliglyph::input::actions::{EditorAction, InputAction};
+
+    //init functions
+    fn create_modal_app(lines: Vec<&str>) -> App {
+        let mut config = Config::default();
+        config.editor.modal_editing = true;
+        let mut app = App::new(config, AppLaunchConfig::default());
+        app.editor.editor_content = lines.into_iter().map(String::from).collect();
+        app
+    }
+
+    fn type_keys(app: &mut App, keys: &str) {
+        for c in keys.chars() {
+            app.process_input_action(InputAction::Editor(EditorAction::WriteChar(c)));
+        }
+    }
+
+    #[test]
+    fn test_escape_returns_to_normal_mode_before_leaving_editor() {
+        let mut app = create_modal_app(vec!["text"]);
+        assert_eq!(app.editor.mode(), Some(Mode::Normal));
+
+        type_keys(&mut app, "ihi ");
+        assert_eq!(app.editor.mode(), Some(Mode::Insert));
+        assert_eq!(app.editor.editor_content, vec!["hi text"]);
+
+        app.process_input_action(InputAction::ToggleActiveArea);
+        assert_eq!(app.editor.mode(), Some(Mode::Normal));
+        assert_eq!(app.active_area, ActiveArea::Editor);
+
+        app.process_input_action(InputAction::ToggleActiveArea);
+        assert_eq!(app.active_area, ActiveArea::CommandLine);
+    }
+
+    #[test]
+    fn test_colon_in_normal_mode_starts_command() {
+        let mut app = create_modal_app(vec!["text"]);
+        type_keys(&mut app, ":");
+        assert_eq!(app.active_area, ActiveArea::CommandLine);
+        assert_eq!(app.command_line.input, ":");
+        assert_eq!(app.editor.editor_content, vec!["text"]);
+    }
+
+    #[test]
+    fn test_modeless_editing_is_default() {
+        let mut app = App::new(Config::default(), AppLaunchConfig::default());
+        assert_eq!(app.editor.mode(), None);
+        type_keys(&mut app, "dw");
+        assert_eq!(app.editor.editor_content, vec!["dw"]);
+    }
+}
+
+mod integration_comment_tests {
+    use calliglyph::app_config::AppLaunchConfig;
+    use calliglyph::config::Config;
+    use calliglyph::core::app::*;
+    use calliglyph::input::actions::{EditorAction, InputAction};
+    use std::path::PathBuf;
+
+    fn create_app_for_file(name: &str, lines: Vec<&str>) -> App {
+        let mut app = App::new(Config::default(), AppLaunchConfig::default());
+        app.editor.editor_content = lines.into_iter().map(String::from).collect();
+        app.file_path = Some(PathBuf::from(name));
+        app
+    }
+
+    #[test]
+    fn test_toggle_comment_uses_tokens_of_file_extension() {
+        let mut app = create_app_for_file("script.py", vec!["    print(1)"]);
+        app.process_input_action(InputAction::Editor(EditorAction::ToggleComment));
+        assert_eq!(app.editor.editor_content, vec!["    # print(1)"]);
+        assert!(app.content_modified);
+
+        app.process_input_action(InputAction::Editor(EditorAction::ToggleComment));
+        assert_eq!(app.editor.editor_content, vec!["    print(1)"]);
+
+        let mut app = create_app_for_file("main.rs", vec!["let x = 1;"]);
+        app.process_input_action(InputAction::Editor(EditorAction::ToggleComment));
+        assert_eq!(app.editor.editor_content, vec!["// let x = 1;"]);
+    }
+
+    #[test]
+    fn test_toggle_comment_without_known_file_type_changes_nothing() {
+        let mut app = create_app_for_file("notes.unknown", vec!["text"]);
+        app.process_input_action(InputAction::Editor(EditorAction::ToggleComment));
+        assert_eq!(app.editor.editor_content, vec!["text"]);
+    }
+}
+
+mod integration_text_command_tests {
+    use calliglyph::app_config::AppLaunchConfig;
+    use calliglyph::config::Config;
+    use calliglyph::core::app::*;
+    use calliglyph::core::cursor::CursorPosition;
+    use calliglyph::input::actions::InputAction;
+
+    fn create_app_with_editor_content(lines: Vec<&str>) -> App {
+        let mut app = App::new(Config::default(), AppLaunchConfig::default());
+        app.editor.editor_content = lines.into_iter().map(String::from).collect();
+        app
+    }
+
+    fn run_command(app: &mut App, input: &str) {
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = input.to_string();
+        app.process_input_action(InputAction::ENTER);
+    }
+
+    #[test]
+    fn test_sort_with_options_marks_buffer_modified() {
+        let mut app = create_app_with_editor_content(vec!["10 b", "9 a", "10 b", "1 c"]);
+        run_command(&mut app, ":sort nu");
+        assert!(app.popup.is_none());
+        assert_eq!(app.editor.editor_content, vec!["1 c", "9 a", "10 b"]);
+        assert!(app.content_modified);
+
+        run_command(&mut app, ":sort r");
+        assert_eq!(app.editor.editor_content, vec!["9 a", "10 b", "1 c"]);
+    }
+
+    #[test]
+    fn test_sort_with_unknown_option_fails() {
+        let mut app = create_app_with_editor_content(vec!["b", "a"]);
+        run_command(&mut app, ":sort x");
+        assert!(app.popup.is_some());
+        assert_eq!(app.editor.editor_content, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_uniq_and_reverse_on_selected_lines() {
+        let mut app = create_app_with_editor_content(vec!["a", "a", "b", "c"]);
+        app.editor.text_selection_start = Some(CursorPosition { x: 0, y: 0 });
+        app.editor.text_selection_end = Some(CursorPosition { x: 0, y: 3 });
+        run_command(&mut app, ":uniq");
+        assert_eq!(app.editor.editor_content, vec!["a", "b", "c"]);
+
+        run_command(&mut app, ":reverse");
+        assert_eq!(app.editor.editor_content, vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_case_commands() {
+        let mut app = create_app_with_editor_content(vec!["fn getUserName() {}"]);
+        run_command(&mut app, ":snake");
+        assert_eq!(app.editor.editor_content, vec!["fn get_user_name() {}"]);
+        run_command(&mut app, ":camel");
+        assert_eq!(app.editor.editor_content, vec!["fn getUserName() {}"]);
+        run_command(&mut app, ":upper");
+        assert_eq!(app.editor.editor_content, vec!["FN GETUSERNAME() {}"]);
+        run_command(&mut app, ":title");
+        assert_eq!(app.editor.editor_content, vec!["Fn Getusername() {}"]);
+        run_command(&mut app, ":lower");
+        assert_eq!(app.editor.editor_content, vec!["fn getusername() {}"]);
+    }
+}
+
+mod integration_goto_tests {
+    use calliglyph::app_config::AppLaunchConfig;
+    use calliglyph::config::Config;
+    use calliglyph::core::app::*;
+    use calliglyph::input::actions::{EditorAction, InputAction, PopupAction};
+
+    fn create_app_with_lines(count: usize) -> App {
+        let mut app = App::new(Config::default(), AppLaunchConfig::default());
+        app.editor.editor_content = (1..=count).map(|i| format!("line {i}")).collect();
+        app.editor.editor_height = 10;
+        app
+    }
+
+    fn run_command(app: &mut App, input: &str) {
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = input.to_string();
+        app.process_input_action(InputAction::ENTER);
+    }
+
+    #[test]
+    fn test_goto_commands() {
+        let mut app = create_app_with_lines(100);
+        run_command(&mut app, ":42");
+        assert_eq!(app.editor.cursor.y, 41);
+        assert_eq!(app.editor.scroll_offset, 36);
+
+        run_command(&mut app, ":goto 3:4");
+        assert_eq!((app.editor.cursor.x, app.editor.cursor.y), (3, 2));
+
+        run_command(&mut app, ":+10");
+        assert_eq!(app.editor.cursor.y, 12);
+        run_command(&mut app, ":goto -2");
+        assert_eq!(app.editor.cursor.y, 10);
+        assert!(app.popup.is_none());
+    }
+
+    #[test]
+    fn test_goto_without_valid_line_fails() {
+        let mut app = create_app_with_lines(5);
+        run_command(&mut app, ":goto");
+        assert!(app.popup.is_some());
+        assert_eq!(app.editor.cursor.y, 0);
+    }
+
+    #[test]
+    fn test_goto_prompt_jumps_to_typed_line() {
+        let mut app = create_app_with_lines(100);
+        app.process_input_action(InputAction::Editor(EditorAction::GotoLine));
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        for c in "60:3".chars() {
+            app.process_input_action(InputAction::Popup(PopupAction::WriteChar(c)));
+        }
+        app.process_input_action(InputAction::ENTER);
+
+        assert!(app.popup.is_none());
+        assert!(app.pending_states.is_empty());
+        assert_eq!(app.active_area, ActiveArea::Editor);
+        assert_eq!((app.editor.cursor.x, app.editor.cursor.y), (2, 59));
+    }
+
+    #[test]
+    fn test_goto_prompt_cancelled_with_escape() {
+        let mut app = create_app_with_lines(100);
+        app.process_input_action(InputAction::Editor(EditorAction::GotoLine));
+        app.process_input_action(InputAction::Popup(PopupAction::WriteChar('9')));
+        app.process_input_action(InputAction::Popup(PopupAction::Close));
+
+        assert!(app.popup.is_none());
+        assert!(app.pending_states.is_empty());
+        assert_eq!(app.editor.cursor.y, 0);
+    }
+}
+
+mod integration_marks_tests {
+    use calliglyph::app_config::AppLaunchConfig;
+    use calliglyph::config::Config;
+    use calliglyph::core::app::*;
+    use calliglyph::input::actions::{EditorAction, InputAction};
+
+    fn create_app_with_lines(count: usize) -> App {
+        let mut app = App::new(Config::default(), AppLaunchConfig::default());
+        app.editor.editor_content = (1..=count).map(|i| format!("line {i}")).collect();
+        app.editor.editor_height = 10;
+        app
+    }
+
+    fn run_command(app: &mut App, input: &str) {
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = input.to_string();
+        app.process_input_action(InputAction::ENTER);
+    }
+
+    #[test]
+    fn test_mark_follows_lines_inserted_above_it() {
+        let mut app = create_app_with_lines(20);
+        run_command(&mut app, ":goto 10:3");
+        run_command(&mut app, ":mark a");
+        run_command(&mut app, ":1");
+
+        app.active_area = ActiveArea::Editor;
+        app.process_input_action(InputAction::Editor(EditorAction::DuplicateLines));
+        run_command(&mut app, ":'a");
+        assert!(app.popup.is_none());
+        assert_eq!((app.editor.cursor.x, app.editor.cursor.y), (2, 10));
+    }
+
+    #[test]
+    fn test_marks_list_and_delete() {
+        let mut app = create_app_with_lines(5);
+        run_command(&mut app, ":mark b");
+        run_command(&mut app, ":marks");
+        assert!(app.popup.is_some());
+        app.close_popup();
+
+        run_command(&mut app, ":delmarks b");
+        assert_eq!(app.editor.editor_content.mark('b'), None);
+        run_command(&mut app, ":'b");
+        assert!(app.popup.is_some());
+    }
+
+    #[test]
+    fn test_invalid_mark_name_fails() {
+        let mut app = create_app_with_lines(5);
+        run_command(&mut app, ":mark ?");
+        assert!(app.popup.is_some());
+        assert_eq!(app.editor.editor_content.marks().count(), 0);
+    }
+
+    #[test]
+    fn test_jump_back_and_forward_actions() {
+        let mut app = create_app_with_lines(100);
+        run_command(&mut app, ":50");
+        run_command(&mut app, ":80");
+
+        app.active_area = ActiveArea::Editor;
+        app.process_input_action(InputAction::Editor(EditorAction::JumpBack));
+        assert_eq!(app.editor.cursor.y, 49);
+        app.process_input_action(InputAction::Editor(EditorAction::JumpBack));
+        assert_eq!(app.editor.cursor.y, 0);
+        app.process_input_action(InputAction::Editor(EditorAction::JumpForward));
+        assert_eq!(app.editor.cursor.y, 49);
+    }
+}
+
+mod integration_macro_tests {
+    use calliglyph::app_config::AppLaunchConfig;
+    use calliglyph::config::Config;
+    use calliglyph::core::app::*;
+    use calliglyph::input::actions::{EditorAction, InputAction, Motion};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn create_app_with_lines(lines: Vec<&str>) -> App {
+        let mut app = App::new(Config::default(), AppLaunchConfig::default());
+        app.editor.editor_content = lines.into_iter().map(String::from).collect();
+        app.editor.editor_height = 10;
+        app
+    }
+
+    fn run_command(app: &mut App, input: &str) {
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = input.to_string();
+        app.process_input_action(InputAction::ENTER);
+    }
+
+    fn editor_action(app: &mut App, action: EditorAction) {
+        app.active_area = ActiveArea::Editor;
+        app.process_input_action(InputAction::Editor(action));
+    }
+
+    /// records "- " at the line start and a move down into register q
+    fn record_bullet_macro(app: &mut App) {
+        editor_action(app, EditorAction::ToggleMacroRecording);
+        assert_eq!(app.macros.recording(), Some('q'));
+        editor_action(app, EditorAction::MoveCursor(Motion::LineStart));
+        editor_action(app, EditorAction::WriteChar('-'));
+        editor_action(app, EditorAction::WriteChar(' '));
+        editor_action(app, EditorAction::MoveCursor(Motion::Down));
+        editor_action(app, EditorAction::ToggleMacroRecording);
+        assert_eq!(app.macros.recording(), None);
+    }
+
+    #[test]
+    fn test_macro_replays_with_count_as_one_undo_step() {
+        let mut app = create_app_with_lines(vec!["a", "b", "c", "d", "e"]);
+        record_bullet_macro(&mut app);
+        assert_eq!(app.editor.editor_content[0], "- a");
+
+        run_command(&mut app, ":play q 3");
+        assert!(app.popup.is_none());
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["- a", "- b", "- c", "- d", "e"]
+        );
+        assert!(app.content_modified);
+
+        editor_action(&mut app, EditorAction::UNDO);
+        assert_eq!(app.editor.editor_content, vec!["- a", "b", "c", "d", "e"]);
+
+        // the play key replays the macro used last
+        app.editor.cursor.y = 4;
+        editor_action(&mut app, EditorAction::PlayMacro);
+        assert_eq!(app.editor.editor_content[4], "- e");
+    }
+
+    #[test]
+    fn test_record_and_play_commands() {
+        let mut app = create_app_with_lines(vec!["x", "y", "z"]);
+        run_command(&mut app, ":record a");
+        editor_action(&mut app, EditorAction::WriteChar('!'));
+        editor_action(&mut app, EditorAction::MoveCursor(Motion::Down));
+        run_command(&mut app, ":record");
+        assert_eq!(app.macros.recording(), None);
+
+        app.editor.cursor.x = 0;
+        run_command(&mut app, ":@a");
+        assert_eq!(app.editor.editor_content, vec!["!x", "!y", "z"]);
+
+        run_command(&mut app, ":macros");
+        assert!(app.popup.is_some());
+    }
+
+    #[test]
+    fn test_playing_unknown_macro_fails() {
+        let mut app = create_app_with_lines(vec!["x"]);
+        run_command(&mut app, ":play z");
+        assert!(app.popup.is_some());
+        app.close_popup();
+        run_command(&mut app, ":play ab");
+        assert!(app.popup.is_some());
+    }
+
+    #[test]
+    fn test_saved_macro_is_loaded_on_next_start() {
+        let temp_path = PathBuf::from("temp_macro_config.toml");
+        Config::set_test_config_path(Some(temp_path.clone()));
+
+        let mut app = create_app_with_lines(vec!["a", "b"]);
+        record_bullet_macro(&mut app);
+        run_command(&mut app, ":savemacro q");
+        assert!(app.popup.is_none());
+
+        let mut app = App::new(Config::load(), AppLaunchConfig::default());
+        app.editor.editor_content = vec!["a".to_string(), "b".to_string(), "c".to_string()].into();
+        run_command(&mut app, ":play q 2");
+        assert_eq!(app.editor.editor_content, vec!["- a", "- b", "c"]);
+
+        run_command(&mut app, ":delmacro q");
+        assert!(Config::load().macros.is_empty());
+
+        fs::remove_file(&temp_path).expect("Failed to remove test config file");
+        Config::set_test_config_path(None);
+    }
+}
+
+mod integration_register_tests {
+    use calliglyph::app_config::AppLaunchConfig;
+    use calliglyph::config::Config;
+    use calliglyph::core::app::*;
+    use calliglyph::core::cursor::CursorPosition;
+    use calliglyph::input::actions::{Direction, EditorAction, InputAction, PopupAction};
+    use calliglyph::ui::popups::popup::PopupType;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_app_with_lines(lines: Vec<&str>) -> App {
+        let mut app = App::new(Config::default(), AppLaunchConfig::default());
+        app.editor.editor_content = lines.into_iter().map(String::from).collect();
+        app.editor.editor_height = 10;
+        app
+    }
+
+    fn run_command(app: &mut App, input: &str) {
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = input.to_string();
+        app.process_input_action(InputAction::ENTER);
+    }
+
+    fn select(app: &mut App, y: usize, from: usize, to: usize) {
+        app.editor.text_selection_start = Some(CursorPosition { x: from, y });
+        app.editor.text_selection_end = Some(CursorPosition { x: to, y });
+    }
+
+    fn copy(app: &mut App, y: usize, from: usize, to: usize) {
+        select(app, y, from, to);
+        app.active_area = ActiveArea::Editor;
+        app.process_input_action(InputAction::Editor(EditorAction::COPY));
+    }
+
+    #[test]
+    fn test_yank_and_put_named_register() {
+        let mut app = create_app_with_lines(vec!["alpha beta", ""]);
+        select(&mut app, 0, 0, 5);
+        run_command(&mut app, ":yank a");
+        assert!(app.popup.is_none());
+        copy(&mut app, 0, 6, 10);
+
+        app.editor.cursor.y = 1;
+        run_command(&mut app, ":put a");
+        assert!(app.popup.is_none());
+        assert_eq!(app.editor.editor_content, vec!["alpha beta", "alpha"]);
+        assert!(app.content_modified);
+        // the clipboard still pastes what was copied last
+        assert_eq!(app.editor.clipboard.copied_text, vec!["beta"]);
+
+        run_command(&mut app, ":put b");
+        assert!(app.popup.is_some());
+        app.close_popup();
+        run_command(&mut app, ":yank 1");
+        assert!(app.popup.is_some());
+    }
+
+    #[test]
+    fn test_registers_command_lists_registers() {
+        let mut app = create_app_with_lines(vec!["alpha beta"]);
+        run_command(&mut app, ":reg");
+        assert!(app.popup.is_some(), "empty registers are reported");
+        app.close_popup();
+
+        copy(&mut app, 0, 0, 5);
+        run_command(&mut app, ":registers");
+        assert!(matches!(
+            app.popup.as_ref().map(|popup| popup.get_popup_type()),
+            Some(PopupType::Info)
+        ));
+    }
+
+    #[test]
+    fn test_clipboard_history_picker_pastes_pick() {
+        let mut app = create_app_with_lines(vec!["one two", ""]);
+        copy(&mut app, 0, 0, 3);
+        copy(&mut app, 0, 4, 7);
+        app.editor.cursor.y = 1;
+
+        app.process_input_action(InputAction::Editor(EditorAction::ClipboardHistory));
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        app.process_input_action(InputAction::Popup(PopupAction::MoveCursor(Direction::Down)));
+        app.process_input_action(InputAction::ENTER);
+
+        assert!(app.popup.is_none());
+        assert!(app.pending_states.is_empty());
+        assert_eq!(app.editor.editor_content, vec!["one two", "one"]);
+        assert_eq!(app.editor.clipboard.copied_text, vec!["one"]);
+
+        // cancelling pastes nothing
+        app.process_input_action(InputAction::Editor(EditorAction::ClipboardHistory));
+        app.process_input_action(InputAction::Popup(PopupAction::Close));
+        assert!(app.popup.is_none());
+        assert!(app.pending_states.is_empty());
+        assert_eq!(app.editor.editor_content, vec!["one two", "one"]);
+    }
+
+    #[test]
+    fn test_clipboard_is_shared_across_buffers() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("other.txt");
+        fs::write(&path, "").unwrap();
+        let mut app = create_app_with_lines(vec!["alpha beta"]);
+        copy(&mut app, 0, 0, 5);
+        select(&mut app, 0, 6, 10);
+        run_command(&mut app, ":yank b");
+
+        run_command(&mut app, &format!(":e {}", path.display()));
+        assert_eq!(app.editor.editor_content, vec![""]);
+        app.active_area = ActiveArea::Editor;
+        app.process_input_action(InputAction::Editor(EditorAction::PASTE));
+        run_command(&mut app, ":put b");
+        assert_eq!(app.editor.editor_content, vec!["betaalpha"]);
+
+        run_command(&mut app, ":bp");
+        assert_eq!(app.editor.clipboard.copied_text, vec!["alpha"]);
+        assert_eq!(app.editor.clipboard.history().count(), 1);
+    }
+}
+
+mod integration_undo_tree_tests {
+    use calliglyph::app_config::AppLaunchConfig;
+    use calliglyph::config::Config;
+    use calliglyph::core::app::*;
+    use calliglyph::input::actions::{Direction, EditorAction, InputAction, PopupAction};
+    use calliglyph::ui::popups::popup::PopupType;
+
+    fn create_app_with_lines(lines: Vec<&str>) -> App {
+        // chars typed are states of their own, to branch between them
+        let mut config = Config::default();
+        config.editor.undo_group_idle_ms = 0;
+        let mut app = App::new(config, AppLaunchConfig::default());
+        app.editor.editor_content = lines.into_iter().map(String::from).collect();
+        app.editor.editor_height = 10;
+        app
+    }
+
+    fn run_command(app: &mut App, input: &str) {
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = input.to_string();
+        app.process_input_action(InputAction::ENTER);
+    }
+
+    fn editor_action(app: &mut App, action: EditorAction) {
+        app.active_area = ActiveArea::Editor;
+        app.process_input_action(InputAction::Editor(action));
+    }
+
+    /// types ab, undoes b and types c, leaving states "", a, ab and ac
+    fn branch(app: &mut App) {
+        editor_action(app, EditorAction::WriteChar('a'));
+        editor_action(app, EditorAction::WriteChar('b'));
+        editor_action(app, EditorAction::UNDO);
+        editor_action(app, EditorAction::WriteChar('c'));
+        assert_eq!(app.editor.editor_content, vec!["ac"]);
+    }
+
+    #[test]
+    fn test_undo_tree_popup_goes_to_picked_branch() {
+        let mut app = create_app_with_lines(vec![""]);
+        branch(&mut app);
+
+        editor_action(&mut app, EditorAction::UndoTree);
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        // rows are "", a, ab and ac with ac selected, ab is one up
+        app.process_input_action(InputAction::Popup(PopupAction::MoveCursor(Direction::Up)));
+        app.process_input_action(InputAction::ENTER);
+
+        assert!(app.popup.is_none());
+        assert!(app.pending_states.is_empty());
+        assert_eq!(app.editor.editor_content, vec!["ab"]);
+        assert!(app.content_modified);
+    }
+
+    #[test]
+    fn test_undo_command_goes_to_state_by_number() {
+        let mut app = create_app_with_lines(vec![""]);
+        branch(&mut app);
+
+        run_command(&mut app, ":undo 2");
+        assert!(app.popup.is_none());
+        assert_eq!(app.editor.editor_content, vec!["ab"]);
+        run_command(&mut app, ":undo 0");
+        assert_eq!(app.editor.editor_content, vec![""]);
+        assert!(!app.content_modified);
+        run_command(&mut app, ":redo");
+        assert_eq!(app.editor.editor_content, vec!["a"]);
+
+        run_command(&mut app, ":undo 42");
+        assert!(app.popup.is_some());
+    }
+
+    #[test]
+    fn test_older_state_key_steps_across_branches() {
+        let mut app = create_app_with_lines(vec![""]);
+        branch(&mut app);
+        editor_action(&mut app, EditorAction::OlderState);
+        assert_eq!(app.editor.editor_content, vec!["ab"]);
+        editor_action(&mut app, EditorAction::NewerState);
+        assert_eq!(app.editor.editor_content, vec!["ac"]);
+    }
+
+    #[test]
+    fn test_earlier_previews_diff_then_applies() {
+        let mut app = create_app_with_lines(vec![""]);
+        branch(&mut app);
+
+        run_command(&mut app, ":earlier 2");
+        assert!(matches!(
+            app.pending_states.front(),
+            Some(PendingState::TimeTravel(1))
+        ));
+        // the text is left alone until the diff is accepted
+        assert_eq!(app.editor.editor_content, vec!["ac"]);
+        app.process_input_action(InputAction::ENTER);
+        assert!(app.popup.is_none());
+        assert_eq!(app.editor.editor_content, vec!["a"]);
+
+        run_command(&mut app, ":later 1m");
+        app.process_input_action(InputAction::Popup(PopupAction::Close));
+        assert!(app.popup.is_none());
+        assert!(app.pending_states.is_empty());
+        assert_eq!(app.editor.editor_content, vec!["a"]);
+    }
+
+    #[test]
+    fn test_earlier_by_saves_and_bad_counts() {
+        let mut app = create_app_with_lines(vec![""]);
+        run_command(&mut app, ":earlier 1f");
+        assert!(matches!(
+            app.popup.as_ref().map(|p| p.get_popup_type()),
+            Some(PopupType::Error)
+        ));
+        app.close_popup();
+
+        editor_action(&mut app, EditorAction::WriteChar('a'));
+        app.editor.undo_redo_manager.mark_saved();
+        editor_action(&mut app, EditorAction::WriteChar('b'));
+        run_command(&mut app, ":earlier 1f");
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(app.editor.editor_content, vec!["a"]);
+
+        run_command(&mut app, ":earlier 10x");
+        assert!(matches!(
+            app.popup.as_ref().map(|p| p.get_popup_type()),
+            Some(PopupType::Error)
+        ));
+    }
+}
@@ -1,6 +1,6 @@
-use calliglyph::config::{Config, KeymapConfig};
+use calliglyph::config::{ClipboardProviderKind, Config, KeymapConfig};
 use calliglyph::errors::config_errors::ConfigError;
-use calliglyph::input::actions::{DebugAction, Direction, EditorAction, InputAction};
+use calliglyph::input::actions::{DebugAction, EditorAction, InputAction, Motion};
 use crossterm::event::{KeyCode, KeyModifiers};
 use std::{fs, path::PathBuf};
 
@@ -37,10 +37,74 @@ fn test_runtime_maps_integrity() {
     let up = (KeyModifiers::empty(), KeyCode::Up);
     assert_eq!(
         runtime_maps.editor.get(&up),
+        Some(&InputAction::Editor(EditorAction::MoveCursor(Motion::Up))),
+        "Up key binding failed."
+    );
+
+    // Check motions beyond single steps and their selection variants
+    let ctrl_right = (KeyModifiers::CONTROL, KeyCode::Right);
+    assert_eq!(
+        runtime_maps.editor.get(&ctrl_right),
         Some(&InputAction::Editor(EditorAction::MoveCursor(
-            Direction::Up
+            Motion::WordRight
         ))),
-        "Up key binding failed."
+        "Ctrl+Right binding failed."
+    );
+    let shift_home = (KeyModifiers::SHIFT, KeyCode::Home);
+    assert_eq!(
+        runtime_maps.editor.get(&shift_home),
+        Some(&InputAction::Editor(EditorAction::MoveSelectionCursor(
+            Motion::LineStart
+        ))),
+        "Shift+Home binding failed."
+    );
+    let shift_tab = (KeyModifiers::SHIFT, KeyCode::BackTab);
+    assert_eq!(
+        runtime_maps.editor.get(&shift_tab),
+        Some(&InputAction::Editor(EditorAction::Outdent)),
+        "Shift+BackTab binding failed."
+    );
+    let ctrl_b = (KeyModifiers::CONTROL, KeyCode::Char('b'));
+    assert_eq!(
+        runtime_maps.editor.get(&ctrl_b),
+        Some(&InputAction::Editor(EditorAction::JumpToMatchingBracket)),
+        "Ctrl+b binding failed."
+    );
+    let move_down = (KeyModifiers::CONTROL | KeyModifiers::SHIFT, KeyCode::Down);
+    assert_eq!(
+        runtime_maps.editor.get(&move_down),
+        Some(&InputAction::Editor(EditorAction::MoveLinesDown)),
+        "Ctrl+Shift+Down binding failed."
+    );
+    let ctrl_g = (KeyModifiers::CONTROL, KeyCode::Char('g'));
+    assert_eq!(
+        runtime_maps.editor.get(&ctrl_g),
+        Some(&InputAction::Editor(EditorAction::GotoLine)),
+        "Ctrl+g binding failed."
+    );
+    let ctrl_o = (KeyModifiers::CONTROL, KeyCode::Char('o'));
+    assert_eq!(
+        runtime_maps.editor.get(&ctrl_o),
+        Some(&InputAction::Editor(EditorAction::JumpBack)),
+        "Ctrl+o binding failed."
+    );
+    let ctrl_r = (KeyModifiers::CONTROL, KeyCode::Char('r'));
+    assert_eq!(
+        runtime_maps.editor.get(&ctrl_r),
+        Some(&InputAction::Editor(EditorAction::ToggleMacroRecording)),
+        "Ctrl+r binding failed."
+    );
+    let alt_v = (KeyModifiers::ALT, KeyCode::Char('v'));
+    assert_eq!(
+        runtime_maps.editor.get(&alt_v),
+        Some(&InputAction::Editor(EditorAction::ClipboardHistory)),
+        "Alt+v binding failed."
+    );
+    let alt_u = (KeyModifiers::ALT, KeyCode::Char('u'));
+    assert_eq!(
+        runtime_maps.editor.get(&alt_u),
+        Some(&InputAction::Editor(EditorAction::UndoTree)),
+        "Alt+u binding failed."
     );
 
     // Check a debug key: q -> exit_debug
@@ -69,6 +133,40 @@ fn test_parsing_invalid_key_fails() {
     }
 }
 
+#[test]
+fn test_register_actions_take_register_name() {
+    let mut config = KeymapConfig::default();
+    config
+        .editor
+        .insert("Ctrl+Alt+a".to_string(), "copy_to_register:a".to_string());
+    config.editor.insert(
+        "Ctrl+Alt+p".to_string(),
+        "paste_from_register:3".to_string(),
+    );
+    let runtime_maps = config.build_runtime_maps().unwrap();
+    assert_eq!(
+        runtime_maps.editor.get(&(
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+            KeyCode::Char('a')
+        )),
+        Some(&InputAction::Editor(EditorAction::CopyToRegister('a')))
+    );
+    assert_eq!(
+        runtime_maps.editor.get(&(
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+            KeyCode::Char('p')
+        )),
+        Some(&InputAction::Editor(EditorAction::PasteFromRegister('3')))
+    );
+
+    // history entries can be pasted but not copied into
+    let mut bad_config = KeymapConfig::default();
+    bad_config
+        .editor
+        .insert("Ctrl+Alt+a".to_string(), "copy_to_register:3".to_string());
+    assert!(bad_config.build_runtime_maps().is_err());
+}
+
 #[test]
 fn test_parsing_invalid_action_fails() {
     let mut bad_config = KeymapConfig::default();
@@ -134,6 +232,7 @@ fn test_config_load_and_save_success() {
     // Set custom values on nested structs
     custom_config.editor.tab_width = 8;
     custom_config.editor.undo_history_limit = 500;
+    custom_config.editor.clipboard_provider = ClipboardProviderKind::Osc52;
 
     custom_config
         .keymaps
@@ -149,6 +248,10 @@ fn test_config_load_and_save_success() {
     // Assertions (Load): Check custom values
     assert_eq!(loaded_config.editor.tab_width, 8);
     assert_eq!(loaded_config.editor.undo_history_limit, 500);
+    assert_eq!(
+        loaded_config.editor.clipboard_provider,
+        ClipboardProviderKind::Osc52
+    );
 
     // Check if the custom keymap was loaded and mapped
     let _ = loaded_config.runtime_keymaps.as_ref().unwrap();
@@ -273,3 +376,33 @@ fn test_config_reload_success() {
     // Cleanup
     cleanup_test_path(&temp_path);
 }
+
+#[test]
+fn test_comment_tokens_are_merged_over_defaults() {
+    let temp_path = PathBuf::from("temp_comment_tokens_config.toml");
+    Config::set_test_config_path(Some(temp_path.clone()));
+
+    let content = r#"
+    [editor.comment_tokens.rs]
+    line = ";"
+
+    [editor.comment_tokens.nix]
+    line = ";"
+    "#;
+    fs::write(&temp_path, content).expect("Failed to write comment tokens");
+
+    let loaded_config = Config::load();
+    let tokens = &loaded_config.editor.comment_tokens;
+
+    // set entries replace or add theirs, the other languages keep the built-in ones
+    assert_eq!(tokens["rs"].line.as_deref(), Some(";"));
+    assert_eq!(tokens["rs"].block, None);
+    assert_eq!(tokens["nix"].line.as_deref(), Some(";"));
+    assert_eq!(tokens["py"].line.as_deref(), Some("#"));
+    assert_eq!(
+        tokens["css"].block,
+        Some(("/*".to_string(), "*/".to_string()))
+    );
+
+    cleanup_test_path(&temp_path);
+}
@@ -251,7 +251,7 @@ mod debug_snapshot_tests {
             ActiveArea::Editor,
             Cursor { x: 0, y: 0 },
             None,
-            vec!["test".to_string()],
+            vec!["test".to_string()].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -274,7 +274,7 @@ mod debug_snapshot_tests {
             Some(SnapshotTrigger::Error("test".to_string())),
             Cursor { x: 0, y: 0 },
             None,
-            vec![],
+            vec![].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -296,7 +296,7 @@ mod debug_snapshot_tests {
             Some(SnapshotTrigger::Error("test".to_string())),
             Cursor { x: 0, y: 0 },
             None,
-            vec![],
+            vec![].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -318,7 +318,7 @@ mod debug_snapshot_tests {
             Some(SnapshotTrigger::Command("test".to_string())),
             Cursor { x: 0, y: 0 },
             None,
-            vec![],
+            vec![].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -340,7 +340,7 @@ mod debug_snapshot_tests {
             None, // No trigger
             Cursor { x: 0, y: 0 },
             None,
-            vec![],
+            vec![].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -363,7 +363,7 @@ mod debug_snapshot_tests {
             Some(SnapshotTrigger::Error("test".to_string())),
             Cursor { x: 0, y: 0 },
             None,
-            vec![],
+            vec![].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -378,7 +378,7 @@ mod debug_snapshot_tests {
             Some(SnapshotTrigger::Manual),
             Cursor { x: 0, y: 0 },
             None,
-            vec![],
+            vec![].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -400,7 +400,7 @@ mod debug_snapshot_tests {
             None,
             Cursor { x: 0, y: 0 },
             None,
-            vec![],
+            vec![].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -415,7 +415,7 @@ mod debug_snapshot_tests {
             Some(SnapshotTrigger::Error("test".to_string())),
             Cursor { x: 0, y: 0 },
             None,
-            vec![],
+            vec![].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -436,7 +436,7 @@ mod debug_snapshot_tests {
                 ActiveArea::Editor,
                 Cursor { x: i, y: i },
                 None,
-                vec![format!("line {}", i)],
+                vec![format!("line {}", i)].into(),
                 0,
                 vec![],
                 VecDeque::new(),
@@ -461,7 +461,7 @@ mod debug_snapshot_tests {
             ActiveArea::Editor,
             Cursor { x: 0, y: 0 },
             None,
-            vec![],
+            vec![].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -483,7 +483,7 @@ mod debug_snapshot_tests {
             ActiveArea::CommandLine,
             Cursor { x: 5, y: 10 },
             None,
-            vec!["line1".to_string(), "line2".to_string()],
+            vec!["line1".to_string(), "line2".to_string()].into(),
             2,
             vec!["clip1".to_string()],
             VecDeque::new(),
@@ -793,7 +793,7 @@ mod debug_integration_tests {
             Some(SnapshotTrigger::Error("File not found".to_string())),
             Cursor { x: 0, y: 0 },
             None,
-            vec!["".to_string()],
+            vec!["".to_string()].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -808,7 +808,7 @@ mod debug_integration_tests {
             ActiveArea::Editor,
             Cursor { x: 5, y: 10 },
             None,
-            vec!["Hello".to_string()],
+            vec!["Hello".to_string()].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -835,7 +835,7 @@ mod debug_integration_tests {
             Some(SnapshotTrigger::Error("test".to_string())),
             Cursor { x: 0, y: 0 },
             None,
-            vec![],
+            vec![].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -851,7 +851,7 @@ mod debug_integration_tests {
             None,
             Cursor { x: 0, y: 0 },
             None,
-            vec![],
+            vec![].into(),
             0,
             vec![],
             VecDeque::new(),
@@ -872,7 +872,7 @@ mod debug_integration_tests {
                 ActiveArea::Editor,
                 Cursor { x: i, y: i },
                 None,
-                vec![format!("line {}", i)],
+                vec![format!("line {}", i)].into(),
                 0,
                 vec![],
                 VecDeque::new(),
@@ -4,7 +4,7 @@ mod editor_basic_tests {
     use calliglyph::core::cursor::CursorPosition;
     use calliglyph::core::editor::Editor;
     use calliglyph::errors::editor_errors::EditorError;
-    use calliglyph::input::actions::{Direction, EditorAction, InputAction};
+    use calliglyph::input::actions::{EditorAction, InputAction, Motion};
     use std::sync::Arc;
     trait EditorTestExt {
         fn handle_action_test(&mut self, action: InputAction) -> Result<(), EditorError>;
@@ -92,17 +92,13 @@ mod editor_basic_tests {
         editor.cursor.x = 6;
 
         editor
-            .handle_action_test(InputAction::Editor(EditorAction::MoveCursor(
-                Direction::Left,
-            )))
+            .handle_action_test(InputAction::Editor(EditorAction::MoveCursor(Motion::Left)))
             .unwrap();
         let left_x = editor.cursor.x;
         assert!(left_x < 6);
 
         editor
-            .handle_action_test(InputAction::Editor(EditorAction::MoveCursor(
-                Direction::Right,
-            )))
+            .handle_action_test(InputAction::Editor(EditorAction::MoveCursor(Motion::Right)))
             .unwrap();
         assert!(editor.cursor.x > left_x);
     }
@@ -113,14 +109,12 @@ mod editor_basic_tests {
         editor.cursor.y = 1;
 
         editor
-            .handle_action_test(InputAction::Editor(EditorAction::MoveCursor(Direction::Up)))
+            .handle_action_test(InputAction::Editor(EditorAction::MoveCursor(Motion::Up)))
             .unwrap();
         assert_eq!(editor.cursor.y, 0);
 
         editor
-            .handle_action_test(InputAction::Editor(EditorAction::MoveCursor(
-                Direction::Down,
-            )))
+            .handle_action_test(InputAction::Editor(EditorAction::MoveCursor(Motion::Down)))
             .unwrap();
         assert_eq!(editor.cursor.y, 1);
     }
@@ -160,20 +154,19 @@ mod editor_basic_tests {
         }
         assert_eq!(editor.editor_content[0], "abcdef");
 
-        // Undo all
-        for _ in 0..3 {
-            editor
-                .handle_action_test(InputAction::Editor(EditorAction::UNDO))
-                .unwrap();
-        }
+        // Typed in one go, the chars are undone together
+        editor
+            .handle_action_test(InputAction::Editor(EditorAction::UNDO))
+            .unwrap();
         assert_eq!(editor.editor_content[0], "abc");
+        assert!(editor
+            .handle_action_test(InputAction::Editor(EditorAction::UNDO))
+            .is_err());
 
         // Redo all
-        for _ in 0..3 {
-            editor
-                .handle_action_test(InputAction::Editor(EditorAction::REDO))
-                .unwrap();
-        }
+        editor
+            .handle_action_test(InputAction::Editor(EditorAction::REDO))
+            .unwrap();
         assert_eq!(editor.editor_content[0], "abcdef");
     }
 
@@ -228,7 +221,7 @@ mod editor_basic_tests {
         editor.cursor.y = 5;
 
         editor
-            .handle_action_test(InputAction::Editor(EditorAction::MoveCursor(Direction::Up)))
+            .handle_action_test(InputAction::Editor(EditorAction::MoveCursor(Motion::Up)))
             .unwrap();
         assert!(editor.scroll_offset <= 2);
     }
@@ -270,7 +263,16 @@ mod editor_basic_tests {
             .handle_action_test(InputAction::Editor(EditorAction::UNDO))
             .unwrap();
         assert_eq!(editor.editor_content[0], "Hello World");
-        assert_eq!(editor.cursor.x, 11); //end of line after world
+        // cursor and selection are back as they were before typing
+        assert_eq!(editor.cursor.x, 6);
+        assert_eq!(
+            editor.text_selection_start,
+            Some(CursorPosition { x: 6, y: 0 })
+        );
+        assert_eq!(
+            editor.text_selection_end,
+            Some(CursorPosition { x: 11, y: 0 })
+        );
 
         // Redo
         editor
@@ -646,7 +648,10 @@ mod editor_basic_tests {
             .unwrap();
         assert_eq!(editor.editor_content[0], "Hello World");
 
-        // New action: type 'Y' at a different position
+        // New action: type 'Y' at a different position, leaving the
+        // selection undo brought back
+        editor.text_selection_start = None;
+        editor.text_selection_end = None;
         editor.cursor.x = 0;
         editor
             .handle_action_test(InputAction::Editor(EditorAction::WriteChar('Y')))
@@ -897,7 +902,7 @@ mod editor_cut_tests {
 mod editor_scroll_tests {
     use calliglyph::config::EditorConfig;
     use calliglyph::core::editor::Editor;
-    use calliglyph::input::actions::{Direction, EditorAction, InputAction};
+    use calliglyph::input::actions::{EditorAction, InputAction, Motion};
     use std::sync::Arc;
 
     fn setup_editor(lines: Vec<&str>, scrolloff: u16, margin: u16) -> Editor {
@@ -962,10 +967,8 @@ mod editor_scroll_tests {
         }
 
         // This move should trigger a scroll update
-        ed.handle_input_action(InputAction::Editor(EditorAction::MoveCursor(
-            Direction::Down,
-        )))
-        .unwrap();
+        ed.handle_input_action(InputAction::Editor(EditorAction::MoveCursor(Motion::Down)))
+            .unwrap();
 
         assert_eq!(
             ed.scroll_offset, 1,
@@ -989,3 +992,298 @@ mod editor_scroll_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod editor_multi_cursor_tests {
+    use calliglyph::config::Config;
+    use calliglyph::core::editor::Editor;
+    use calliglyph::input::actions::{EditorAction, InputAction, Motion};
+    use std::sync::Arc;
+
+    /// Helper to create an editor with some starting text.
+    fn create_editor_with_content(lines: Vec<&str>) -> Editor {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = lines.into_iter().map(String::from).collect();
+        editor.editor_height = 10;
+        editor
+    }
+
+    fn act(editor: &mut Editor, action: EditorAction) {
+        editor
+            .handle_input_action(InputAction::Editor(action))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_typing_and_backspace_at_every_cursor() {
+        let mut editor = create_editor_with_content(vec!["one", "two", "three"]);
+        act(&mut editor, EditorAction::AddCursorBelow);
+        act(&mut editor, EditorAction::AddCursorBelow);
+        act(&mut editor, EditorAction::WriteChar('-'));
+        act(&mut editor, EditorAction::WriteChar(' '));
+        assert_eq!(
+            editor.editor_content.to_vec(),
+            vec!["- one", "- two", "- three"]
+        );
+
+        act(&mut editor, EditorAction::BACKSPACE);
+        assert_eq!(
+            editor.editor_content.to_vec(),
+            vec!["-one", "-two", "-three"]
+        );
+
+        // each keystroke is one undo step over all cursors
+        act(&mut editor, EditorAction::UNDO);
+        assert_eq!(
+            editor.editor_content.to_vec(),
+            vec!["- one", "- two", "- three"]
+        );
+        assert!(!editor.has_secondary_cursors());
+    }
+
+    #[test]
+    fn test_cursors_move_together() {
+        let mut editor = create_editor_with_content(vec!["abc", "abc"]);
+        act(&mut editor, EditorAction::AddCursorBelow);
+        act(&mut editor, EditorAction::MoveCursor(Motion::Right));
+        act(&mut editor, EditorAction::DELETE);
+        assert_eq!(editor.editor_content.to_vec(), vec!["ab", "ab"]);
+
+        // moving both onto the same line merges them
+        act(&mut editor, EditorAction::MoveCursor(Motion::Up));
+        assert!(!editor.has_secondary_cursors());
+    }
+
+    #[test]
+    fn test_next_match_copy_and_paste_per_cursor() {
+        let mut editor = create_editor_with_content(vec!["let a = 1;", "let b = 2;"]);
+        act(&mut editor, EditorAction::AddCursorAtNextMatch);
+        act(&mut editor, EditorAction::AddCursorAtNextMatch);
+        act(&mut editor, EditorAction::COPY);
+        assert_eq!(editor.clipboard.copied_text, vec!["let", "let"]);
+
+        act(&mut editor, EditorAction::PASTE);
+        assert_eq!(
+            editor.editor_content.to_vec(),
+            vec!["letlet a = 1;", "letlet b = 2;"]
+        );
+    }
+}
+
+mod editor_block_selection_tests {
+    use calliglyph::config::Config;
+    use calliglyph::core::editor::Editor;
+    use calliglyph::input::actions::{Direction, EditorAction, InputAction, Motion};
+    use std::sync::Arc;
+
+    fn create_editor_with_content(lines: Vec<&str>) -> Editor {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = lines.into_iter().map(String::from).collect();
+        editor.editor_height = 10;
+        editor
+    }
+
+    fn act(editor: &mut Editor, action: EditorAction) {
+        editor
+            .handle_input_action(InputAction::Editor(action))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_cut_and_paste_a_column_of_a_table() {
+        let mut editor = create_editor_with_content(vec!["a1 b1 c1", "a2 b2 c2", "a3 b3 c3"]);
+        act(&mut editor, EditorAction::MoveCursor(Motion::Right));
+        act(&mut editor, EditorAction::MoveCursor(Motion::Right));
+        act(&mut editor, EditorAction::MoveCursor(Motion::Right));
+        for _ in 0..3 {
+            act(
+                &mut editor,
+                EditorAction::MoveBlockSelection(Direction::Right),
+            );
+        }
+        act(
+            &mut editor,
+            EditorAction::MoveBlockSelection(Direction::Down),
+        );
+        act(
+            &mut editor,
+            EditorAction::MoveBlockSelection(Direction::Down),
+        );
+        act(&mut editor, EditorAction::CUT);
+        assert_eq!(
+            editor.editor_content.to_vec(),
+            vec!["a1 c1", "a2 c2", "a3 c3"]
+        );
+
+        // leaving the block and pasting puts the column back at the cursor
+        act(&mut editor, EditorAction::MoveCursor(Motion::Up));
+        act(&mut editor, EditorAction::MoveCursor(Motion::Up));
+        act(&mut editor, EditorAction::MoveCursor(Motion::Right));
+        act(&mut editor, EditorAction::MoveCursor(Motion::Right));
+        assert!(editor.block_selection.is_none());
+        act(&mut editor, EditorAction::PASTE);
+        assert_eq!(
+            editor.editor_content.to_vec(),
+            vec!["a1 c1b1 ", "a2 c2b2 ", "a3 c3b3 "]
+        );
+
+        act(&mut editor, EditorAction::UNDO);
+        assert_eq!(
+            editor.editor_content.to_vec(),
+            vec!["a1 c1", "a2 c2", "a3 c3"]
+        );
+    }
+}
+
+mod editor_indent_tests {
+    use calliglyph::config::EditorConfig;
+    use calliglyph::core::editor::Editor;
+    use calliglyph::input::actions::{EditorAction, InputAction, Motion};
+    use std::sync::Arc;
+
+    fn create_editor_with_content(lines: Vec<&str>) -> Editor {
+        let config = EditorConfig {
+            use_spaces: true,
+            tab_width: 2,
+            ..EditorConfig::default()
+        };
+        let mut editor = Editor::new(Arc::new(config));
+        editor.editor_content = lines.into_iter().map(String::from).collect();
+        editor.editor_height = 10;
+        editor
+    }
+
+    #[test]
+    fn test_enter_keeps_indent_and_indents_after_brace() {
+        let mut editor = create_editor_with_content(vec!["  fn main() {"]);
+        editor
+            .handle_input_action(InputAction::Editor(EditorAction::MoveCursor(
+                Motion::LineEnd,
+            )))
+            .unwrap();
+        editor.handle_input_action(InputAction::ENTER).unwrap();
+        editor
+            .handle_input_action(InputAction::Editor(EditorAction::WriteChar('x')))
+            .unwrap();
+        editor.handle_input_action(InputAction::ENTER).unwrap();
+        assert_eq!(
+            editor.editor_content.to_vec(),
+            vec!["  fn main() {", "    x", "    "]
+        );
+        assert_eq!((editor.cursor.x, editor.cursor.y), (4, 2));
+
+        editor
+            .handle_input_action(InputAction::Editor(EditorAction::UNDO))
+            .unwrap();
+        assert_eq!(
+            editor.editor_content.to_vec(),
+            vec!["  fn main() {", "    x"]
+        );
+    }
+
+    #[test]
+    fn test_tab_and_shift_tab_shift_selected_lines() {
+        let mut editor = create_editor_with_content(vec!["one", "two", "three"]);
+        for _ in 0..2 {
+            editor
+                .handle_input_action(InputAction::Editor(EditorAction::MoveSelectionCursor(
+                    Motion::Down,
+                )))
+                .unwrap();
+        }
+        editor.handle_input_action(InputAction::TAB).unwrap();
+        assert_eq!(
+            editor.editor_content.to_vec(),
+            vec!["  one", "  two", "three"]
+        );
+        assert!(editor.text_selection_start.is_some());
+
+        editor
+            .handle_input_action(InputAction::Editor(EditorAction::Outdent))
+            .unwrap();
+        assert_eq!(editor.editor_content.to_vec(), vec!["one", "two", "three"]);
+    }
+}
+
+mod editor_pairs_tests {
+    use calliglyph::config::{Config, EditorConfig};
+    use calliglyph::core::editor::Editor;
+    use calliglyph::input::actions::{EditorAction, InputAction};
+    use std::sync::Arc;
+
+    fn type_str(editor: &mut Editor, text: &str) {
+        for c in text.chars() {
+            editor
+                .handle_input_action(InputAction::Editor(EditorAction::WriteChar(c)))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_typing_closes_pairs_and_types_over_closers() {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        type_str(&mut editor, "f(\"a\")");
+        assert_eq!(editor.editor_content.to_vec(), vec!["f(\"a\")"]);
+        assert_eq!(editor.cursor.x, 6);
+
+        type_str(&mut editor, " [");
+        editor
+            .handle_input_action(InputAction::Editor(EditorAction::BACKSPACE))
+            .unwrap();
+        assert_eq!(editor.editor_content.to_vec(), vec!["f(\"a\") "]);
+    }
+
+    #[test]
+    fn test_no_pairs_when_auto_pairs_is_empty() {
+        let config = EditorConfig {
+            auto_pairs: Vec::new(),
+            ..EditorConfig::default()
+        };
+        let mut editor = Editor::new(Arc::new(config));
+        type_str(&mut editor, "(()");
+        assert_eq!(editor.editor_content.to_vec(), vec!["(()"]);
+    }
+}
+
+mod editor_line_ops_tests {
+    use calliglyph::config::Config;
+    use calliglyph::core::editor::Editor;
+    use calliglyph::input::actions::{EditorAction, InputAction, Motion};
+    use std::sync::Arc;
+
+    fn create_editor_with_content(lines: Vec<&str>) -> Editor {
+        let mut editor = Editor::new(Arc::new(Config::default().editor));
+        editor.editor_content = lines.into_iter().map(String::from).collect();
+        editor.editor_height = 10;
+        editor
+    }
+
+    fn act(editor: &mut Editor, action: EditorAction) {
+        editor
+            .handle_input_action(InputAction::Editor(action))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_line_actions_edit_and_undo() {
+        let mut editor = create_editor_with_content(vec!["one", "two", "three"]);
+        act(&mut editor, EditorAction::DuplicateLines);
+        act(&mut editor, EditorAction::MoveLinesDown);
+        act(&mut editor, EditorAction::MoveLinesDown);
+        assert_eq!(
+            editor.editor_content.to_vec(),
+            vec!["one", "two", "three", "one"]
+        );
+        assert_eq!(editor.cursor.y, 3);
+
+        act(&mut editor, EditorAction::DeleteLines);
+        act(&mut editor, EditorAction::MoveCursor(Motion::DocumentStart));
+        act(&mut editor, EditorAction::JoinLines);
+        assert_eq!(editor.editor_content.to_vec(), vec!["one two", "three"]);
+
+        for _ in 0..5 {
+            act(&mut editor, EditorAction::UNDO);
+        }
+        assert_eq!(editor.editor_content.to_vec(), vec!["one", "two", "three"]);
+    }
+}